@@ -7,12 +7,17 @@ extern crate ff_zeroize as ff;
 extern crate pairing_plus as pairing;
 extern crate pointproofs;
 extern crate rand;
+extern crate rand_core;
+extern crate rand_xorshift;
 
 use criterion::Benchmark;
 use criterion::Criterion;
+use ff::{Field, PrimeField};
+use pairing::bls12_381::{Fr, FrRepr};
 use pairing::serdes::SerDes;
 use pointproofs::pairings::*;
 use rand::Rng;
+use rand_core::SeedableRng;
 use std::time::Duration;
 
 criterion_group!(
@@ -22,6 +27,16 @@ criterion_group!(
     commit_update,
     single_commit,
     aggregate,
+    all_proofs_tuned,
+    all_proofs,
+    repr_slice,
+    verify_prepared,
+    verify_prepared_10k,
+    param_single_read,
+    param_uncompressed,
+    batch_verify_same_commit,
+    multiexp_32k,
+    proof_unchecked,
 );
 criterion_main!(basic);
 
@@ -713,3 +728,453 @@ fn aggregate(c: &mut Criterion) {
         c.bench("basic", bench);
     }
 }
+
+fn all_proofs_tuned(c: &mut Criterion) {
+    let n = 1024;
+
+    let mut values: Vec<String> = Vec::with_capacity(n);
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    // generate parameter for dimension n
+    let (pp, _vp) = param::paramgen_from_seed(
+        "This is a very very long seed for vector commitment benchmarking",
+        0,
+        n,
+    )
+    .unwrap();
+    println!("parameters generated");
+
+    // a hashed scalar takes 40 bytes in memory;
+    // budgets range from all n scalars down to blocks of 16,
+    // then around the budget from which the FFT is used
+    let pp_clone = pp.clone();
+    let values_clone = values.clone();
+    let bench_str = format!("all_proofs_n_{}_memory_{}", n, 40 * n);
+    let mut bench = Benchmark::new(bench_str, move |b| {
+        b.iter(|| Proof::new_all_tuned(&pp_clone, &values_clone, 40 * n).unwrap());
+    });
+
+    for block in [256, 64, 16].iter() {
+        let pp_clone = pp.clone();
+        let values_clone = values.clone();
+        let max_memory = 40 * *block;
+        let bench_str = format!("all_proofs_n_{}_memory_{}", n, max_memory);
+        bench = bench.with_function(bench_str, move |b| {
+            b.iter(|| Proof::new_all_tuned(&pp_clone, &values_clone, max_memory).unwrap());
+        });
+    }
+
+    // just below and at the crossover to the FFT of Proof::new_all
+    for max_memory in [Proof::new_all_memory(n) - 1, Proof::new_all_memory(n)].iter() {
+        let pp_clone = pp.clone();
+        let values_clone = values.clone();
+        let max_memory = *max_memory;
+        let bench_str = format!("all_proofs_n_{}_memory_{}", n, max_memory);
+        bench = bench.with_function(bench_str, move |b| {
+            b.iter(|| Proof::new_all_tuned(&pp_clone, &values_clone, max_memory).unwrap());
+        });
+    }
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn all_proofs(c: &mut Criterion) {
+    for n in [1024, 4096].iter() {
+        let n = *n;
+
+        let mut values: Vec<String> = Vec::with_capacity(n);
+        for i in 0..n {
+            values.push(format!("this is message number {}", i));
+        }
+
+        // generate parameter for dimension n
+        let (pp, _vp) = param::paramgen_from_seed(
+            "This is a very very long seed for vector commitment benchmarking",
+            0,
+            n,
+        )
+        .unwrap();
+        println!("parameters generated");
+
+        let pp_clone = pp.clone();
+        let values_clone = values.clone();
+        let bench_str = format!("all_proofs_n_{}_new_all", n);
+        let bench = Benchmark::new(bench_str, move |b| {
+            b.iter(|| Proof::new_all(&pp_clone, &values_clone).unwrap());
+        });
+
+        // the naive loop over Proof::new
+        let bench_str = format!("all_proofs_n_{}_naive", n);
+        let bench = bench.with_function(bench_str, move |b| {
+            b.iter(|| {
+                (0..n)
+                    .map(|i| Proof::new(&pp, &values, i).unwrap())
+                    .collect::<Vec<Proof>>()
+            });
+        });
+
+        let bench = bench.warm_up_time(Duration::from_millis(1000));
+        let bench = bench.measurement_time(Duration::from_millis(5000));
+        let bench = bench.sample_size(10);
+        c.bench("basic", bench);
+    }
+}
+
+fn repr_slice(c: &mut Criterion) {
+    let n = 32768;
+
+    // generate parameter for dimension n
+    let (pp, _vp) = param::paramgen_from_seed(
+        "This is a very very long seed for vector commitment benchmarking",
+        0,
+        n,
+    )
+    .unwrap();
+    println!("parameters generated");
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    let scalars: Vec<Fr> = (0..n).map(|_| Fr::random(&mut rng)).collect();
+    let reprs: Vec<FrRepr> = scalars.iter().map(|s| s.into_repr()).collect();
+
+    // columnar: the reprs are used as they are
+    let pp_clone = pp.clone();
+    let reprs_clone = reprs.clone();
+    let bench_str = format!("repr_slice_n_{}_commit_columnar", n);
+    let mut bench = Benchmark::new(bench_str, move |b| {
+        b.iter(|| Commitment::new_from_repr_slice(&pp_clone, &reprs_clone).unwrap());
+    });
+
+    // Vec<Fr>: the scalars are converted on each call
+    let pp_clone = pp.clone();
+    let scalars_clone = scalars.clone();
+    let bench_str = format!("repr_slice_n_{}_commit_vec_fr", n);
+    bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| {
+            let reprs: Vec<FrRepr> = scalars_clone.iter().map(|s| s.into_repr()).collect();
+            Commitment::new_from_repr_slice(&pp_clone, &reprs).unwrap()
+        });
+    });
+
+    let pp_clone = pp.clone();
+    let bench_str = format!("repr_slice_n_{}_proof_columnar", n);
+    bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| Proof::new_from_repr_slice(&pp_clone, &reprs, 0).unwrap());
+    });
+
+    let bench_str = format!("repr_slice_n_{}_proof_vec_fr", n);
+    bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| {
+            let reprs: Vec<FrRepr> = scalars.iter().map(|s| s.into_repr()).collect();
+            Proof::new_from_repr_slice(&pp, &reprs, 0).unwrap()
+        });
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn verify_prepared(c: &mut Criterion) {
+    let n = 1024;
+
+    let mut values: Vec<String> = Vec::with_capacity(n);
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    // generate parameter for dimension n
+    let (pp, vp) = param::paramgen_from_seed(
+        "This is a very very long seed for vector commitment benchmarking",
+        0,
+        n,
+    )
+    .unwrap();
+    println!("parameters generated");
+    let mut vp_prepared = vp.clone();
+    vp_prepared.precomp();
+
+    let com = Commitment::new(&pp, &values).unwrap();
+    let proof = Proof::new(&pp, &values, 0).unwrap();
+
+    // single verification without the prepared generators
+    let com_clone = com.clone();
+    let proof_clone = proof.clone();
+    let value = values[0].clone();
+    let bench_str = format!("verify_n_{}", n);
+    let mut bench = Benchmark::new(bench_str, move |b| {
+        b.iter(|| assert!(proof_clone.verify(&vp, &com_clone, &value, 0)));
+    });
+
+    // single verification with the prepared generators
+    let value = values[0].clone();
+    let bench_str = format!("verify_n_{}_prepared", n);
+    bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| assert!(proof.verify(&vp_prepared, &com, &value, 0)));
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn verify_prepared_10k(c: &mut Criterion) {
+    let n = 1024;
+    let num_verify = 10_000;
+    let num_proofs = 16;
+
+    let mut values: Vec<String> = Vec::with_capacity(n);
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    // generate parameter for dimension n
+    let (pp, vp) = param::paramgen_from_seed(
+        "This is a very very long seed for vector commitment benchmarking",
+        0,
+        n,
+    )
+    .unwrap();
+    println!("parameters generated");
+    let mut vp_prepared = vp.clone();
+    vp_prepared.precomp();
+
+    let com = Commitment::new(&pp, &values).unwrap();
+    let proofs: Vec<Proof> = (0..num_proofs)
+        .map(|i| Proof::new(&pp, &values, i).unwrap())
+        .collect();
+
+    // many verifications with the same parameters, without the prepared generators
+    let com_clone = com.clone();
+    let proofs_clone = proofs.clone();
+    let values_clone = values.clone();
+    let bench_str = format!("verify_n_{}_{}_times", n, num_verify);
+    let mut bench = Benchmark::new(bench_str, move |b| {
+        b.iter(|| {
+            for k in 0..num_verify {
+                let i = k % num_proofs;
+                assert!(proofs_clone[i].verify(&vp, &com_clone, &values_clone[i], i));
+            }
+        });
+    });
+
+    // many verifications with the same parameters, with the prepared generators
+    let bench_str = format!("verify_n_{}_{}_times_prepared", n, num_verify);
+    bench = bench.with_function(bench_str, move |b| {
+        b.iter(|| {
+            for k in 0..num_verify {
+                let i = k % num_proofs;
+                assert!(proofs[i].verify(&vp_prepared, &com, &values[i], i));
+            }
+        });
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn param_single_read(c: &mut Criterion) {
+    // std::fs::File is not buffered, so every point of the per-point path
+    // is a read system call
+    let bench = Benchmark::new("param_deserialize_per_point_read", move |b| {
+        b.iter(|| {
+            let mut f = std::fs::File::open("crs.param").unwrap();
+            pointproofs::pairings::param::PointproofsParams::deserialize(&mut f, true).unwrap()
+        });
+    });
+
+    let bench = bench.with_function("param_deserialize_single_read", move |b| {
+        b.iter(|| {
+            let mut f = std::fs::File::open("crs.param").unwrap();
+            pointproofs::pairings::param::PointproofsParams::deserialize_single_read(&mut f)
+                .unwrap()
+        });
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn param_uncompressed(c: &mut Criterion) {
+    // same params in both encodings, loaded from memory so that
+    // only the point decoding is measured
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let param = pointproofs::pairings::param::PointproofsParams::deserialize(&mut f, true).unwrap();
+    let mut compressed: Vec<u8> = vec![];
+    param.serialize(&mut compressed, true).unwrap();
+    let mut uncompressed: Vec<u8> = vec![];
+    param.serialize(&mut uncompressed, false).unwrap();
+
+    let bench = Benchmark::new("param_deserialize_compressed", move |b| {
+        b.iter(|| {
+            pointproofs::pairings::param::PointproofsParams::deserialize_from_slice(&compressed)
+                .unwrap()
+        });
+    });
+
+    let bench = bench.with_function("param_deserialize_uncompressed", move |b| {
+        b.iter(|| {
+            pointproofs::pairings::param::PointproofsParams::deserialize_from_slice(&uncompressed)
+                .unwrap()
+        });
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn batch_verify_same_commit(c: &mut Criterion) {
+    let n = 1024;
+    let batch = 64;
+
+    let mut values: Vec<String> = Vec::with_capacity(n);
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    // generate parameter for dimension n
+    let (pp, vp) = param::paramgen_from_seed(
+        "This is a very very long seed for vector commitment benchmarking",
+        0,
+        n,
+    )
+    .unwrap();
+    println!("parameters generated");
+
+    let com = Commitment::new(&pp, &values).unwrap();
+    let proofs: Vec<Proof> = (0..batch)
+        .map(|i| Proof::new(&pp, &values, i).unwrap())
+        .collect();
+
+    // one verification per opening
+    let vp_clone = vp.clone();
+    let com_clone = com.clone();
+    let proofs_clone = proofs.clone();
+    let values_clone = values.clone();
+    let bench_str = format!("verify_{}_openings_n_{}", batch, n);
+    let mut bench = Benchmark::new(bench_str, move |b| {
+        b.iter(|| {
+            for i in 0..batch {
+                assert!(proofs_clone[i].verify(&vp_clone, &com_clone, &values_clone[i], i));
+            }
+        });
+    });
+
+    // a single batched verification
+    let bench_str = format!("batch_verify_same_commit_{}_openings_n_{}", batch, n);
+    bench = bench.with_function(bench_str, move |b| {
+        let openings: Vec<(usize, &[u8], &Proof)> = (0..batch)
+            .map(|i| (i, values[i].as_bytes(), &proofs[i]))
+            .collect();
+        b.iter(|| assert!(Proof::batch_verify_same_commit(&vp, &com, &openings)));
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn multiexp_32k(c: &mut Criterion) {
+    use pairing::{CurveAffine, CurveProjective};
+    use pointproofs::pairings::pointproofs_groups::{PointproofsG1, PointproofsG1Affine};
+
+    let n = 32768;
+    let mut points: Vec<PointproofsG1Affine> = Vec::with_capacity(n);
+    let mut point = PointproofsG1::one();
+    for _ in 0..n {
+        points.push(point.into_affine());
+        point.add_assign(&PointproofsG1::one());
+    }
+    let scalars: Vec<FrRepr> = (0..n)
+        .map(|i| hash_to_field_pointproofs(format!("this is message number {}", i)).into_repr())
+        .collect();
+
+    // the direct call, on the calling thread
+    let points_clone = points.clone();
+    let scalars_clone = scalars.clone();
+    let bench_str = format!("sum_of_products_n_{}", n);
+    let mut bench = Benchmark::new(bench_str, move |b| {
+        let scalars_u64: Vec<&[u64; 4]> = scalars_clone.iter().map(|s| &s.0).collect();
+        b.iter(|| PointproofsG1Affine::sum_of_products(&points_clone, &scalars_u64));
+    });
+
+    // the wrapper, split across the rayon thread pool with the parallel feature
+    let bench_str = format!("multiexp_g1_n_{}", n);
+    bench = bench.with_function(bench_str, move |b| {
+        let scalars_u64: Vec<&[u64; 4]> = scalars.iter().map(|s| &s.0).collect();
+        b.iter(|| multiexp_g1(&points, &scalars_u64));
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    let bench = bench.sample_size(10);
+    c.bench("basic", bench);
+}
+
+fn proof_unchecked(c: &mut Criterion) {
+    let n = 1024;
+
+    let mut values: Vec<String> = Vec::with_capacity(n);
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    // generate parameter for dimension n
+    let (pp, mut vp) = param::paramgen_from_seed(
+        "This is a very very long seed for vector commitment benchmarking",
+        0,
+        n,
+    )
+    .unwrap();
+    vp.precomp();
+    println!("parameters generated");
+
+    let com = Commitment::new(&pp, &values).unwrap();
+    let proof = Proof::new(&pp, &values, 0).unwrap();
+    let bytes = proof.to_bytes();
+
+    // the difference of the first two is the cost of the subgroup check
+    let bytes_clone = bytes.clone();
+    let bench = Benchmark::new("proof_deserialize", move |b| {
+        b.iter(|| Proof::deserialize(&mut bytes_clone[..].as_ref(), true).unwrap());
+    });
+    let bytes_clone = bytes.clone();
+    let bench = bench.with_function("proof_deserialize_unchecked", move |b| {
+        b.iter(|| Proof::deserialize_unchecked(&mut bytes_clone[..].as_ref()).unwrap());
+    });
+
+    // and its share of a whole verification
+    let vp_clone = vp.clone();
+    let com_clone = com.clone();
+    let values_clone = values.clone();
+    let bytes_clone = bytes.clone();
+    let bench = bench.with_function("proof_deserialize_and_verify", move |b| {
+        b.iter(|| {
+            let proof = Proof::deserialize(&mut bytes_clone[..].as_ref(), true).unwrap();
+            assert!(proof.verify(&vp_clone, &com_clone, &values_clone[0], 0))
+        });
+    });
+    let bench = bench.with_function("proof_verify_unchecked", move |b| {
+        b.iter(|| assert!(Proof::verify_unchecked(&vp, &com, &bytes, &values[0], 0)));
+    });
+
+    let bench = bench.warm_up_time(Duration::from_millis(1000));
+    let bench = bench.measurement_time(Duration::from_millis(5000));
+    c.bench("basic", bench);
+}
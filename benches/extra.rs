@@ -18,30 +18,20 @@ criterion_group!(benches, bench_pre);
 criterion_main!(benches);
 
 fn bench_commit_helper(prover_params: &ProverParams, n: usize, b: &mut Bencher) {
-    let mut init_values = Vec::with_capacity(n);
+    let mut values = Vec::with_capacity(n);
     for i in 0..n {
         let s = format!("this is message number {}", i);
-        init_values.push(s.into_bytes());
-    }
-
-    let mut values: Vec<&[u8]> = Vec::with_capacity(n);
-    for e in init_values.iter().take(n) {
-        values.push(&e);
+        values.push(s.into_bytes());
     }
 
     b.iter(|| Commitment::new(prover_params, &values));
 }
 
 fn bench_prove_helper(prover_params: &ProverParams, n: usize, b: &mut Bencher) {
-    let mut init_values = Vec::with_capacity(n);
+    let mut values = Vec::with_capacity(n);
     for i in 0..n {
         let s = format!("this is message number {}", i);
-        init_values.push(s.into_bytes());
-    }
-
-    let mut values: Vec<&[u8]> = Vec::with_capacity(n);
-    for e in init_values.iter().take(n) {
-        values.push(&e);
+        values.push(s.into_bytes());
     }
 
     let mut i: usize = 0;
@@ -57,21 +47,15 @@ fn bench_prove_helper(prover_params: &ProverParams, n: usize, b: &mut Bencher) {
 }
 
 fn bench_commit_des_and_update_helper(prover_params: &ProverParams, n: usize, b: &mut Bencher) {
-    let mut init_old_values = Vec::with_capacity(n);
-    let mut init_new_values = Vec::with_capacity(n);
+    let mut old_values = Vec::with_capacity(n);
+    let mut new_values = Vec::with_capacity(n);
     for i in 0..n {
         let s = format!("this is old message number {}", i);
-        init_old_values.push(s.into_bytes());
+        old_values.push(s.into_bytes());
         let t = format!("this is new message number {}", i);
-        init_new_values.push(t.into_bytes());
+        new_values.push(t.into_bytes());
     }
 
-    let mut old_values: Vec<&[u8]> = Vec::with_capacity(n);
-    let mut new_values: Vec<&[u8]> = Vec::with_capacity(n);
-    for i in 0..n {
-        old_values.push(&init_old_values[i]);
-        new_values.push(&init_new_values[i]);
-    }
     let mut buf: Vec<u8> = vec![];
     let com = Commitment::new(&prover_params, &old_values).unwrap();
     assert!(com.serialize(&mut buf, true).is_ok());
@@ -85,20 +69,13 @@ fn bench_commit_des_and_update_helper(prover_params: &ProverParams, n: usize, b:
 }
 
 fn bench_commit_update_helper(prover_params: &ProverParams, n: usize, b: &mut Bencher) {
-    let mut init_old_values = Vec::with_capacity(n);
-    let mut init_new_values = Vec::with_capacity(n);
+    let mut old_values = Vec::with_capacity(n);
+    let mut new_values = Vec::with_capacity(n);
     for i in 0..n {
         let s = format!("this is old message number {}", i);
-        init_old_values.push(s.into_bytes());
+        old_values.push(s.into_bytes());
         let t = format!("this is new message number {}", i);
-        init_new_values.push(t.into_bytes());
-    }
-
-    let mut old_values: Vec<&[u8]> = Vec::with_capacity(n);
-    let mut new_values: Vec<&[u8]> = Vec::with_capacity(n);
-    for i in 0..n {
-        old_values.push(&init_old_values[i]);
-        new_values.push(&init_new_values[i]);
+        new_values.push(t.into_bytes());
     }
 
     let com = Commitment::new(&prover_params, &old_values).unwrap();
@@ -115,15 +92,10 @@ fn bench_proof_des_and_update_helper(prover_params: &ProverParams, n: usize, b:
     // Does not include to/from bytes conversion, because this is supposed to be a local operation
     let update_index = n / 2; // We will update message number n/2 and then benchmark changing proofs for others
 
-    let mut init_old_values = Vec::with_capacity(n);
+    let mut old_values = Vec::with_capacity(n);
     for i in 0..n {
         let s = format!("this is old message number {}", i);
-        init_old_values.push(s.into_bytes());
-    }
-
-    let mut old_values: Vec<&[u8]> = Vec::with_capacity(n);
-    for e in init_old_values.iter().take(n) {
-        old_values.push(&e);
+        old_values.push(s.into_bytes());
     }
 
     let mut proofs = Vec::with_capacity(n);
@@ -162,15 +134,10 @@ fn bench_proof_update_helper(prover_params: &ProverParams, n: usize, b: &mut Ben
     // Does not include to/from bytes conversion, because this is supposed to be a local operation
     let update_index = n / 2; // We will update message number n/2 and then benchmark changing proofs for others
 
-    let mut init_old_values = Vec::with_capacity(n);
+    let mut old_values = Vec::with_capacity(n);
     for i in 0..n {
         let s = format!("this is old message number {}", i);
-        init_old_values.push(s.into_bytes());
-    }
-
-    let mut old_values: Vec<&[u8]> = Vec::with_capacity(n);
-    for e in init_old_values.iter().take(n) {
-        old_values.push(&e);
+        old_values.push(s.into_bytes());
     }
 
     let mut proofs = Vec::with_capacity(n);
@@ -242,15 +209,10 @@ fn bench_pre(c: &mut Criterion) {
         // verification
         let prover_params_clone = prover_params.clone();
         let bench = bench.with_function(format!("N_{}_verify", *n), move |b| {
-            let mut init_values = Vec::with_capacity(*n);
+            let mut values = Vec::with_capacity(*n);
             for i in 0..*n {
                 let s = format!("this is message number {}", i);
-                init_values.push(s.into_bytes());
-            }
-
-            let mut values: Vec<&[u8]> = Vec::with_capacity(*n);
-            for e in init_values.iter().take(*n) {
-                values.push(&e);
+                values.push(s.into_bytes());
             }
 
             let com = Commitment::new(&prover_params_clone, &values).unwrap();
@@ -4,10 +4,34 @@ use pairing_plus::{bls12_381::{Fq12, G1Affine, G2Affine, self, Fr, FrRepr}, serd
 use rand::rngs::OsRng;
 use rand::RngCore;
 use super::hash_to_field_pointproofs::*;
+use pairings::misc::short_digest;
+use pairings::param::{check_ciphersuite, MAX_N};
+use pairings::serdes::read_group_point;
+use pairings::pointproofs_groups::multiexp;
 use zeroize::Zeroize;
 
-#[derive(Debug, PartialEq)]
+const G1_COMPRESSED_LEN: usize = 48;
+const G2_COMPRESSED_LEN: usize = 96;
+const GT_COMPRESSED_LEN: usize = 576;
+const G1_UNCOMPRESSED_LEN: usize = 96;
+const G2_UNCOMPRESSED_LEN: usize = 192;
+
+/// Set in the u32 holding n when the points are serialized uncompressed.
+/// n is at most MAX_N, so the bit is free, and readers that predate it
+/// reject the blob as n > MAX_N.
+pub const UNCOMPRESSED_FLAG: u32 = 1 << 31;
+
+/// Set in the u32 holding n when a ciphersuite id follows n.
+/// It is only set for a non-zero ciphersuite, so params for ciphersuite 0
+/// keep the format without an id, which readers take as ciphersuite 0.
+pub const CIPHERSUITE_FLAG: u32 = 1 << 30;
+
+/// Debug prints n, a digest of the serialization and the ciphersuite; see `debug_full`.
+#[derive(PartialEq)]
 pub struct PointproofsParams {
+    /// ciphersuite id
+    pub ciphersuite: u8,
+
     /// parameter N
     pub n: usize,
 
@@ -27,56 +51,208 @@ pub struct PointproofsParams {
     pub gt_alpha_nplus1: Fq12,
 }
 
+impl std::fmt::Debug for PointproofsParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut buf: Vec<u8> = vec![];
+        let digest = match self.serialize(&mut buf, true) {
+            Ok(()) => short_digest(&buf),
+            Err(_) => "invalid".to_string(),
+        };
+        f.debug_struct("PointproofsParams")
+            .field("n", &self.n)
+            .field("digest", &digest)
+            .field("ciphersuite", &self.ciphersuite)
+            .finish()
+    }
+}
+
+impl PointproofsParams {
+    /// The Debug output with every point;
+    /// this is large, and `{:?}` prints a digest instead.
+    pub fn debug_full(&self) -> String {
+        format!(
+            "PointproofsParams {{ ciphersuite: {}, n: {}, g1_alpha_1_to_n: {:?}, g1_alpha_nplus2_to_2n: {:?}, g2_alpha_1_to_n: {:?}, g2_alpha_nplus2_to_2n: {:?}, gt_alpha_nplus1: {:?} }}",
+            self.ciphersuite,
+            self.n,
+            self.g1_alpha_1_to_n,
+            self.g1_alpha_nplus2_to_2n,
+            self.g2_alpha_1_to_n,
+            self.g2_alpha_nplus2_to_2n,
+            self.gt_alpha_nplus1
+        )
+    }
+}
+
 impl SerDes for PointproofsParams {
+    /// `| n | [ciphersuite id] | g1 points | g2 points | gt |`, with n as u32 in little endian.
+    /// With compressed = false the points are written uncompressed,
+    /// which doubles their size but saves a square root per point on load,
+    /// and `UNCOMPRESSED_FLAG` is set in n. gt has a single encoding.
+    /// A non-zero ciphersuite id is written after n, and `CIPHERSUITE_FLAG` is set in n;
+    /// ciphersuite 0 is written without an id.
     fn serialize<W: Write>(&self, w: &mut W, compressed: bool) -> Result<()> {
-        if !compressed {
-            return Err(Error::new(
-                ErrorKind::Other,
-                "Pointproofs params can only be (de)serialized with compressed=true",
+        if self.n > MAX_N || self.n == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Invalid n",
             ));
         }
-        if self.n > (u32::max_value() as usize) {
+        if !check_ciphersuite(self.ciphersuite) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                "Invalid n",
+                "Invalid ciphersuite id",
             ));
         }
-        w.write_all(&(self.n as u32).to_le_bytes())?;
+        let mut n = self.n as u32;
+        if !compressed {
+            n |= UNCOMPRESSED_FLAG;
+        }
+        if self.ciphersuite != 0 {
+            n |= CIPHERSUITE_FLAG;
+        }
+        w.write_all(&n.to_le_bytes())?;
+        if self.ciphersuite != 0 {
+            w.write_all(&[self.ciphersuite])?;
+        }
         for pt in &self.g1_alpha_1_to_n {
-            pt.serialize(w, true)?;
+            pt.serialize(w, compressed)?;
         }
         for pt in &self.g1_alpha_nplus2_to_2n {
-            pt.serialize(w, true)?;
+            pt.serialize(w, compressed)?;
         }
         for pt in &self.g2_alpha_1_to_n {
-            pt.serialize(w, true)?;
+            pt.serialize(w, compressed)?;
         }
         for pt in &self.g2_alpha_nplus2_to_2n {
-            pt.serialize(w, true)?;
+            pt.serialize(w, compressed)?;
         }
         self.gt_alpha_nplus1.serialize(w, true)?;
         Ok(())
     }
-    
-    fn deserialize<R: Read>(r: &mut R, compressed: bool) -> Result<Self> {
-        if !compressed {
-            return Err(Error::new(
-                ErrorKind::Other,
-                "Pointproofs params can only be (de)serialized with compressed=true",
-            ));
+
+    /// The encoding of the points is read from the header,
+    /// so either value of compressed reads both encodings.
+    fn deserialize<R: Read>(r: &mut R, _compressed: bool) -> Result<Self> {
+        let header = Self::peek_header(r)?;
+        Self::deserialize_after_header(&header, r)
+    }
+}
+
+/// The header of serialized `PointproofsParams`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParamsHeader {
+    /// parameter N
+    pub n: usize,
+
+    /// the ciphersuite id, if the serialization carries one;
+    /// params for ciphersuite 0 are written without an id, so this is None
+    pub ciphersuite: Option<u8>,
+
+    /// whether the points are compressed
+    pub compressed: bool,
+}
+
+impl ParamsHeader {
+    /// The length of the header: n, then the ciphersuite id if any.
+    pub fn header_len(&self) -> usize {
+        match self.ciphersuite {
+            Some(_) => 5,
+            None => 4,
+        }
+    }
+
+    /// The length of the serialized params after the header;
+    /// 0 if n is 0, as no params of size 0 are ever serialized.
+    pub fn body_len(&self) -> usize {
+        let point_len = if self.compressed {
+            G1_COMPRESSED_LEN + G2_COMPRESSED_LEN
+        } else {
+            G1_UNCOMPRESSED_LEN + G2_UNCOMPRESSED_LEN
+        };
+        // n + (n - 1) points in each of G1 and G2, then gt
+        match (2 * self.n).checked_sub(1) {
+            Some(points) => points * point_len + GT_COMPRESSED_LEN,
+            None => 0,
+        }
+    }
+}
+
+impl PointproofsParams {
+    /// The number of bytes `serialize` writes for params of size n
+    /// and the ciphersuite: the header, i.e., the 4 bytes of n and the
+    /// ciphersuite id unless it is 0, then the points in the requested
+    /// encoding and gt.
+    /// Returns None if n is 0 or larger than MAX_N, or if the ciphersuite
+    /// is not valid, which `serialize` rejects.
+    pub fn serialized_len(n: usize, ciphersuite: u8, compressed: bool) -> Option<usize> {
+        if n > MAX_N || n == 0 || !check_ciphersuite(ciphersuite) {
+            return None;
         }
+        let header = ParamsHeader {
+            n,
+            ciphersuite: if ciphersuite == 0 {
+                None
+            } else {
+                Some(ciphersuite)
+            },
+            compressed,
+        };
+        Some(header.header_len() + header.body_len())
+    }
 
+    /// Reads the header of serialized params, i.e., the leading n,
+    /// the encoding of the points and the ciphersuite id,
+    /// and leaves the reader right after it;
+    /// a full parse can then continue with `Self::deserialize_after_header`.
+    /// Returns an error if n is 0 or larger than MAX_N,
+    /// or if the ciphersuite id is not valid.
+    pub fn peek_header<R: Read>(r: &mut R) -> Result<ParamsHeader> {
         // read parameter n
         let mut buf = [0u8; 4];
         r.read_exact(&mut buf)?;
-        let n = u32::from_le_bytes(buf) as usize;
+        let raw = u32::from_le_bytes(buf);
+        let compressed = raw & UNCOMPRESSED_FLAG == 0;
+        let n = (raw & !UNCOMPRESSED_FLAG & !CIPHERSUITE_FLAG) as usize;
+
+        if n > MAX_N || n == 0 {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "The size of n has passed the maximal allowed value.",
+            ));
+        }
 
-        if n > 65535 || n == 0 {
+        // read the ciphersuite id, if any; 0 is never written
+        let ciphersuite = if raw & CIPHERSUITE_FLAG != 0 {
+            let mut csid = [0u8; 1];
+            r.read_exact(&mut csid)?;
+            if csid[0] == 0 || !check_ciphersuite(csid[0]) {
+                return Err(Error::new(ErrorKind::InvalidData, "Invalid ciphersuite id"));
+            }
+            Some(csid[0])
+        } else {
+            None
+        };
+        Ok(ParamsHeader {
+            n,
+            ciphersuite,
+            compressed,
+        })
+    }
+
+    /// Deserializes the rest of the params, after `Self::peek_header`
+    /// has read the header from the same reader.
+    pub fn deserialize_after_header<R: Read>(header: &ParamsHeader, r: &mut R) -> Result<Self> {
+        let n = header.n;
+        if n > MAX_N || n == 0 {
             return Err(Error::new(
                 ErrorKind::Other,
                 "The size of n has passed the maximal allowed value.",
             ));
         }
+        let ciphersuite = header.ciphersuite.unwrap_or(0);
+        if !check_ciphersuite(ciphersuite) {
+            return Err(Error::new(ErrorKind::InvalidData, "Invalid ciphersuite id"));
+        }
 
         let mut g1_alpha_1_to_n: Vec<G1Affine> = vec![];
         let mut g1_alpha_nplus2_to_2n: Vec<G1Affine> = vec![];
@@ -84,26 +260,28 @@ impl SerDes for PointproofsParams {
         let mut g2_alpha_nplus2_to_2n: Vec<G2Affine> = vec![];
         let gt_alpha_nplus1: Fq12;
 
+        let compressed = header.compressed;
         for _ in 0..n {
-            let tmp = G1Affine::deserialize(r, true)?;
+            let tmp: G1Affine = read_group_point(r, compressed)?;
             g1_alpha_1_to_n.push(tmp);
         }
         for _ in 0..n - 1 {
-            let tmp = G1Affine::deserialize(r, true)?;
+            let tmp: G1Affine = read_group_point(r, compressed)?;
             g1_alpha_nplus2_to_2n.push(tmp);
         }
         for _ in 0..n {
-            let tmp = G2Affine::deserialize(r, true)?;
+            let tmp: G2Affine = read_group_point(r, compressed)?;
             g2_alpha_1_to_n.push(tmp);
         }
         for _ in 0..n - 1 {
-            let tmp = G2Affine::deserialize(r, true)?;
+            let tmp: G2Affine = read_group_point(r, compressed)?;
             g2_alpha_nplus2_to_2n.push(tmp);
         }
 
         gt_alpha_nplus1 = Fq12::deserialize(r, true)?;
 
         Ok(PointproofsParams {
+            ciphersuite,
             n,
             g1_alpha_1_to_n,
             g1_alpha_nplus2_to_2n,
@@ -114,61 +292,200 @@ impl SerDes for PointproofsParams {
     }
 }
 
-fn random_scalar() -> Fr {
+impl PointproofsParams {
+    /// Deserializes the params from a byte slice, with the format of `deserialize`.
+    /// n is read first and the length of the slice is checked against it,
+    /// so a short blob claiming a large n fails before any point is read.
+    /// Returns an error if the slice is too short or too long.
+    pub fn deserialize_from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 4 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "The input is too short"));
+        }
+        let header = Self::peek_header(&mut &bytes[..])?;
+        if bytes.len() < header.header_len() + header.body_len() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "The input is too short for the claimed n",
+            ));
+        }
+
+        let mut reader = bytes;
+        let param = Self::deserialize(&mut reader, true)?;
+        if !reader.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Trailing bytes after the serialized params",
+            ));
+        }
+        Ok(param)
+    }
+
+    /// Deserializes the params, with the format of `deserialize`,
+    /// reading all the points in a single pass instead of one read per point.
+    /// This is much faster on an unbuffered reader; the points are then
+    /// parsed from memory. The buffer grows with the data actually read,
+    /// so a short input claiming a large n does not allocate the full size.
+    pub fn deserialize_single_read<R: Read>(r: &mut R) -> Result<Self> {
+        let header = Self::peek_header(r)?;
+        let len = header.body_len();
+        let mut bytes = vec![];
+        r.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "The input is too short for the claimed n",
+            ));
+        }
+        Self::deserialize_after_header(&header, &mut bytes[..].as_ref())
+    }
+
+    /// Deserializes the params, with the format of `deserialize`, from several
+    /// readers taken in order as if they were concatenated, e.g., one per section:
+    /// the header and g1_alpha_1_to_n, then g1_alpha_nplus2_to_2n, g2_alpha_1_to_n,
+    /// g2_alpha_nplus2_to_2n and gt_alpha_nplus1. The split points are free,
+    /// so a part may hold several sections, or a piece of one.
+    /// Each part must be read to its end, and the params must pass `consistent`.
+    /// Returns an error if the parts are too short, too long, or inconsistent.
+    pub fn deserialize_parts<R: Read>(parts: &mut [R]) -> Result<Self> {
+        let mut reader = ConcatReader { parts, current: 0 };
+        let param = Self::deserialize(&mut reader, true)?;
+        let mut extra = [0u8; 1];
+        if reader.read(&mut extra)? != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Trailing bytes after the serialized params",
+            ));
+        }
+        if let Err(e) = consistent_detailed(&param) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Inconsistent params: {:?}", e),
+            ));
+        }
+        Ok(param)
+    }
+}
+
+/// Reads a list of readers one after the other.
+struct ConcatReader<'a, R: 'a> {
+    parts: &'a mut [R],
+    current: usize,
+}
+
+impl<'a, R: Read> Read for ConcatReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        while self.current < self.parts.len() {
+            let len = self.parts[self.current].read(buf)?;
+            if len > 0 || buf.is_empty() {
+                return Ok(len);
+            }
+            // this part is over
+            self.current += 1;
+        }
+        Ok(0)
+    }
+}
+
+/// The powers of alpha in the conventional KZG ordering.
+#[derive(Debug, PartialEq)]
+pub struct KzgSrs {
+    /// g1^{alpha^0}, g1^{alpha^1}, ..., g1^{alpha^D}
+    pub g1_powers: Vec<G1Affine>,
+
+    /// g2^{alpha^0}, g2^{alpha^1}, ..., g2^{alpha^D}
+    pub g2_powers: Vec<G2Affine>,
+}
+
+impl PointproofsParams {
+    /// Exports the powers 0..=N of both groups as a KZG SRS of degree N.
+    pub fn to_kzg_srs(&self) -> KzgSrs {
+        KzgSrs {
+            g1_powers: [vec![G1Affine::one()], self.g1_alpha_1_to_n.clone()].concat(),
+            g2_powers: [vec![G2Affine::one()], self.g2_alpha_1_to_n.clone()].concat(),
+        }
+    }
+
+    /// Exports the powers 0..=degree of both groups as a KZG SRS.
+    /// A KZG SRS needs contiguous powers, and g^{alpha^{N+1}} is never
+    /// published (the security of pointproofs relies on it),
+    /// so the hole cannot be filled and degree > N is an error.
+    pub fn to_kzg_srs_with_degree(&self, degree: usize) -> Result<KzgSrs> {
+        if degree > self.n {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "KZG degree is too large: g^{alpha^{N+1}} is not part of the params",
+            ));
+        }
+        let mut srs = self.to_kzg_srs();
+        srs.g1_powers.truncate(degree + 1);
+        srs.g2_powers.truncate(degree + 1);
+        Ok(srs)
+    }
+}
+
+fn random_scalar_with_rng<R: RngCore>(rng: &mut R) -> Fr {
     let mut r: [u8; 64] = [0; 64];
-    // @dev: 下面这两行代码取代了原始代码中的 OsRng {}.fill_bytes(&mut r[..]);
-    let mut rng = OsRng::new().expect("Failed to create OsRng instance");
     rng.fill_bytes(&mut r[..]);
-    // OsRng {}.fill_bytes(&mut r[..]); // 原始代码, 来自 rand_core::OsRng
     // For convenience, just using already-implemented hash-to-field
     let res = hash_to_field_pointproofs(&r[..]);
     r.zeroize();
     res
 }
 
-// Checks that a set of parameters are in the correct form (g2^alpha, g2^alpha^2, etc.) for some alpha
-pub fn consistent(params: &PointproofsParams) -> bool {
-    // First, check all points are in the group, nonzero, and not the generator
-    // (Subgroup check is already done in our deserialization code)
-    if params
-        .g2_alpha_1_to_n
-        .iter()
-        .any(|&x| x == G2Affine::zero() || x == G2Affine::one())
-    {
-        return false;
-    }
+/// The stage of `consistent_detailed` that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsistencyFailure {
+    /// g2_alpha_1_to_n\[i\] is the identity or the generator
+    G2Alpha1ToN(usize),
+    /// g2_alpha_nplus2_to_2n\[i\] is the identity or the generator
+    G2AlphaNplus2To2N(usize),
+    /// g1_alpha_1_to_n\[i\] is the identity or the generator
+    G1Alpha1ToN(usize),
+    /// g1_alpha_nplus2_to_2n\[i\] is the identity or the generator
+    G1AlphaNplus2To2N(usize),
+    /// e(R_1, g_2) != e(g_1, R_2): the g1 and g2 powers differ
+    G1G2Powers,
+    /// e(S, g_2^alpha) != e(T, g_2): the g1 powers are not successive powers of alpha
+    AlphaShift,
+    /// e(g_1^{alpha^N}, g_2^alpha) != gt_alpha_nplus1
+    GtAlphaNplus1,
+    /// e(T, g_2^{alpha^N}) != e(U_1, g_2) or e(g_1, U_2): the powers N+2..2N are wrong
+    AlphaNplus2To2N,
+    /// OsRng is not available
+    RngUnavailable,
+    /// n is 0 or larger than MAX_N, or a list does not hold n (resp. n - 1) points
+    InvalidLength,
+}
 
-    if params
-        .g2_alpha_nplus2_to_2n
-        .iter()
-        .any(|&x| x == G2Affine::zero() || x == G2Affine::one())
-    {
-        return false;
-    }
+// Checks that a set of parameters are in the correct form (g2^alpha, g2^alpha^2, etc.) for some alpha.
+// The random scalars are drawn from OsRng; returns false if OsRng is not available.
+pub fn consistent(params: &PointproofsParams) -> bool {
+    consistent_detailed(params).is_ok()
+}
 
-    if params
-        .g1_alpha_1_to_n
-        .iter()
-        .any(|&x| x == G1Affine::zero() || x == G1Affine::one())
-    {
-        return false;
-    }
+// Same as `consistent`, but the random scalars are drawn from the caller's rng.
+pub fn consistent_with_rng<R: RngCore>(params: &PointproofsParams, rng: &mut R) -> bool {
+    consistent_detailed_with_rng(params, rng).is_ok()
+}
 
-    if params
-        .g1_alpha_nplus2_to_2n
-        .iter()
-        .any(|&x| x == G1Affine::zero() || x == G1Affine::one())
-    {
-        return false;
+// Same as `consistent`, but reports which check failed.
+pub fn consistent_detailed(
+    params: &PointproofsParams,
+) -> std::result::Result<(), ConsistencyFailure> {
+    match OsRng::new() {
+        Ok(mut rng) => consistent_detailed_with_rng(params, &mut rng),
+        Err(_) => Err(ConsistencyFailure::RngUnavailable),
     }
+}
 
-    // Generate N random scalars r_1, ..., r_N
-    let mut rs_owned: Vec<FrRepr> = vec![];
+// Same as `consistent_detailed`, but the random scalars are drawn from the caller's rng.
+pub fn consistent_detailed_with_rng<R: RngCore>(
+    params: &PointproofsParams,
+    rng: &mut R,
+) -> std::result::Result<(), ConsistencyFailure> {
+    check_points(params)?;
+    let rs_owned = consistency_scalars(params, rng);
     let mut rs: Vec<&[u64; 4]> = vec![];
-    for _ in 0..params.n {
-        let r = random_scalar().into_repr();
-        rs_owned.push(r);
-    }
     for item in rs_owned.iter() {
         rs.push(&item.0);
     }
@@ -181,7 +498,10 @@ pub fn consistent(params: &PointproofsParams) -> bool {
     // U_1 = prod{i=1}^{N-1} ("g_1^{alpha^{i+N+1}")^{r_i}
     // U_2 = prod{i=1}^{N-1} ("g_2^{alpha^{i+N+1}")^{r_i}
 
-    let pt_s: bls12_381::G1Affine = G1Affine::sum_of_products(
+    // with n = 1 the lists of S, T, U_1 and U_2 are empty, so the four points
+    // are the identity and checks 2 and 4 hold trivially: there is no power
+    // of alpha above alpha^N to check
+    let pt_s: bls12_381::G1Affine = multiexp(
         &params.g1_alpha_1_to_n[0..params.n - 1],
         &rs[0..params.n - 1],
     )
@@ -192,17 +512,14 @@ pub fn consistent(params: &PointproofsParams) -> bool {
         tmp.add_assign_mixed(&pt_s);
         tmp.into_affine()
     };
-    let pt_r2 = G2Affine::sum_of_products(&params.g2_alpha_1_to_n[0..params.n], &rs[0..params.n])
-        .into_affine();
-    let pt_t =
-        G1Affine::sum_of_products(&params.g1_alpha_1_to_n[1..params.n], &rs[0..params.n - 1])
-            .into_affine();
-    let pt_u1 = G1Affine::sum_of_products(
+    let pt_r2 = multiexp(&params.g2_alpha_1_to_n[0..params.n], &rs[0..params.n]).into_affine();
+    let pt_t = multiexp(&params.g1_alpha_1_to_n[1..params.n], &rs[0..params.n - 1]).into_affine();
+    let pt_u1 = multiexp(
         &params.g1_alpha_nplus2_to_2n[0..params.n - 1],
         &rs[0..params.n - 1],
     )
     .into_affine();
-    let pt_u2 = G2Affine::sum_of_products(
+    let pt_u2 = multiexp(
         &params.g2_alpha_nplus2_to_2n[0..params.n - 1],
         &rs[0..params.n - 1],
     )
@@ -216,27 +533,433 @@ pub fn consistent(params: &PointproofsParams) -> bool {
     // 1: e(R_1, g_2) = e(g_1, R_2)
     // which essentially checks e("g_1^{alpha^i}", g_2) = e(g_1, "g_2^{alpha^i}") for all 1<=i<=N
     if g2.pairing_with(&pt_r1) != g1.pairing_with(&pt_r2) {
-        return false;
+        return Err(ConsistencyFailure::G1G2Powers);
     }
 
     // 2: e(S, g_2^alpha) = e(T, g_2)
     // which essentially checks e("g_1^{alpha^i}", g_2^alpha) = e(g_1^{alpha^{i+1}}, g_2) for all 1<=i<=N-1
     if pt_s.pairing_with(g2alpha) != pt_t.pairing_with(&g2) {
-        return false;
+        return Err(ConsistencyFailure::AlphaShift);
     }
 
     // 3: e(g_1^{alpha^N}, g_2^alpha) = "e(g_1, g_2)^{alpha^{N+1}}"
     let mut tmp = params.g1_alpha_1_to_n[params.n - 1].pairing_with(g2alpha);
     tmp.sub_assign(&params.gt_alpha_nplus1);
     if !tmp.is_zero() {
-        return false;
+        return Err(ConsistencyFailure::GtAlphaNplus1);
     }
 
     // 4: e(T, g_2^{alpha^N}) = e(U_1, g_2) = e(g_1, U_2)
     let tmp = pt_t.pairing_with(&params.g2_alpha_1_to_n[params.n - 1]);
     if tmp != pt_u1.pairing_with(&g2) || tmp != pt_u2.pairing_with(&g1) {
-        return false;
+        return Err(ConsistencyFailure::AlphaNplus2To2N);
+    }
+
+    Ok(())
+}
+
+// Checks the lengths of the lists, and that all points are in the group,
+// nonzero, and not the generator
+// (Subgroup check is already done in our deserialization code)
+fn check_points(params: &PointproofsParams) -> std::result::Result<(), ConsistencyFailure> {
+    if params.n == 0
+        || params.n > MAX_N
+        || params.g1_alpha_1_to_n.len() != params.n
+        || params.g2_alpha_1_to_n.len() != params.n
+        || params.g1_alpha_nplus2_to_2n.len() != params.n - 1
+        || params.g2_alpha_nplus2_to_2n.len() != params.n - 1
+    {
+        return Err(ConsistencyFailure::InvalidLength);
+    }
+
+    if let Some(i) = params
+        .g2_alpha_1_to_n
+        .iter()
+        .position(|&x| x == G2Affine::zero() || x == G2Affine::one())
+    {
+        return Err(ConsistencyFailure::G2Alpha1ToN(i));
+    }
+
+    if let Some(i) = params
+        .g2_alpha_nplus2_to_2n
+        .iter()
+        .position(|&x| x == G2Affine::zero() || x == G2Affine::one())
+    {
+        return Err(ConsistencyFailure::G2AlphaNplus2To2N(i));
+    }
+
+    if let Some(i) = params
+        .g1_alpha_1_to_n
+        .iter()
+        .position(|&x| x == G1Affine::zero() || x == G1Affine::one())
+    {
+        return Err(ConsistencyFailure::G1Alpha1ToN(i));
+    }
+
+    if let Some(i) = params
+        .g1_alpha_nplus2_to_2n
+        .iter()
+        .position(|&x| x == G1Affine::zero() || x == G1Affine::one())
+    {
+        return Err(ConsistencyFailure::G1AlphaNplus2To2N(i));
+    }
+    Ok(())
+}
+
+// Generate N random scalars r_1, ..., r_N
+fn consistency_scalars<R: RngCore>(params: &PointproofsParams, rng: &mut R) -> Vec<FrRepr> {
+    let mut rs_owned: Vec<FrRepr> = vec![];
+    for _ in 0..params.n {
+        let r = random_scalar_with_rng(rng).into_repr();
+        rs_owned.push(r);
+    }
+    rs_owned
+}
+
+// Same as `consistent`, with the multi-exponentiations and pairings
+// spread across the rayon thread pool.
+#[cfg(feature = "parallel")]
+pub fn consistent_parallel(params: &PointproofsParams) -> bool {
+    match OsRng::new() {
+        Ok(mut rng) => consistent_detailed_parallel_with_rng(params, &mut rng).is_ok(),
+        Err(_) => false,
+    }
+}
+
+// Same as `consistent_detailed_with_rng`, with the multi-exponentiations and pairings
+// spread across the rayon thread pool; the multi-exponentiations are the shared
+// `multiexp`, which splits large ones across the threads.
+// With the same rng, the result is identical to `consistent_detailed_with_rng`:
+// the scalars are drawn in the same order, and the checks are reported in the same order.
+#[cfg(feature = "parallel")]
+pub fn consistent_detailed_parallel_with_rng<R: RngCore>(
+    params: &PointproofsParams,
+    rng: &mut R,
+) -> std::result::Result<(), ConsistencyFailure> {
+    check_points(params)?;
+    let rs_owned = consistency_scalars(params, rng);
+    let mut rs: Vec<&[u64; 4]> = vec![];
+    for item in rs_owned.iter() {
+        rs.push(&item.0);
+    }
+    let n = params.n;
+
+    // S, R_2, T, U_1 and U_2 as in `consistent_detailed_with_rng`
+    let ((pt_s, pt_r2), (pt_t, (pt_u1, pt_u2))) = rayon::join(
+        || {
+            rayon::join(
+                || multiexp(&params.g1_alpha_1_to_n[0..n - 1], &rs[0..n - 1]),
+                || multiexp(&params.g2_alpha_1_to_n[0..n], &rs[0..n]),
+            )
+        },
+        || {
+            rayon::join(
+                || multiexp(&params.g1_alpha_1_to_n[1..n], &rs[0..n - 1]),
+                || {
+                    rayon::join(
+                        || multiexp(&params.g1_alpha_nplus2_to_2n[0..n - 1], &rs[0..n - 1]),
+                        || multiexp(&params.g2_alpha_nplus2_to_2n[0..n - 1], &rs[0..n - 1]),
+                    )
+                },
+            )
+        },
+    );
+    let pt_s = pt_s.into_affine();
+    let pt_r1: bls12_381::G1Affine = {
+        let mut tmp = params.g1_alpha_1_to_n[n - 1].mul(Fr::from_repr(rs_owned[n - 1]).unwrap());
+        tmp.add_assign_mixed(&pt_s);
+        tmp.into_affine()
+    };
+    let pt_r2 = pt_r2.into_affine();
+    let pt_t = pt_t.into_affine();
+    let pt_u1 = pt_u1.into_affine();
+    let pt_u2 = pt_u2.into_affine();
+
+    let g2 = G2Affine::one();
+    let g1 = G1Affine::one();
+    let g2alpha = &params.g2_alpha_1_to_n[0];
+
+    // all the pairings of the 4 checks of `consistent_detailed_with_rng`
+    let (((p1a, p1b), (p2a, p2b)), ((p3, p4a), (p4b, p4c))) = rayon::join(
+        || {
+            rayon::join(
+                || rayon::join(|| g2.pairing_with(&pt_r1), || g1.pairing_with(&pt_r2)),
+                || rayon::join(|| pt_s.pairing_with(g2alpha), || pt_t.pairing_with(&g2)),
+            )
+        },
+        || {
+            rayon::join(
+                || {
+                    rayon::join(
+                        || params.g1_alpha_1_to_n[n - 1].pairing_with(g2alpha),
+                        || pt_t.pairing_with(&params.g2_alpha_1_to_n[n - 1]),
+                    )
+                },
+                || rayon::join(|| pt_u1.pairing_with(&g2), || pt_u2.pairing_with(&g1)),
+            )
+        },
+    );
+
+    if p1a != p1b {
+        return Err(ConsistencyFailure::G1G2Powers);
+    }
+    if p2a != p2b {
+        return Err(ConsistencyFailure::AlphaShift);
+    }
+    if p3 != params.gt_alpha_nplus1 {
+        return Err(ConsistencyFailure::GtAlphaNplus1);
+    }
+    if p4a != p4b || p4a != p4c {
+        return Err(ConsistencyFailure::AlphaNplus2To2N);
+    }
+    Ok(())
+}
+
+// Same as `consistent_parallel`, using the given rayon thread pool instead of the global one.
+// With a single thread pool, the multi-exponentiations and pairings run one after the other,
+// as in `consistent`.
+#[cfg(feature = "parallel")]
+pub fn consistent_parallel_in_pool(params: &PointproofsParams, pool: &rayon::ThreadPool) -> bool {
+    pool.install(|| consistent_parallel(params))
+}
+
+// Same as `consistent_detailed_parallel_with_rng`, using the given rayon thread pool
+// instead of the global one.
+#[cfg(feature = "parallel")]
+pub fn consistent_detailed_parallel_with_rng_in_pool<R: RngCore + Send>(
+    params: &PointproofsParams,
+    rng: &mut R,
+    pool: &rayon::ThreadPool,
+) -> std::result::Result<(), ConsistencyFailure> {
+    pool.install(|| consistent_detailed_parallel_with_rng(params, rng))
+}
+
+/// A proof that a contributor turned the params for alpha into
+/// the params for alpha * s, and knows s.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContributionProof {
+    /// g1^s
+    pub g1_s: G1Affine,
+    /// g2^s
+    pub g2_s: G2Affine,
+    /// g1^k, the commitment of the Schnorr proof of knowledge of s
+    pub pok_commit: G1Affine,
+    /// k + c * s, the response of the Schnorr proof of knowledge of s
+    pub pok_response: Fr,
+}
+
+// The challenge of the Schnorr proof binds the proof to both params.
+fn contribution_challenge(
+    prev: &PointproofsParams,
+    new: &PointproofsParams,
+    g1_s: &G1Affine,
+    g2_s: &G2Affine,
+    pok_commit: &G1Affine,
+) -> Result<Fr> {
+    let mut bytes: Vec<u8> = b"Pointproofs-Contribution".to_vec();
+    prev.serialize(&mut bytes, true)?;
+    new.serialize(&mut bytes, true)?;
+    g1_s.serialize(&mut bytes, true)?;
+    g2_s.serialize(&mut bytes, true)?;
+    pok_commit.serialize(&mut bytes, true)?;
+    Ok(hash_to_field_pointproofs(&bytes))
+}
+
+impl PointproofsParams {
+    /// Contributes to a multi-party setup: raises the i-th power of alpha
+    /// in every element to the i-th power of a fresh secret s from the rng,
+    /// so the new params are for alpha * s.
+    /// s is wiped afterwards; the new params are secure as long as one of
+    /// the contributors in the chain wiped their secret.
+    /// Returns an error if the params are malformed or cannot be serialized.
+    pub fn contribute<R: RngCore>(prev: &Self, rng: &mut R) -> Result<(Self, ContributionProof)> {
+        let n = prev.n;
+        if n == 0
+            || prev.g1_alpha_1_to_n.len() != n
+            || prev.g2_alpha_1_to_n.len() != n
+            || prev.g1_alpha_nplus2_to_2n.len() != n - 1
+            || prev.g2_alpha_nplus2_to_2n.len() != n - 1
+        {
+            return Err(Error::new(ErrorKind::InvalidData, "Invalid n"));
+        }
+        let mut s = random_scalar_with_rng(rng);
+
+        // power = s^i for the i-th power of alpha
+        let mut power = Fr::one();
+        let mut g1_alpha_1_to_n: Vec<G1Affine> = Vec::with_capacity(n);
+        let mut g2_alpha_1_to_n: Vec<G2Affine> = Vec::with_capacity(n);
+        for i in 0..n {
+            power.mul_assign(&s);
+            g1_alpha_1_to_n.push(prev.g1_alpha_1_to_n[i].mul(power).into_affine());
+            g2_alpha_1_to_n.push(prev.g2_alpha_1_to_n[i].mul(power).into_affine());
+        }
+        power.mul_assign(&s);
+        let gt_alpha_nplus1 = prev.gt_alpha_nplus1.pow(power.into_repr());
+        let mut g1_alpha_nplus2_to_2n: Vec<G1Affine> = Vec::with_capacity(n - 1);
+        let mut g2_alpha_nplus2_to_2n: Vec<G2Affine> = Vec::with_capacity(n - 1);
+        for i in 0..n - 1 {
+            power.mul_assign(&s);
+            g1_alpha_nplus2_to_2n.push(prev.g1_alpha_nplus2_to_2n[i].mul(power).into_affine());
+            g2_alpha_nplus2_to_2n.push(prev.g2_alpha_nplus2_to_2n[i].mul(power).into_affine());
+        }
+        power.zeroize();
+
+        let new = PointproofsParams {
+            ciphersuite: prev.ciphersuite,
+            n,
+            g1_alpha_1_to_n,
+            g1_alpha_nplus2_to_2n,
+            g2_alpha_1_to_n,
+            g2_alpha_nplus2_to_2n,
+            gt_alpha_nplus1,
+        };
+
+        // prove knowledge of s
+        let g1_s = G1Affine::one().mul(s).into_affine();
+        let g2_s = G2Affine::one().mul(s).into_affine();
+        let mut k = random_scalar_with_rng(rng);
+        let pok_commit = G1Affine::one().mul(k).into_affine();
+        let mut pok_response = match contribution_challenge(prev, &new, &g1_s, &g2_s, &pok_commit)
+        {
+            Ok(c) => c,
+            Err(e) => {
+                s.zeroize();
+                k.zeroize();
+                return Err(e);
+            }
+        };
+        pok_response.mul_assign(&s);
+        pok_response.add_assign(&k);
+        s.zeroize();
+        k.zeroize();
+
+        Ok((
+            new,
+            ContributionProof {
+                g1_s,
+                g2_s,
+                pok_commit,
+                pok_response,
+            },
+        ))
+    }
+
+    /// Checks that `new` was derived from `prev` by `Self::contribute`:
+    /// the first power moved from alpha to alpha * s, the contributor
+    /// knows s, and `new` is consistent, hence all its powers are powers
+    /// of alpha * s.
+    /// The random scalars of the consistency check are drawn from OsRng;
+    /// returns false if OsRng is not available.
+    pub fn verify_contribution(prev: &Self, new: &Self, proof: &ContributionProof) -> bool {
+        match OsRng::new() {
+            Ok(mut rng) => Self::verify_contribution_with_rng(prev, new, proof, &mut rng),
+            Err(_) => false,
+        }
+    }
+
+    /// Same as `verify_contribution`, but the random scalars are drawn from the caller's rng.
+    pub fn verify_contribution_with_rng<R: RngCore>(
+        prev: &Self,
+        new: &Self,
+        proof: &ContributionProof,
+        rng: &mut R,
+    ) -> bool {
+        if prev.n != new.n
+            || prev.ciphersuite != new.ciphersuite
+            || prev.n == 0
+            || prev.g1_alpha_1_to_n.is_empty()
+            || new.g1_alpha_1_to_n.is_empty()
+            || proof.g1_s == G1Affine::zero()
+        {
+            return false;
+        }
+        let g1 = G1Affine::one();
+        let g2 = G2Affine::one();
+
+        // g1_s and g2_s have the same exponent s
+        if proof.g1_s.pairing_with(&g2) != g1.pairing_with(&proof.g2_s) {
+            return false;
+        }
+
+        // the new alpha is the previous one times s
+        if new.g1_alpha_1_to_n[0].pairing_with(&g2)
+            != prev.g1_alpha_1_to_n[0].pairing_with(&proof.g2_s)
+        {
+            return false;
+        }
+
+        // g1^response = pok_commit * g1_s^challenge
+        let challenge =
+            match contribution_challenge(prev, new, &proof.g1_s, &proof.g2_s, &proof.pok_commit) {
+                Ok(c) => c,
+                Err(_) => return false,
+            };
+        let mut rhs = proof.g1_s.mul(challenge);
+        rhs.add_assign_mixed(&proof.pok_commit);
+        if g1.mul(proof.pok_response) != rhs {
+            return false;
+        }
+
+        consistent_with_rng(new, rng)
     }
+}
+
+impl PointproofsParams {
+    /// Checks that `self` is `master` truncated to `self.n`, i.e., that both
+    /// were generated with the same alpha: every g^{alpha^i} of `self` is the
+    /// g^{alpha^i} of `master`, and e(g2,g1)^{alpha^{n+1}} of `self` is the
+    /// pairing of g1^{alpha^{n+1}} of `master` with g2.
+    /// The points of `self` are compared to the points of `master` directly,
+    /// except g^{alpha^{N+1}} (the hole of `master`), which `self` contains when
+    /// n < N < 2n and is checked against e(g2,g1)^{alpha^{N+1}} of `master`.
+    /// Both sets must have the same ciphersuite.
+    /// Neither set is checked for consistency; run `consistent` on `master`.
+    /// Note that `master` publishes g1^{alpha^{n+1}} when n < N, so `self` is
+    /// only binding for the parties who do not hold `master`.
+    pub fn is_truncation_of(&self, master: &Self) -> bool {
+        for params in [self, master].iter() {
+            if params.n == 0
+                || params.g1_alpha_1_to_n.len() != params.n
+                || params.g2_alpha_1_to_n.len() != params.n
+                || params.g1_alpha_nplus2_to_2n.len() != params.n - 1
+                || params.g2_alpha_nplus2_to_2n.len() != params.n - 1
+            {
+                return false;
+            }
+        }
+        if self.n > master.n || self.ciphersuite != master.ciphersuite {
+            return false;
+        }
+        let g1 = G1Affine::one();
+        let g2 = G2Affine::one();
+
+        // the exponents of self are 1..=n and n+2..=2n, in this order
+        let exponents = (1..=self.n).chain(self.n + 2..=2 * self.n);
+        let g1_points = self.g1_alpha_1_to_n.iter().chain(self.g1_alpha_nplus2_to_2n.iter());
+        let g2_points = self.g2_alpha_1_to_n.iter().chain(self.g2_alpha_nplus2_to_2n.iter());
+        for ((e, p1), p2) in exponents.zip(g1_points).zip(g2_points) {
+            if e <= master.n {
+                if *p1 != master.g1_alpha_1_to_n[e - 1] || *p2 != master.g2_alpha_1_to_n[e - 1] {
+                    return false;
+                }
+            } else if e == master.n + 1 {
+                if p1.pairing_with(&g2) != master.gt_alpha_nplus1
+                    || g1.pairing_with(p2) != master.gt_alpha_nplus1
+                {
+                    return false;
+                }
+            } else if *p1 != master.g1_alpha_nplus2_to_2n[e - master.n - 2]
+                || *p2 != master.g2_alpha_nplus2_to_2n[e - master.n - 2]
+            {
+                return false;
+            }
+        }
 
-    true
-}
\ No newline at end of file
+        // the hole of self
+        if self.n == master.n {
+            self.gt_alpha_nplus1 == master.gt_alpha_nplus1
+        } else {
+            master.g1_alpha_1_to_n[self.n].pairing_with(&g2) == self.gt_alpha_nplus1
+        }
+    }
+}
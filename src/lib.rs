@@ -1,14 +1,49 @@
 #![cfg_attr(feature = "cargo-clippy", allow(clippy::cognitive_complexity))]
 #![cfg_attr(feature = "cargo-clippy", allow(clippy::missing_safety_doc))]
+#![cfg_attr(not(feature = "std"), no_std)]
+// without std only the verification path is built,
+// so some helpers and imports of the shared modules are unused
+#![cfg_attr(not(feature = "std"), allow(dead_code, unused_imports))]
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
 extern crate bigint;
+extern crate blake2;
 extern crate ff_zeroize as ff;
 extern crate pairing_plus;
+#[cfg(feature = "std")]
 extern crate pointproofs_paramgen;
 extern crate sha2;
+extern crate sha3;
+#[cfg(feature = "std")]
 extern crate rand;
 extern crate zeroize;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
 pub mod pairings;
+#[cfg(feature = "std")]
 pub(crate) mod forfix;
 
-#[cfg(test)]
+/// Releases the memory held by process-global or thread-local caches.
+///
+/// The crate currently keeps no such caches: every cache is owned by
+/// the instance that uses it, and is released when that instance is dropped.
+///     * `ProverParams::precomp_3`, `precomp_256`, `precomp_window`:
+///     the pre-computed table of the prover parameters.
+///     * `VerifierParams::precomp_3`, `precomp_256`:
+///     the pre-computed table of the verifier parameters.
+///     * `VerifierParams::precomp`:
+///     the prepared generators of the verifier parameters, for the Miller loop;
+///     it is released by dropping the `VerifierParams`, or by re-creating them,
+///     e.g., deserializing them again, as the cache is not serialized.
+/// So this function does nothing for now. Any cache that is made global
+/// in the future must be cleared here, so that callers can rely
+/// on this function to release memory before forking or idling.
+pub fn clear_caches() {}
+
+#[cfg(all(test, feature = "std"))]
 mod test;
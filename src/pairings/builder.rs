@@ -0,0 +1,80 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for building a commitment one value at a time.
+
+use pairings::param::MAX_N;
+use pairings::*;
+
+impl CommitmentBuilder {
+    /// start a new builder for a vector of n values.
+    ///     * input: the number of values n
+    ///     * output: an empty builder
+    /// At most MAX_N values are reserved up front, whatever n is.
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            n,
+            max_value_len: None,
+            values: Vec::with_capacity(n.min(MAX_N)),
+        }
+    }
+
+    /// reject the values longer than max_value_len, in bytes,
+    /// as `Commitment::new_bounded` does.
+    pub fn with_max_value_len(mut self, max_value_len: usize) -> Self {
+        self.max_value_len = Some(max_value_len);
+        self
+    }
+
+    /// append the next value
+    ///     * input: the value
+    ///     * error: `PointproofsError::TooManyValues` if n values were already pushed,
+    ///     or `PointproofsError::ValueTooLong` if the value is longer than the bound
+    /// A rejected value is not kept, so the builder can go on with another one.
+    pub fn push<Blob: AsRef<[u8]>>(&mut self, value: Blob) -> Result<(), PointproofsError> {
+        let value = value.as_ref();
+        if self.values.len() >= self.n {
+            return Err(PointproofsError::TooManyValues {
+                got: self.values.len() + 1,
+                max: self.n,
+            });
+        }
+        if let Some(max) = self.max_value_len {
+            if value.len() > max {
+                return Err(PointproofsError::ValueTooLong {
+                    index: self.values.len(),
+                    len: value.len(),
+                    max,
+                });
+            }
+        }
+        self.values.push(value.to_vec());
+        Ok(())
+    }
+
+    /// the number of values pushed so far
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// whether no value was pushed yet
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// the values pushed so far, by index
+    pub fn values(&self) -> &[Vec<u8>] {
+        &self.values
+    }
+
+    /// commit to the pushed values
+    ///     * input: prover parameter set
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or `PointproofsError::MismatchedN`
+    ///     if fewer than n values were pushed or the parameters are not for n values
+    /// The output is identical to `Commitment::new` on the same values.
+    pub fn finish(&self, prover_params: &ProverParams) -> Result<Commitment, PointproofsError> {
+        if self.values.len() != self.n || prover_params.n != self.n {
+            return Err(PointproofsError::MismatchedN);
+        }
+        Commitment::new(prover_params, &self.values)
+    }
+}
@@ -1,5 +1,21 @@
 //! this file is part of the pointproofs.
 //! it exposes Rust APIs to C.
+//! The header is generated with cbindgen, see `make`.
+//!
+//! Ownership rules:
+//!     * `pointproofs_pp`, `pointproofs_vp`, `pointproofs_commitment` and `pointproofs_proof`
+//!     are opaque handles to objects owned by Rust. Every handle returned through an
+//!     output pointer must be released exactly once with the matching
+//!     `pointproofs_free_prover_params`, `pointproofs_free_verifier_params`,
+//!     `pointproofs_free_commit` or `pointproofs_free_proof`.
+//!     * Handles passed as inputs are borrowed, not consumed; copying the struct
+//!     copies the handle, not the object.
+//!     * `pointproofs_*_bytes` returned by the `*_serial` functions are owned by Rust
+//!     and must be released with the matching `pointproofs_free_*_string`.
+//!     `pointproofs_*_bytes` passed to the `*_deserial` functions are borrowed,
+//!     and can be freed by the caller afterwards.
+//!     * `pointproofs_value` is always borrowed; the data must stay valid for the call.
+//!     * On failure (return value -1) no handle is written, so there is nothing to free.
 
 extern crate libc;
 use pairing_plus::serdes::SerDes;
@@ -25,6 +41,7 @@ pub struct pointproofs_value {
 
 /// serialized prover parameter struct
 #[repr(C)]
+#[derive(Clone)]
 pub struct pointproofs_pp_bytes {
     data: *mut u8,
     len: usize,
@@ -32,6 +49,7 @@ pub struct pointproofs_pp_bytes {
 
 /// serialized verifer parameter struct
 #[repr(C)]
+#[derive(Clone)]
 pub struct pointproofs_vp_bytes {
     data: *mut u8,
     len: usize,
@@ -314,25 +332,25 @@ pub unsafe extern "C" fn pointproofs_paramgen(
 /// Free prover parameter
 #[no_mangle]
 pub unsafe extern "C" fn pointproofs_free_prover_params(pp: pointproofs_pp) {
-    Box::from_raw(pp.data);
+    drop(Box::from_raw(pp.data as *mut ProverParams));
 }
 
 /// Free verifier parameter
 #[no_mangle]
 pub unsafe extern "C" fn pointproofs_free_verifier_params(vp: pointproofs_vp) {
-    Box::from_raw(vp.data);
+    drop(Box::from_raw(vp.data as *mut VerifierParams));
 }
 
 /// Free commitment
 #[no_mangle]
 pub unsafe extern "C" fn pointproofs_free_commit(commit: pointproofs_commitment) {
-    Box::from_raw(commit.data);
+    drop(Box::from_raw(commit.data as *mut Commitment));
 }
 
 /// Free proof
 #[no_mangle]
 pub unsafe extern "C" fn pointproofs_free_proof(proof: pointproofs_proof) {
-    Box::from_raw(proof.data);
+    drop(Box::from_raw(proof.data as *mut Proof));
 }
 
 fn pointproofs_value_slice<'a>(vv: &pointproofs_value) -> &'a [u8] {
@@ -2,43 +2,63 @@
 //! It defines APIs for constructing and updating commitments.
 
 use ff::{Field, PrimeField};
+use pairing_plus::serdes::SerDes;
 use pairing_plus::{bls12_381::*, CurveAffine, CurveProjective};
-use pairings::err::*;
 use pairings::hash_to_field_pointproofs::*;
 use pairings::misc::*;
 use pairings::param::*;
 use pairings::*;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Below this many values `Commitment::new_parallel` falls back to the serial path.
+#[cfg(feature = "parallel")]
+pub(crate) const PARALLEL_COMMIT_THRESHOLD: usize = 1024;
 
 impl Commitment {
     /// generate a new commitment.
     ///     * input: prover parameter set
     ///     * input: a list of n values
     ///     * output: a commitment
-    ///     * error: invalid ciphersuite/parameters
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
     /// note that if the #values does not match the parameter n,
-    /// an error will be returned.
+    /// an error will be returned; more than n values is reported
+    /// separately as `PointproofsError::TooManyValues`.
     /// if one were to generate a commitment for a vector of length
     /// less than n, then the caller should pad the vector.
     /// In this scenario, the caller should define the right
     /// format for padding.
+    /// The values may be secret, so the scalars hashed from them
+    /// are zeroized when dropped.
     pub fn new<Blob: AsRef<[u8]>>(
         prover_params: &ProverParams,
         values: &[Blob],
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         };
 
+        if values.len() > prover_params.n {
+            return Err(PointproofsError::TooManyValues {
+                got: values.len(),
+                max: prover_params.n,
+            });
+        };
         if prover_params.n != values.len() {
-            return Err(ERR_INVALID_VALUE.to_owned());
+            return Err(PointproofsError::MismatchedN);
         };
+        check_value_lens(values, prover_params.max_value_len)?;
 
         // hash the values into scalars
-        let scalars_fr_repr: Vec<FrRepr> = values
-            .iter()
-            .map(|s| hash_to_field_repr_pointproofs(s.as_ref()))
-            .collect();
+        let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+            values
+                .iter()
+                .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, s.as_ref()))
+                .collect(),
+        );
         let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
 
         // commit = \prod pp[i]^scalar[i]
@@ -46,10 +66,520 @@ impl Commitment {
 
         Ok(Self {
             ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
+            commit,
+        })
+    }
+
+    /// generate a new commitment, together with the proof for one index.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the index of the proof
+    ///     * output: a commitment and the proof for index
+    ///     * error: invalid ciphersuite/parameters, index out of range, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is identical to `Commitment::new` and `Proof::new`;
+    /// the values are only hashed once for both.
+    pub fn new_with_proof<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        index: usize,
+    ) -> Result<(Self, Proof), PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        if values.len() > prover_params.n {
+            return Err(PointproofsError::TooManyValues {
+                got: values.len(),
+                max: prover_params.n,
+            });
+        };
+        if prover_params.n != values.len() {
+            return Err(PointproofsError::MismatchedN);
+        };
+        if index >= prover_params.n {
+            return Err(PointproofsError::IndexOutOfRange);
+        };
+        check_value_lens(values, prover_params.max_value_len)?;
+
+        // hash the values into scalars
+        let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+            values
+                .iter()
+                .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, s.as_ref()))
+                .collect(),
+        );
+        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+        // commit = \prod pp[i]^scalar[i]
+        let commit = pp_sum_of_prod_helper(&prover_params, &scalars_u64, 0, prover_params.n);
+        // proof = \prod pp[n - index + i]^scalar[i]
+        let proof = pp_sum_of_prod_helper(
+            &prover_params,
+            &scalars_u64,
+            prover_params.n - index,
+            2 * prover_params.n - index,
+        );
+
+        Ok((
+            Self {
+                ciphersuite: prover_params.ciphersuite,
+                n: prover_params.n,
+                commit,
+            },
+            Proof {
+                ciphersuite: prover_params.ciphersuite,
+                n: prover_params.n,
+                proof,
+            },
+        ))
+    }
+
+    /// generate the commitment to the default vector,
+    /// i.e., n copies of the default value, the empty byte string.
+    ///     * input: prover parameter set
+    ///     * output: the commitment to the default vector
+    ///     * error: invalid ciphersuite, or `PointproofsError::InvalidParams`
+    ///     if the parameters do not hold 2n generators
+    /// The output is identical to `Commitment::new` over n empty values,
+    /// and can be updated one index at a time from the empty value.
+    /// It costs n additions and one exponentiation instead of an
+    /// n-term multi-exponentiation.
+    pub fn empty(prover_params: &ProverParams) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+        // the prover parameters hold 2n generators, see paramgen_from_alpha
+        if prover_params.generators.len() != 2 * prover_params.n {
+            return Err(PointproofsError::InvalidParams);
+        };
+
+        // commit = (\prod pp[i])^hash("")
+        let mut commit = PointproofsG1::zero();
+        for g in prover_params.generators[0..prover_params.n].iter() {
+            commit.add_assign_mixed(g);
+        }
+        commit.mul_assign(hash_to_field_ciphersuite(prover_params.ciphersuite, b""));
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
+            commit,
+        })
+    }
+
+    /// generate a new commitment, rejecting oversized values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the maximum length of a value, in bytes
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for the first value longer than max_value_len
+    /// The output is identical to `Commitment::new`.
+    pub fn new_bounded<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        max_value_len: usize,
+    ) -> Result<Self, PointproofsError> {
+        check_value_lens(values, max_value_len)?;
+        Self::new(prover_params, values)
+    }
+
+    /// generate a new commitment from an iterator over the values.
+    ///     * input: prover parameter set
+    ///     * input: the n values, in order
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The values are hashed as they come, so only their hashes
+    /// are kept in memory; the output is identical to `Commitment::new`
+    /// for the same sequence of values.
+    /// A value is checked against the bound before it is hashed,
+    /// so the iteration stops at the first oversized value.
+    pub fn new_from_iter<I>(
+        prover_params: &ProverParams,
+        values: I,
+    ) -> Result<Self, PointproofsError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        // hash the values into scalars, counting them as we go
+        let mut scalars_fr_repr: Zeroizing<Vec<FrRepr>> =
+            Zeroizing::new(Vec::with_capacity(prover_params.n));
+        let mut count = 0;
+        for value in values {
+            let value = value.as_ref();
+            check_value_len(count, value, prover_params.max_value_len)?;
+            if count < prover_params.n {
+                scalars_fr_repr.push(hash_to_field_repr_ciphersuite(
+                    prover_params.ciphersuite,
+                    value,
+                ));
+            }
+            count += 1;
+        }
+
+        if count > prover_params.n {
+            return Err(PointproofsError::TooManyValues {
+                got: count,
+                max: prover_params.n,
+            });
+        };
+        if prover_params.n != count {
+            return Err(PointproofsError::MismatchedN);
+        };
+        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+        // commit = \prod pp[i]^scalar[i]
+        let commit = pp_sum_of_prod_helper(&prover_params, &scalars_u64, 0, prover_params.n);
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
+            commit,
+        })
+    }
+
+    /// generate a new commitment, hashing the values in chunks.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the number of values hashed at a time
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// Each chunk is hashed and folded into the commitment with its own
+    /// multi-exponentiation, so at most chunk scalars are kept in memory
+    /// instead of n; a chunk of 0 is taken as 1.
+    /// The output is identical to `Commitment::new`.
+    pub fn new_chunked<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        chunk: usize,
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        if values.len() > prover_params.n {
+            return Err(PointproofsError::TooManyValues {
+                got: values.len(),
+                max: prover_params.n,
+            });
+        };
+        if prover_params.n != values.len() {
+            return Err(PointproofsError::MismatchedN);
+        };
+        check_value_lens(values, prover_params.max_value_len)?;
+
+        let n = prover_params.n;
+        let chunk = chunk.max(1).min(n.max(1));
+        let mut commit = PointproofsG1::zero();
+        for start in (0..n).step_by(chunk) {
+            let end = std::cmp::min(start + chunk, n);
+
+            // hash the current chunk into scalars
+            let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+                values[start..end]
+                    .iter()
+                    .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, s.as_ref()))
+                    .collect(),
+            );
+            let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+            // commit += \prod_{i=start}^{end-1} pp[i]^scalar[i]
+            commit.add_assign(&pp_sum_of_prod_helper(
+                &prover_params,
+                &scalars_u64,
+                start,
+                end,
+            ));
+        }
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
+            commit,
+        })
+    }
+
+    /// generate a new commitment from the already hashed values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n scalars, in repr form
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, non-canonical scalars
+    /// The i-th scalar is expected to be hash_to_field(values\[i\]);
+    /// the output then matches `Commitment::new`.
+    /// This avoids re-hashing for callers that keep the hashes
+    /// in a single contiguous buffer.
+    pub fn new_from_repr_slice(
+        prover_params: &ProverParams,
+        reprs: &[FrRepr],
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        if reprs.len() > prover_params.n {
+            return Err(PointproofsError::TooManyValues {
+                got: reprs.len(),
+                max: prover_params.n,
+            });
+        };
+        if prover_params.n != reprs.len() {
+            return Err(PointproofsError::MismatchedN);
+        };
+        if !is_canonical_repr_slice(reprs) {
+            return Err(PointproofsError::NonCanonicalScalar);
+        }
+
+        let scalars_u64: Vec<&[u64; 4]> = reprs.iter().map(|s| &s.0).collect();
+
+        // commit = \prod pp[i]^scalar[i]
+        let commit = pp_sum_of_prod_helper(&prover_params, &scalars_u64, 0, prover_params.n);
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
+            commit,
+        })
+    }
+
+    /// generate a new commitment from the already hashed values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n scalars, see `ProverParams::hash_values`
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters
+    /// The output matches `Commitment::new` for the values the scalars
+    /// were hashed from, so a vector can be hashed once and reused for
+    /// the commitment and every `Proof::new_prehashed`.
+    pub fn new_prehashed(
+        prover_params: &ProverParams,
+        scalars: &[Fr],
+    ) -> Result<Self, PointproofsError> {
+        let reprs: Zeroizing<Vec<FrRepr>> =
+            Zeroizing::new(scalars.iter().map(|s| s.into_repr()).collect());
+        Self::new_from_repr_slice(prover_params, &reprs)
+    }
+
+    /// generate a new commitment, using the rayon thread pool.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is identical to `Commitment::new`.
+    /// For n < `PARALLEL_COMMIT_THRESHOLD` the thread overhead dominates
+    /// and the serial path is used instead.
+    #[cfg(feature = "parallel")]
+    pub fn new_parallel<Blob: AsRef<[u8]> + Sync>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+    ) -> Result<Self, PointproofsError> {
+        Self::new_parallel_with_threshold(prover_params, values, PARALLEL_COMMIT_THRESHOLD)
+    }
+
+    /// generate a new commitment, using the given rayon thread pool
+    /// instead of the global one.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the thread pool
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is identical to `Commitment::new`.
+    /// The work is split into one chunk per thread of the pool,
+    /// so a single thread pool computes the serial multi-exponentiation.
+    #[cfg(feature = "parallel")]
+    pub fn new_parallel_in_pool<Blob: AsRef<[u8]> + Sync>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        pool: &rayon::ThreadPool,
+    ) -> Result<Self, PointproofsError> {
+        pool.install(|| Self::new_parallel(prover_params, values))
+    }
+
+    /// generate a new commitment, using the rayon thread pool
+    /// whenever n >= threshold.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the minimum n for which the parallel path is taken
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is identical to `Commitment::new`.
+    #[cfg(feature = "parallel")]
+    pub fn new_parallel_with_threshold<Blob: AsRef<[u8]> + Sync>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        threshold: usize,
+    ) -> Result<Self, PointproofsError> {
+        use rayon::prelude::*;
+
+        if values.len() < threshold {
+            return Self::new(prover_params, values);
+        }
+
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        if values.len() > prover_params.n {
+            return Err(PointproofsError::TooManyValues {
+                got: values.len(),
+                max: prover_params.n,
+            });
+        };
+        if prover_params.n != values.len() {
+            return Err(PointproofsError::MismatchedN);
+        };
+        check_value_lens(values, prover_params.max_value_len)?;
+
+        // hash the values into scalars
+        let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+            values
+                .par_iter()
+                .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, s.as_ref()))
+                .collect(),
+        );
+        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+        // split [0, n) into one chunk per thread;
+        // commit = \prod_{chunk} \prod_{i \in chunk} pp[i]^scalar[i]
+        let num_threads = rayon::current_num_threads();
+        let chunk_size = ((prover_params.n + num_threads - 1) / num_threads).max(1);
+        let commit = scalars_u64
+            .par_chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let start = i * chunk_size;
+                pp_sum_of_prod_helper(&prover_params, chunk, start, start + chunk.len())
+            })
+            .reduce(PointproofsG1::zero, |mut acc, e| {
+                acc.add_assign(&e);
+                acc
+            });
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
             commit,
         })
     }
 
+    /// generate the commitments to many independent vectors.
+    ///     * input: prover parameter set
+    ///     * input: a list of vectors of n values each
+    ///     * output: a commitment for each vector
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`, for the first faulty vector
+    /// Each output is identical to `Commitment::new` over the vector.
+    /// The values are hashed into one buffer that is reused across vectors.
+    pub fn new_many<Blob: AsRef<[u8]>, Values: AsRef<[Blob]>>(
+        prover_params: &ProverParams,
+        vectors: &[Values],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        };
+
+        let mut scalars_fr_repr: Zeroizing<Vec<FrRepr>> =
+            Zeroizing::new(Vec::with_capacity(prover_params.n));
+        let mut res = Vec::with_capacity(vectors.len());
+        for values in vectors.iter() {
+            let values = values.as_ref();
+            if values.len() > prover_params.n {
+                return Err(PointproofsError::TooManyValues {
+                    got: values.len(),
+                    max: prover_params.n,
+                });
+            };
+            if prover_params.n != values.len() {
+                return Err(PointproofsError::MismatchedN);
+            };
+            check_value_lens(values, prover_params.max_value_len)?;
+
+            // hash the values into scalars
+            scalars_fr_repr.clear();
+            scalars_fr_repr.extend(
+                values
+                    .iter()
+                    .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, s.as_ref())),
+            );
+            let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+            // commit = \prod pp[i]^scalar[i]
+            res.push(Self {
+                ciphersuite: prover_params.ciphersuite,
+                n: prover_params.n,
+                commit: pp_sum_of_prod_helper(&prover_params, &scalars_u64, 0, prover_params.n),
+            });
+        }
+        Ok(res)
+    }
+
+    /// generate the commitments to many independent vectors,
+    /// one vector per task of the rayon thread pool.
+    ///     * input: prover parameter set
+    ///     * input: a list of vectors of n values each
+    ///     * output: a commitment for each vector
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is identical to `Commitment::new_many`, except that
+    /// the error is for any faulty vector, not necessarily the first one.
+    #[cfg(feature = "parallel")]
+    pub fn new_many_parallel<Blob: AsRef<[u8]> + Sync, Values: AsRef<[Blob]> + Sync>(
+        prover_params: &ProverParams,
+        vectors: &[Values],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        use rayon::prelude::*;
+
+        vectors
+            .par_iter()
+            .map(|values| Self::new(prover_params, values.as_ref()))
+            .collect()
+    }
+
+    /// generate the commitments to many independent vectors,
+    /// using the given rayon thread pool instead of the global one.
+    ///     * input: prover parameter set
+    ///     * input: a list of vectors of n values each
+    ///     * input: the thread pool
+    ///     * output: a commitment for each vector
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is identical to `Commitment::new_many_parallel`;
+    /// with a single thread pool the vectors are committed one at a time.
+    #[cfg(feature = "parallel")]
+    pub fn new_many_parallel_in_pool<Blob: AsRef<[u8]> + Sync, Values: AsRef<[Blob]> + Sync>(
+        prover_params: &ProverParams,
+        vectors: &[Values],
+        pool: &rayon::ThreadPool,
+    ) -> Result<Vec<Self>, PointproofsError> {
+        pool.install(|| Self::new_many_parallel(prover_params, vectors))
+    }
+
     /// upated an existing commitment
     ///     * input: commitment
     ///     * input: prover parameter set
@@ -57,29 +587,39 @@ impl Commitment {
     ///     * input: the old value
     ///     * input: the new value
     ///     * output: mutate self to the new commitment
-    ///     * error: invalid ciphersuite, parameters
+    ///     * error: invalid ciphersuite, parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
     pub fn update<Blob: AsRef<[u8]>>(
         &mut self,
         prover_params: &ProverParams,
         changed_index: usize,
         value_before: Blob,
         value_after: Blob,
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if !n_matches(self.n, prover_params.n) {
+            return Err(PointproofsError::MismatchedN);
         }
         if prover_params.n <= changed_index {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         };
+        let max_value_len = prover_params.max_value_len;
+        check_value_len(changed_index, value_before.as_ref(), max_value_len)?;
+        check_value_len(changed_index, value_after.as_ref(), max_value_len)?;
+        // a commitment of unknown n now has the n of the parameters
+        self.n = prover_params.n;
 
         // multiplier = hash(new_value) - hash(old_value)
-        let mut multiplier = hash_to_field_pointproofs(&value_before);
+        let mut multiplier = hash_to_field_ciphersuite(self.ciphersuite, &value_before);
         multiplier.negate();
-        multiplier.add_assign(&hash_to_field_pointproofs(&value_after));
+        multiplier.add_assign(&hash_to_field_ciphersuite(self.ciphersuite, &value_after));
 
         // new_commit = old_commit * g[index]^multiplier
         let res = pp_single_exp_helper(&prover_params, multiplier, changed_index);
@@ -88,6 +628,29 @@ impl Commitment {
         Ok(())
     }
 
+    /// Same as `Commitment::update`, but returns the updated commitment
+    /// and leaves self untouched.
+    ///     * input: commitment
+    ///     * input: prover parameter set
+    ///     * input: the index of the value to be updated
+    ///     * input: the old value
+    ///     * input: the new value
+    ///     * output: the new commitment
+    ///     * error: invalid ciphersuite, parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    pub fn updated<Blob: AsRef<[u8]>>(
+        &self,
+        prover_params: &ProverParams,
+        changed_index: usize,
+        value_before: Blob,
+        value_after: Blob,
+    ) -> Result<Self, PointproofsError> {
+        let mut res = self.clone();
+        res.update(prover_params, changed_index, value_before, value_after)?;
+        Ok(res)
+    }
+
     /// upated an existing commitment with a list of messages
     ///     * input: commitment
     ///     * input: prover parameter set
@@ -95,49 +658,63 @@ impl Commitment {
     ///     * input: the old values
     ///     * input: the new values
     ///     * output: mutate self to the new commitment
-    ///     * error: invalid ciphersuite, parameters
+    ///     * error: invalid ciphersuite, parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`, with its position in the lists
     /// Note that if their exist duplicated indices, an error
     /// will be returned.
     /// Also note that changed_index.len() should be within [0, n)
     /// 0 is valid -- the output commit stays unchanged
     /// n is invalid -- in this case the caller should call Commitment::new
+    /// To pass the changes as (index, old value, new value) tuples,
+    /// see `Commitment::batch_update_changes`.
     pub fn batch_update<Blob: AsRef<[u8]>>(
         &mut self,
         prover_params: &ProverParams,
         changed_index: &[usize],
         value_before: &[Blob],
         value_after: &[Blob],
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if !n_matches(self.n, prover_params.n) {
+            return Err(PointproofsError::MismatchedN);
         }
         // check the parameters are valid
         for index in changed_index {
             if prover_params.n <= *index {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::IndexOutOfRange);
             };
         }
         if changed_index.len() >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         }
         if changed_index.len() != value_before.len() || changed_index.len() != value_after.len() {
-            return Err(ERR_INDEX_VALUE_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexValueMismatch);
         }
         if !misc::has_unique_elements(changed_index) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
+        check_value_lens(value_before, prover_params.max_value_len)?;
+        check_value_lens(value_after, prover_params.max_value_len)?;
+        // a commitment of unknown n now has the n of the parameters
+        self.n = prover_params.n;
 
         // get the scalars from the hashes
         let mut multiplier_set: Vec<FrRepr> = Vec::with_capacity(value_before.len());
         for i in 0..value_before.len() {
             // multiplier = hash(new_value) - hash(old_value)
-            let mut multiplier = hash_to_field_pointproofs(&value_before[i]);
+            let mut multiplier = hash_to_field_ciphersuite(self.ciphersuite, &value_before[i]);
             multiplier.negate();
-            multiplier.add_assign(&hash_to_field_pointproofs(&value_after[i]));
+            multiplier.add_assign(&hash_to_field_ciphersuite(
+                self.ciphersuite,
+                &value_after[i],
+            ));
             multiplier_set.push(multiplier.into_repr());
         }
         let scalars_u64: Vec<&[u64; 4]> = multiplier_set.iter().map(|s| &s.0).collect();
@@ -158,20 +735,212 @@ impl Commitment {
                 for e in changed_index.iter() {
                     pre = [
                         pre,
-                        prover_params.precomp
-                            [changed_index[*e] * 256..(changed_index[*e] + 1) * 256]
-                            .to_vec(),
+                        prover_params.precomp[*e * 256..(*e + 1) * 256].to_vec(),
                     ]
                     .concat();
                 }
                 PointproofsG1Affine::sum_of_products_precomp_256(&basis, &scalars_u64, &pre)
             } else {
                 // without pre_computation
-                PointproofsG1Affine::sum_of_products(&basis[..], &scalars_u64)
+                multiexp_g1(&basis[..], &scalars_u64)
             }
         };
         // new_commit = old_commit * \prod g[index]^multiplier
         self.commit.add_assign(&delta);
         Ok(())
     }
+
+    /// upated an existing commitment with a list of changes
+    ///     * input: commitment
+    ///     * input: prover parameter set
+    ///     * input: the changes, as (index, old value, new value)
+    ///     * output: mutate self to the new commitment
+    ///     * error: invalid ciphersuite, parameters, duplicated indices,
+    ///     or oversized values
+    /// Same as `Commitment::batch_update`; the result equals
+    /// calling `Commitment::update` for each change in turn.
+    ///
+    /// This is `batch_update(prover_params, changes)` with the changes
+    /// given as `&[(usize, &[u8], &[u8])]`, like `Proof::batch_update`;
+    /// it has its own name because `Commitment::batch_update` keeps its
+    /// original signature, with the indices and values in separate lists.
+    pub fn batch_update_changes<Blob: AsRef<[u8]>>(
+        &mut self,
+        prover_params: &ProverParams,
+        changes: &[(usize, Blob, Blob)],
+    ) -> Result<(), PointproofsError> {
+        let changed_index: Vec<usize> = changes.iter().map(|c| c.0).collect();
+        let value_before: Vec<&[u8]> = changes.iter().map(|c| c.1.as_ref()).collect();
+        let value_after: Vec<&[u8]> = changes.iter().map(|c| c.2.as_ref()).collect();
+        self.batch_update(prover_params, &changed_index, &value_before, &value_after)
+    }
+
+    /// checks that the commitment opens to a full vector of values.
+    ///     * input: commitment
+    ///     * input: prover parameter set
+    ///     * input: the claimed n values
+    ///     * output: if the commitment is the commitment to the values
+    /// The commitment is recomputed with one multi-exponentiation,
+    /// which is much cheaper than verifying n proofs.
+    /// This needs the G1 powers of the prover parameters;
+    /// a verifier that only holds `VerifierParams` cannot do this check.
+    pub fn verify_full<Blob: AsRef<[u8]>>(
+        &self,
+        prover_params: &ProverParams,
+        claimed_values: &[Blob],
+    ) -> bool {
+        if self.ciphersuite != prover_params.ciphersuite {
+            return false;
+        }
+        if !n_matches(self.n, prover_params.n) {
+            return false;
+        }
+        match Self::new(prover_params, claimed_values) {
+            Ok(com) => com.commit == self.commit,
+            Err(_) => false,
+        }
+    }
+
+    /// extend an existing commitment to a larger vector.
+    ///     * input: commitment
+    ///     * input: the prover parameter set of the commitment, for size N
+    ///     * input: the prover parameter set for the larger size n
+    ///     * input: the n - N values appended to the vector
+    ///     * output: mutate self to the commitment for the n values
+    ///     * error: invalid ciphersuite, parameters, or number of values, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len` of the larger parameters
+    /// The output is identical to `Commitment::new` over the n values with
+    /// the larger parameters, but only the appended values are hashed.
+    /// This assumes both parameter sets were generated from the same alpha;
+    /// the first N generators are checked to match, which is the only part
+    /// of the old commitment that is reused.
+    pub fn extend<Blob: AsRef<[u8]>>(
+        &mut self,
+        old_prover_params: &ProverParams,
+        new_prover_params: &ProverParams,
+        appended_values: &[Blob],
+    ) -> Result<(), PointproofsError> {
+        // checks that cipersuite is supported
+        if self.ciphersuite != old_prover_params.ciphersuite
+            || self.ciphersuite != new_prover_params.ciphersuite
+            || !check_ciphersuite(self.ciphersuite)
+        {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        let old_n = old_prover_params.n;
+        let new_n = new_prover_params.n;
+        if old_n >= new_n
+            || old_prover_params.generators[..old_n] != new_prover_params.generators[..old_n]
+        {
+            return Err(PointproofsError::InvalidParams);
+        }
+        if appended_values.len() != new_n - old_n || !n_matches(self.n, old_n) {
+            return Err(PointproofsError::MismatchedN);
+        }
+        check_value_lens(appended_values, new_prover_params.max_value_len)?;
+
+        // hash the appended values into scalars
+        let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+            appended_values
+                .iter()
+                .map(|s| hash_to_field_repr_ciphersuite(new_prover_params.ciphersuite, s.as_ref()))
+                .collect(),
+        );
+        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+        // new_commit = old_commit * \prod_{i=N}^{n-1} g[i]^hash(value[i])
+        let delta = pp_sum_of_prod_helper(new_prover_params, &scalars_u64, old_n, new_n);
+        self.commit.add_assign(&delta);
+        self.n = new_n;
+        Ok(())
+    }
+
+    /// combine two commitments into a commitment to the sum of their vectors.
+    ///     * input: two commitments for the same parameter set
+    ///     * output: a commitment
+    ///     * error: the ciphersuites differ
+    /// If self commits to the scalars m_1, ..., m_n and other to
+    /// m'_1, ..., m'_n, where m_i = hash_to_field(v_i),
+    /// then the output is \prod g1[i]^(m_i + m'_i), that is,
+    /// a commitment to the elementwise sum of the scalars in Fr.
+    /// The sums are in general not hashes of any values, so the output
+    /// equals `Commitment::new_from_repr_slice` for the summed scalars,
+    /// rather than `Commitment::new` for some vector of values.
+    /// The commitments must be for the same n, when it is known;
+    /// a deserialized commitment does not record n, so for those
+    /// the caller must ensure both were built with the same parameters.
+    pub fn add(&self, other: &Self) -> Result<Self, PointproofsError> {
+        if self.ciphersuite != other.ciphersuite || !check_ciphersuite(self.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if !n_matches(self.n, other.n) {
+            return Err(PointproofsError::MismatchedN);
+        }
+        let mut commit = self.commit;
+        commit.add_assign(&other.commit);
+        Ok(Self {
+            ciphersuite: self.ciphersuite,
+            n: std::cmp::max(self.n, other.n),
+            commit,
+        })
+    }
+
+    /// Returns the fingerprint of the commitment:
+    /// the SHA-256 of `| ciphersuite id | commit |`,
+    /// i.e., of its serialization with compressed = true.
+    /// The serialization of a commitment is canonical, so two commitments
+    /// are equal if and only if their fingerprints are.
+    /// A party that learnt the fingerprint from a trusted source can use it
+    /// to check commitment bytes received from an untrusted one,
+    /// see `Proof::verify_bytes_checked`.
+    pub fn fingerprint(&self) -> CommitmentFingerprint {
+        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        // serializing a valid point into a vector does not fail
+        self.commit.into_affine().serialize(&mut buf, true).unwrap();
+
+        let mut hasher = Sha256::new();
+        hasher.input(&buf);
+        let mut fingerprint = [0u8; 32];
+        fingerprint.copy_from_slice(&hasher.result());
+        fingerprint
+    }
+
+    /// the ciphersuite of the commitment
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+}
+
+impl ProverParams {
+    /// checks that a commitment was built from a known vector of values.
+    ///     * input: prover parameter set
+    ///     * input: commitment
+    ///     * input: the claimed n values
+    ///     * output: if the commitment is the commitment to the values
+    /// Same as `Commitment::verify_full`: the commitment is recomputed,
+    /// which needs the G1 powers of the prover parameters.
+    /// `VerifierParams` only hold the G2 powers and cannot do this check.
+    pub fn verify_commit<Blob: AsRef<[u8]>>(&self, com: &Commitment, values: &[Blob]) -> bool {
+        com.verify_full(self, values)
+    }
+
+    /// hashes the values into scalars with the ciphersuite of the parameters.
+    ///     * input: prover parameter set
+    ///     * input: the values
+    ///     * output: hash_to_field(values\[i\]) for each value
+    ///     * error: `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The output is the input of `Commitment::new_prehashed`
+    /// and `Proof::new_prehashed`.
+    pub fn hash_values<Blob: AsRef<[u8]>>(
+        &self,
+        values: &[Blob],
+    ) -> Result<Vec<Fr>, PointproofsError> {
+        check_value_lens(values, self.max_value_len)?;
+        Ok(values
+            .iter()
+            .map(|s| hash_to_field_ciphersuite(self.ciphersuite, s.as_ref()))
+            .collect())
+    }
 }
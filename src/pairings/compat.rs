@@ -0,0 +1,76 @@
+//! this file is part of the pointproofs.
+//! It defines readers for earlier serialization formats.
+//!
+//! v0 is the format without a version header:
+//! `|ciphersuite id| point |`, with the point compressed.
+//! It is the format written by the current `serialize` with
+//! compressed = true; `deserialize` also reads the uncompressed
+//! encoding, flagged by `UNCOMPRESSED_POINT_FLAG` in the ciphersuite id.
+
+use pairing_plus::CurveProjective;
+use pairings::err::*;
+use pairings::param::*;
+use pairings::serdes::read_canonical_point;
+use pairings::*;
+
+/// reads `|ciphersuite id| point |`;
+/// the identity is rejected unless allow_identity is set
+fn read_v0<R: std::io::Read>(
+    reader: &mut R,
+    allow_identity: bool,
+) -> std::io::Result<(Ciphersuite, PointproofsG1)> {
+    // constants stores ciphersuite id
+    let mut constants: [u8; 1] = [0u8; 1];
+    reader.read_exact(&mut constants)?;
+
+    // check the ciphersuite id in the blob
+    if !check_ciphersuite(constants[0]) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_CIPHERSUITE,
+        ));
+    }
+
+    let point = read_canonical_point(reader, true)?;
+    if point.is_zero() && !allow_identity {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_IDENTITY_POINT,
+        ));
+    }
+    Ok((constants[0], point))
+}
+
+impl Commitment {
+    /// Convert a blob in the v0 format into a commitment:
+    ///
+    /// bytes => `|ciphersuite id | commit |`
+    ///
+    /// Returns an error if deserialization fails.
+    pub fn deserialize_v0<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let (ciphersuite, commit) = read_v0(reader, false)?;
+        // n is not serialized
+        Ok(Commitment {
+            ciphersuite,
+            n: 0,
+            commit,
+        })
+    }
+}
+
+impl Proof {
+    /// Convert a blob in the v0 format into a proof:
+    ///
+    /// bytes => `|ciphersuite id | proof |`
+    ///
+    /// Returns an error if deserialization fails.
+    pub fn deserialize_v0<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let (ciphersuite, proof) = read_v0(reader, true)?;
+        // n is not serialized
+        Ok(Proof {
+            ciphersuite,
+            n: 0,
+            proof,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+//! this file is part of the pointproofs.
+//! It defines the Debug output of the parameters, which omits the points.
+
+use pairing_plus::serdes::SerDes;
+use pairings::misc::short_digest;
+use pairings::*;
+use std::fmt;
+
+impl ProverParams {
+    /// A digest of `| ciphersuite id | n | generators |`;
+    /// the pre-computed table is derived from the generators and left out,
+    /// so it does not change with `precomp_3` or `precomp_256`.
+    fn debug_digest(&self) -> String {
+        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        buf.extend_from_slice(&(self.n as u32).to_le_bytes());
+        for e in self.generators.iter() {
+            // serializing a valid point into a vector does not fail
+            e.serialize(&mut buf, true).unwrap();
+        }
+        short_digest(&buf)
+    }
+
+    /// The Debug output with every generator and pre-computed point;
+    /// this is large, and `{:?}` prints a digest instead.
+    pub fn debug_full(&self) -> String {
+        format!(
+            "ProverParams {{ ciphersuite: {}, n: {}, generators: {:?}, pp_len: {}, precomp: {:?} }}",
+            self.ciphersuite, self.n, self.generators, self.pp_len, self.precomp
+        )
+    }
+}
+
+impl fmt::Debug for ProverParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ProverParams")
+            .field("ciphersuite", &self.ciphersuite)
+            .field("n", &self.n)
+            .field("precomp", &(self.pp_len != 0))
+            .field("digest", &self.debug_digest())
+            .finish()
+    }
+}
+
+impl VerifierParams {
+    /// A digest of `| ciphersuite id | n | generators | gt_elt |`;
+    /// the pre-computed and prepared points are derived from the
+    /// generators and left out.
+    fn debug_digest(&self) -> String {
+        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        buf.extend_from_slice(&(self.n as u32).to_le_bytes());
+        for e in self.generators.iter() {
+            // serializing a valid point into a vector does not fail
+            e.serialize(&mut buf, true).unwrap();
+        }
+        self.gt_elt.serialize(&mut buf, true).unwrap();
+        short_digest(&buf)
+    }
+
+    /// The Debug output with every generator and pre-computed point;
+    /// this is large, and `{:?}` prints a digest instead.
+    pub fn debug_full(&self) -> String {
+        format!(
+            "VerifierParams {{ ciphersuite: {}, n: {}, generators: {:?}, pp_len: {}, precomp: {:?}, gt_elt: {:?}, prepared: {:?} }}",
+            self.ciphersuite,
+            self.n,
+            self.generators,
+            self.pp_len,
+            self.precomp,
+            self.gt_elt,
+            self.prepared
+        )
+    }
+}
+
+impl fmt::Debug for VerifierParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VerifierParams")
+            .field("ciphersuite", &self.ciphersuite)
+            .field("n", &self.n)
+            .field("precomp", &(self.pp_len != 0))
+            .field("prepared", &!self.prepared.is_empty())
+            .field("digest", &self.debug_digest())
+            .finish()
+    }
+}
@@ -0,0 +1,153 @@
+//! this file is part of the pointproofs.
+//! It defines the changes between two commitments, for updating proofs remotely.
+use ff::{Field, PrimeField};
+use pairing_plus::bls12_381::*;
+use pairing_plus::CurveProjective;
+use pairings::hash_to_field_pointproofs::*;
+use pairings::param::*;
+use pairings::*;
+
+impl Commitment {
+    /// Computes the changes that turn self into other.
+    ///     * input: the commitment before the changes
+    ///     * input: the commitment after the changes
+    ///     * input: prover parameter set
+    ///     * input: the changes, as (index, old value, new value)
+    ///     * output: the delta from self to other
+    ///     * error: invalid ciphersuite/parameters, or `DeltaMismatch`
+    ///     if the changes do not turn self into other
+    /// A commitment is a single group element, so the changed indices
+    /// cannot be recovered from self and other alone; the caller supplies
+    /// them, and they are checked against the two commitments.
+    ///
+    /// The delta carries the index and hash(new value) - hash(old value)
+    /// of every change, i.e., 36 bytes per change once serialized,
+    /// against 8 bytes plus both values for the raw changes.
+    /// So it is smaller whenever the values are longer than 14 bytes
+    /// on average, and it does not reveal the values to the proof holders.
+    pub fn delta<Blob: AsRef<[u8]>>(
+        &self,
+        other: &Self,
+        prover_params: &ProverParams,
+        changes: &[(usize, Blob, Blob)],
+    ) -> Result<CommitmentDelta, PointproofsError> {
+        if self.ciphersuite != other.ciphersuite || self.ciphersuite != prover_params.ciphersuite {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        let plan = Proof::plan_updates(prover_params, changes)?;
+
+        // other = self * \prod g[index]^multiplier
+        let bases: Vec<PointproofsG1Affine> = plan
+            .changes
+            .iter()
+            .map(|(index, _)| prover_params.generators[*index])
+            .collect();
+        let scalars_u64: Vec<&[u64; 4]> = plan.changes.iter().map(|(_, s)| &s.0).collect();
+        let mut commit = self.commit;
+        if !bases.is_empty() {
+            commit.add_assign(&multiexp_g1(&bases, &scalars_u64));
+        }
+        if commit != other.commit {
+            return Err(PointproofsError::DeltaMismatch);
+        }
+
+        Ok(CommitmentDelta {
+            ciphersuite: self.ciphersuite,
+            changes: plan.changes,
+        })
+    }
+
+    /// Same as `Commitment::updated`, but also returns a witness of the change
+    /// that lets the holders of other proofs update them without the values.
+    ///     * input: commitment
+    ///     * input: prover parameter set
+    ///     * input: the index of the value to be updated
+    ///     * input: the old value
+    ///     * input: the new value
+    ///     * output: the new commitment, and the witness of the change
+    ///     * error: invalid ciphersuite, parameters
+    /// The witness only carries the index and hash(new value) - hash(old value).
+    pub fn update_with_witness<Blob: AsRef<[u8]>>(
+        &self,
+        prover_params: &ProverParams,
+        changed_index: usize,
+        value_before: Blob,
+        value_after: Blob,
+    ) -> Result<(Self, UpdateWitness), PointproofsError> {
+        let res = self.updated(prover_params, changed_index, &value_before, &value_after)?;
+
+        // multiplier = hash(new_value) - hash(old_value)
+        let mut multiplier = hash_to_field_ciphersuite(self.ciphersuite, &value_before);
+        multiplier.negate();
+        multiplier.add_assign(&hash_to_field_ciphersuite(self.ciphersuite, &value_after));
+        Ok((
+            res,
+            UpdateWitness {
+                ciphersuite: self.ciphersuite,
+                index: changed_index,
+                multiplier: multiplier.into_repr(),
+            },
+        ))
+    }
+}
+
+impl Proof {
+    /// Updating an existing proof with the delta between two commitments.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the delta
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters
+    /// Same as `Proof::batch_update` for the changes the delta was built from.
+    /// The delta may come from an untrusted party: if it is not the delta
+    /// between the two commitments, the updated proof does not verify
+    /// against the new commitment.
+    pub fn apply_delta(
+        &mut self,
+        prover_params: &ProverParams,
+        proof_index: usize,
+        delta: &CommitmentDelta,
+    ) -> Result<(), PointproofsError> {
+        if self.ciphersuite != delta.ciphersuite || !check_ciphersuite(delta.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        for (index, multiplier) in delta.changes.iter() {
+            if *index >= prover_params.n {
+                return Err(PointproofsError::IndexOutOfRange);
+            }
+            if Fr::from_repr(*multiplier).is_err() {
+                return Err(PointproofsError::NonCanonicalScalar);
+            }
+        }
+        self.apply_plan(
+            &UpdatePlan {
+                prover_params,
+                changes: delta.changes.clone(),
+            },
+            proof_index,
+        )
+    }
+
+    /// Updating an existing proof with the witness of a change.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the witness from `Commitment::update_with_witness`
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters
+    /// Same as `Proof::update`, without learning the old and new values.
+    pub fn update_from_witness(
+        &mut self,
+        prover_params: &ProverParams,
+        proof_index: usize,
+        witness: &UpdateWitness,
+    ) -> Result<(), PointproofsError> {
+        self.apply_delta(
+            prover_params,
+            proof_index,
+            &CommitmentDelta {
+                ciphersuite: witness.ciphersuite,
+                changes: vec![(witness.index, witness.multiplier)],
+            },
+        )
+    }
+}
@@ -0,0 +1,117 @@
+//! this file is part of the pointproofs.
+//! It defines a versioned envelope for the serialized objects.
+use pairing_plus::serdes::SerDes;
+use pairings::err::*;
+use pairings::serdes::deserialize_whole_slice;
+use pairings::*;
+use std::io::{Read, Write};
+
+/// The magic tag that starts every envelope.
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"PTPF";
+
+/// The format version written by `VersionedSerDes::serialize_versioned`.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// (De)serialization inside a versioned envelope:
+///
+/// `| magic | version | kind | payload length | payload |`
+///
+/// where the payload is the `SerDes` serialization with compressed = true,
+/// kind identifies the type of the payload, and the length is a u32 in
+/// little endian. A reader can skip an envelope after reading its
+/// 10 bytes header, without parsing the payload.
+pub trait VersionedSerDes: SerDes + Sized {
+    /// The kind byte of the envelope.
+    const KIND: u8;
+
+    /// Serialize self into an envelope.
+    /// Returns an error if the serialization fails,
+    /// or if the payload is longer than u32::MAX.
+    fn serialize_versioned<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut payload: Vec<u8> = vec![];
+        self.serialize(&mut payload, true)?;
+        if payload.len() > u32::max_value() as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                ERR_ENVELOPE_LEN,
+            ));
+        }
+
+        let mut buf: Vec<u8> = ENVELOPE_MAGIC.to_vec();
+        buf.push(ENVELOPE_VERSION);
+        buf.push(Self::KIND);
+        buf.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        buf.append(&mut payload);
+        writer.write_all(&buf)?;
+        Ok(())
+    }
+
+    /// Deserialize an envelope.
+    /// Returns an error if the magic tag or the kind do not match,
+    /// if the version is unknown, if the reader holds fewer bytes than the
+    /// length claims, or if the payload is not exactly one object.
+    /// Nothing is read after the payload.
+    fn deserialize_versioned<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut header = [0u8; 10];
+        reader.read_exact(&mut header)?;
+        if header[0..4] != ENVELOPE_MAGIC || header[5] != Self::KIND {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_ENVELOPE,
+            ));
+        }
+        if header[4] != ENVELOPE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_ENVELOPE_VERSION,
+            ));
+        }
+        let mut len = [0u8; 4];
+        len.copy_from_slice(&header[6..10]);
+        let len = u32::from_le_bytes(len) as usize;
+
+        // the buffer grows with the data actually read,
+        // so a short input claiming a large length does not allocate it
+        let mut payload: Vec<u8> = vec![];
+        reader.by_ref().take(len as u64).read_to_end(&mut payload)?;
+        if payload.len() != len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                ERR_TRUNCATED,
+            ));
+        }
+        deserialize_whole_slice(&payload, 0)
+    }
+}
+
+impl VersionedSerDes for Commitment {
+    const KIND: u8 = 0;
+}
+
+impl VersionedSerDes for Proof {
+    const KIND: u8 = 1;
+}
+
+impl VersionedSerDes for ProverParams {
+    const KIND: u8 = 2;
+}
+
+impl VersionedSerDes for VerifierParams {
+    const KIND: u8 = 3;
+}
+
+impl VersionedSerDes for SaltedCommitment {
+    const KIND: u8 = 4;
+}
+
+impl VersionedSerDes for HashCommitment {
+    const KIND: u8 = 5;
+}
+
+impl VersionedSerDes for CommitmentDelta {
+    const KIND: u8 = 6;
+}
+
+impl VersionedSerDes for UpdateWitness {
+    const KIND: u8 = 7;
+}
@@ -0,0 +1,259 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for proving that two commitments agree at an index,
+//! and that the value at an index is not a given value.
+
+use ff::{Field, PrimeField};
+use pairing_plus::bls12_381::*;
+use pairing_plus::{CurveAffine, CurveProjective};
+use pairings::hash_to_field_pointproofs::*;
+use pairings::misc::{check_value_len, n_matches};
+use pairings::param::*;
+use pairings::pointproofs_groups::*;
+use pairings::*;
+
+impl Proof {
+    /// Generate a proof that two commitments have the same value at an index,
+    /// without revealing the value.
+    ///     * input: prover parameter set
+    ///     * input: the first commitment and its n values
+    ///     * input: the second commitment and its n values
+    ///     * input: the index
+    ///     * output: an equality proof
+    ///     * error: invalid ciphersuite/parameters, `MismatchedN` if a
+    ///     commitment was made with parameters for another n, or
+    ///     `ValueMismatch` if the values at the index are not equal
+    /// Both commitments must be generated under the same parameters.
+    /// The proof is the difference of the two proofs at the index,
+    /// i.e., a proof that com_a / com_b opens to 0 at the index.
+    pub fn prove_equal<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        com_a: &Commitment,
+        values_a: &[Blob],
+        com_b: &Commitment,
+        values_b: &[Blob],
+        index: usize,
+    ) -> Result<EqualityProof, PointproofsError> {
+        if com_a.ciphersuite != prover_params.ciphersuite
+            || com_b.ciphersuite != prover_params.ciphersuite
+        {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if !n_matches(com_a.n, prover_params.n) || !n_matches(com_b.n, prover_params.n) {
+            return Err(PointproofsError::MismatchedN);
+        }
+        if values_a.len() != prover_params.n || values_b.len() != prover_params.n {
+            return Err(PointproofsError::MismatchedN);
+        }
+        if index >= prover_params.n {
+            return Err(PointproofsError::IndexOutOfRange);
+        }
+        if values_a[index].as_ref() != values_b[index].as_ref() {
+            return Err(PointproofsError::ValueMismatch);
+        }
+
+        let proof_a = Self::new(prover_params, values_a, index)?;
+        let mut proof_b = Self::new(prover_params, values_b, index)?;
+        proof_b.proof.negate();
+        let mut proof = proof_a.proof;
+        proof.add_assign(&proof_b.proof);
+        Ok(EqualityProof {
+            ciphersuite: prover_params.ciphersuite,
+            proof,
+        })
+    }
+
+    /// Generate a proof that the value at an index is not the forbidden value.
+    ///     * input: prover parameter set
+    ///     * input: the n values of the commitment
+    ///     * input: the index
+    ///     * input: the forbidden value
+    ///     * output: a not-equal proof
+    ///     * error: invalid ciphersuite/parameters, `ForbiddenValue`
+    ///     if the value at the index is the forbidden value, or
+    ///     `ValueTooLong` if a value or the forbidden value is longer
+    ///     than `ProverParams::max_value_len`
+    /// The proof is the opening proof at the index, together with
+    /// d = hash(value) - hash(forbidden) != 0, so the verifier checks the
+    /// opening to hash(forbidden) + d without being given the value.
+    ///
+    /// This is NOT zero-knowledge: given the forbidden value, d reveals
+    /// hash(value), so a verifier can test candidate values one by one.
+    /// A zero-knowledge variant would need auxiliary data to hide d,
+    /// e.g., a randomized commitment to d with a proof of knowledge
+    /// of its inverse, which this crate does not provide.
+    pub fn prove_not_equal<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        index: usize,
+        forbidden: &[u8],
+    ) -> Result<NotEqualProof, PointproofsError> {
+        if values.len() != prover_params.n {
+            return Err(PointproofsError::MismatchedN);
+        }
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if index >= prover_params.n {
+            return Err(PointproofsError::IndexOutOfRange);
+        }
+        // the cheap checks come before the proof, which costs a multiexp of size n
+        let max_value_len = prover_params.max_value_len;
+        check_value_len(index, values[index].as_ref(), max_value_len)?;
+        check_value_len(index, forbidden, max_value_len)?;
+        let mut difference = hash_to_field_ciphersuite(prover_params.ciphersuite, &values[index]);
+        let hash_forbidden = hash_to_field_ciphersuite(prover_params.ciphersuite, forbidden);
+        difference.sub_assign(&hash_forbidden);
+        if difference.is_zero() {
+            return Err(PointproofsError::ForbiddenValue);
+        }
+
+        let proof = Self::new(prover_params, values, index)?;
+        Ok(NotEqualProof {
+            ciphersuite: prover_params.ciphersuite,
+            proof: proof.proof,
+            difference: difference.into_repr(),
+        })
+    }
+}
+
+impl EqualityProof {
+    /// Verify a proof that two commitments have the same value at an index.
+    ///     * input: the equality proof
+    ///     * input: verifier parameter set
+    ///     * input: the first commitment
+    ///     * input: the second commitment
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// The verification formula is
+    /// e(com_a / com_b, param[n-index-1]) = e(proof, generator_of_g2)
+    /// which has no gt_elt term since the difference opens to 0.
+    /// Returns false if a commitment was made with parameters for another n.
+    pub fn verify(
+        &self,
+        verifier_params: &VerifierParams,
+        com_a: &Commitment,
+        com_b: &Commitment,
+        index: usize,
+    ) -> bool {
+        if self.ciphersuite != verifier_params.ciphersuite
+            || self.ciphersuite != com_a.ciphersuite
+            || self.ciphersuite != com_b.ciphersuite
+        {
+            return false;
+        }
+        if !check_ciphersuite(self.ciphersuite) {
+            return false;
+        }
+        if !n_matches(com_a.n, verifier_params.n) || !n_matches(com_b.n, verifier_params.n) {
+            return false;
+        }
+        if index >= verifier_params.n {
+            return false;
+        }
+
+        // check e(com_a / com_b, param[n-index-1]) * e(proof^{-1}, generator_of_g2) = 1
+        let mut com = com_b.commit;
+        com.negate();
+        com.add_assign(&com_a.commit);
+        let mut proof = self.proof;
+        proof.negate();
+        let pairing = pointproofs_pairing_product(
+            com.into_affine(),
+            verifier_params.generators[verifier_params.n - index - 1],
+            proof.into_affine(),
+            PointproofsG2Affine::one(),
+        );
+        pairing == Fq12::one()
+    }
+}
+
+impl NotEqualProof {
+    /// Verify a proof that the value at an index is not the forbidden value.
+    ///     * input: the not-equal proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the index
+    ///     * input: the forbidden value
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// The verification formula is the one of `Proof::verify`, with
+    /// hash(value) replaced by hash(forbidden) + d, and d != 0.
+    /// A forbidden value longer than `VerifierParams::max_value_len`
+    /// fails verification before it is hashed.
+    pub fn verify(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        index: usize,
+        forbidden: &[u8],
+    ) -> bool {
+        if self.ciphersuite != verifier_params.ciphersuite || self.ciphersuite != com.ciphersuite {
+            return false;
+        }
+        if !check_ciphersuite(self.ciphersuite) {
+            return false;
+        }
+        if !n_matches(com.n, verifier_params.n) {
+            return false;
+        }
+        if index >= verifier_params.n || com.commit.is_zero() {
+            return false;
+        }
+        let difference = match Fr::from_repr(self.difference) {
+            Ok(p) => p,
+            Err(_e) => return false,
+        };
+        if difference.is_zero() {
+            return false;
+        }
+        // reject an oversized forbidden value before hashing it
+        if forbidden.len() > verifier_params.max_value_len {
+            return false;
+        }
+
+        // hash = hash(forbidden) + d, the hash of the committed value
+        let mut hash = hash_to_field_ciphersuite(self.ciphersuite, forbidden);
+        hash.add_assign(&difference);
+        let hash_inverse = match hash.inverse() {
+            Some(p) => p,
+            None => return false,
+        };
+
+        // check e(com^{1/hash}, param[n-index-1]) * e(proof^{-1/hash}, generator_of_g2) = gt_elt
+        let mut com_mut = com.commit;
+        let mut proof_mut = self.proof;
+        proof_mut.negate();
+        com_mut.mul_assign(hash_inverse);
+        proof_mut.mul_assign(hash_inverse);
+        let pairing = pointproofs_pairing_product(
+            com_mut.into_affine(),
+            verifier_params.generators[verifier_params.n - index - 1],
+            proof_mut.into_affine(),
+            PointproofsG2Affine::one(),
+        );
+        pairing == verifier_params.gt_elt
+    }
+}
+
+/// Verify a proof that two commitments have the same value at an index;
+/// same as `EqualityProof::verify`.
+pub fn verify_equal(
+    verifier_params: &VerifierParams,
+    com_a: &Commitment,
+    com_b: &Commitment,
+    index: usize,
+    eq_proof: &EqualityProof,
+) -> bool {
+    eq_proof.verify(verifier_params, com_a, com_b, index)
+}
+
+/// Verify a proof that the value at an index is not the forbidden value;
+/// same as `NotEqualProof::verify`.
+pub fn verify_not_equal(
+    verifier_params: &VerifierParams,
+    com: &Commitment,
+    index: usize,
+    forbidden: &[u8],
+    proof: &NotEqualProof,
+) -> bool {
+    proof.verify(verifier_params, com, index, forbidden)
+}
@@ -5,11 +5,253 @@ pub(crate) const ERR_SEED_TOO_SHORT: &str = "The seed length is too short";
 pub(crate) const ERR_CIPHERSUITE: &str = "Invalid ciphersuite ID";
 pub(crate) const ERR_COMPRESS: &str = "Only support compress=true mode";
 pub(crate) const ERR_INVALID_VALUE: &str = "Invalid number of values";
+pub(crate) const ERR_MISMATCHED_N: &str =
+    "The n of the commitment, proof or values does not match the parameters";
+pub(crate) const ERR_TOO_MANY_VALUES: &str = "More values than the parameters support";
+pub(crate) const ERR_VALUE_TOO_LONG: &str = "Value is longer than the maximum length";
 pub(crate) const ERR_INVALID_INDEX: &str = "Invalid index";
 pub(crate) const ERR_DUPLICATED_INDEX: &str = "Duplicated index";
+pub(crate) const ERR_EMPTY_SET: &str = "Empty set of indices";
+pub(crate) const ERR_EMPTY_RANGE: &str = "Empty index range";
+pub(crate) const ERR_INVALID_RANGE: &str = "Index range is out of bound";
 pub(crate) const ERR_INDEX_PROOF_NOT_MATCH: &str = "Length of index and proof sets do not match";
 pub(crate) const ERR_X_COM_SIZE: &str =
     "Invalid sizes for commit, proof, or values for cross commit";
 pub(crate) const ERR_MAX_N: &str = "N is too large";
+pub(crate) const ERR_ZERO_N: &str = "N must be at least 1";
+pub(crate) const ERR_UNKNOWN_N: &str = "The n of the parameters is unknown";
+pub(crate) const ERR_TRUNCATED: &str = "The input is too short for the claimed n";
+pub(crate) const ERR_TRAILING: &str = "Trailing bytes after the serialized data";
+pub(crate) const ERR_NON_CANONICAL: &str = "Scalar is not in canonical form";
+pub(crate) const ERR_NON_CANONICAL_POINT: &str = "Point is not in canonical form";
+pub(crate) const ERR_POINT_ENCODING: &str = "Point is not in the expected encoding";
+pub(crate) const ERR_INVALID_POINT: &str = "The bytes are not a valid point";
+pub(crate) const ERR_INVALID_FIELD_ELEMENT: &str = "The bytes are not a valid field element";
+pub(crate) const ERR_SHORT_INPUT: &str = "The input is too short";
+pub(crate) const ERR_SUBGROUP: &str = "Point is not in the prime order subgroup";
+pub(crate) const ERR_PRECOMP_WINDOW: &str = "Invalid pre-computation window size";
+pub(crate) const ERR_PRECOMP_TOO_LARGE: &str = "The pre-computed table exceeds the memory budget";
+pub(crate) const ERR_PRECOMP_LEN: &str = "Invalid length of the pre-computed table";
 pub(crate) const ERR_PARAM: &str = "The input parameter is not correct";
+pub(crate) const ERR_MULTIEXP_LEN: &str = "The numbers of points and scalars do not match";
 pub(crate) const ERR_INDEX_VALUE_NOT_MATCH: &str = "Length of index and value sets do not match";
+pub(crate) const ERR_IDENTITY_COMMIT: &str = "The commitment is the identity element";
+pub(crate) const ERR_IDENTITY_POINT: &str = "The point is the identity element";
+pub(crate) const ERR_PAIRING_CHECK: &str = "Pairing check failed";
+pub(crate) const ERR_INCONSISTENT_PARAM: &str = "Input params are not consistent";
+pub(crate) const ERR_RNG: &str = "Random number generator is not available";
+pub(crate) const ERR_HASH_MISMATCH: &str = "The value does not match the committed hash";
+pub(crate) const ERR_TWEAK: &str = "The tweak is too long";
+pub(crate) const ERR_COMMIT_ENCODING: &str = "The commitment bytes are not a valid commitment";
+pub(crate) const ERR_FINGERPRINT: &str = "The commitment does not match the expected fingerprint";
+pub(crate) const ERR_ENVELOPE: &str = "Not a serialization envelope of the expected type";
+pub(crate) const ERR_ENVELOPE_VERSION: &str = "Unsupported serialization format version";
+pub(crate) const ERR_ENVELOPE_LEN: &str = "The payload is too large for the envelope";
+pub(crate) const ERR_DELTA: &str = "The changes do not explain the difference of the commitments";
+pub(crate) const ERR_VALUE_MISMATCH: &str = "The values at the index are not equal";
+pub(crate) const ERR_FORBIDDEN_VALUE: &str = "The value at the index is the forbidden value";
+pub(crate) const ERR_CONFLICTING_VALUE: &str = "The same index appears with different values";
+
+/// Errors returned by the pointproofs APIs.
+#[derive(Debug)]
+pub enum PointproofsError {
+    /// The seed is shorter than 32 bytes.
+    SeedTooShort,
+    /// The ciphersuite is not supported, or the ciphersuites do not match.
+    InvalidCiphersuite,
+    /// The number of values, or the n recorded in a commitment or a proof,
+    /// does not match the parameter n.
+    MismatchedN,
+    /// More values than the parameter n.
+    TooManyValues {
+        /// the number of values
+        got: usize,
+        /// the parameter n
+        max: usize,
+    },
+    /// A value is longer than the maximum value length.
+    ValueTooLong {
+        /// the index of the value
+        index: usize,
+        /// the length of the value
+        len: usize,
+        /// the maximum value length
+        max: usize,
+    },
+    /// An index is not within [0, n), or the number of indices is invalid.
+    IndexOutOfRange,
+    /// The same index appears more than once.
+    DuplicatedIndex,
+    /// The set of indices to aggregate is empty.
+    EmptySet,
+    /// The index range [lo, hi) is empty.
+    EmptyRange,
+    /// The index range [lo, hi) is not within [0, n].
+    InvalidRange,
+    /// The lengths of the indices and the proofs do not match.
+    IndexProofMismatch,
+    /// The lengths of the indices and the values do not match.
+    IndexValueMismatch,
+    /// The lengths of the commitments, proofs or values
+    /// for cross commitment aggregation do not match.
+    CrossCommitSizeMismatch,
+    /// The parameter n is too large.
+    NTooLarge,
+    /// The parameter n is 0.
+    InvalidN,
+    /// A scalar is not below the field modulus.
+    NonCanonicalScalar,
+    /// The pre-computation window is not supported.
+    InvalidPrecompWindow,
+    /// The pre-computed table would take more memory than the budget.
+    PrecompTooLarge {
+        /// the estimated size of the table, in bytes
+        estimated: usize,
+        /// the budget, in bytes
+        max: usize,
+    },
+    /// The parameters are not valid.
+    InvalidParams,
+    /// The random number generator is not available.
+    RngUnavailable,
+    /// The revealed value does not match the committed hash.
+    HashMismatch,
+    /// The tweak is too long.
+    InvalidTweak,
+    /// The changes do not turn one commitment into the other.
+    DeltaMismatch,
+    /// The values at the index are not equal.
+    ValueMismatch,
+    /// The value at the index is the forbidden value.
+    ForbiddenValue,
+    /// An index appears more than once, with different values.
+    ConflictingValue,
+    /// A deserialized point is on the curve, but not in the prime order subgroup.
+    SubgroupCheckFailed,
+    /// The bytes given to a `from_slice` parser are not a valid encoding;
+    /// the message says which check failed.
+    InvalidEncoding(&'static str),
+    /// Serialization or deserialization failed.
+    #[cfg(feature = "std")]
+    Serde(std::io::Error),
+}
+
+impl core::fmt::Display for PointproofsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            PointproofsError::SeedTooShort => write!(f, "{}", ERR_SEED_TOO_SHORT),
+            PointproofsError::InvalidCiphersuite => write!(f, "{}", ERR_CIPHERSUITE),
+            PointproofsError::MismatchedN => write!(f, "{}", ERR_MISMATCHED_N),
+            PointproofsError::TooManyValues { got, max } => {
+                write!(f, "{}: got {}, max {}", ERR_TOO_MANY_VALUES, got, max)
+            }
+            PointproofsError::ValueTooLong { index, len, max } => write!(
+                f,
+                "{}: index {}, len {}, max {}",
+                ERR_VALUE_TOO_LONG, index, len, max
+            ),
+            PointproofsError::IndexOutOfRange => write!(f, "{}", ERR_INVALID_INDEX),
+            PointproofsError::DuplicatedIndex => write!(f, "{}", ERR_DUPLICATED_INDEX),
+            PointproofsError::EmptySet => write!(f, "{}", ERR_EMPTY_SET),
+            PointproofsError::EmptyRange => write!(f, "{}", ERR_EMPTY_RANGE),
+            PointproofsError::InvalidRange => write!(f, "{}", ERR_INVALID_RANGE),
+            PointproofsError::IndexProofMismatch => write!(f, "{}", ERR_INDEX_PROOF_NOT_MATCH),
+            PointproofsError::IndexValueMismatch => write!(f, "{}", ERR_INDEX_VALUE_NOT_MATCH),
+            PointproofsError::CrossCommitSizeMismatch => write!(f, "{}", ERR_X_COM_SIZE),
+            PointproofsError::NTooLarge => write!(f, "{}", ERR_MAX_N),
+            PointproofsError::InvalidN => write!(f, "{}", ERR_ZERO_N),
+            PointproofsError::NonCanonicalScalar => write!(f, "{}", ERR_NON_CANONICAL),
+            PointproofsError::InvalidPrecompWindow => write!(f, "{}", ERR_PRECOMP_WINDOW),
+            PointproofsError::PrecompTooLarge { estimated, max } => write!(
+                f,
+                "{}: estimated {} bytes, budget {} bytes",
+                ERR_PRECOMP_TOO_LARGE, estimated, max
+            ),
+            PointproofsError::InvalidParams => write!(f, "{}", ERR_INCONSISTENT_PARAM),
+            PointproofsError::RngUnavailable => write!(f, "{}", ERR_RNG),
+            PointproofsError::HashMismatch => write!(f, "{}", ERR_HASH_MISMATCH),
+            PointproofsError::InvalidTweak => write!(f, "{}", ERR_TWEAK),
+            PointproofsError::DeltaMismatch => write!(f, "{}", ERR_DELTA),
+            PointproofsError::ValueMismatch => write!(f, "{}", ERR_VALUE_MISMATCH),
+            PointproofsError::ForbiddenValue => write!(f, "{}", ERR_FORBIDDEN_VALUE),
+            PointproofsError::ConflictingValue => write!(f, "{}", ERR_CONFLICTING_VALUE),
+            PointproofsError::SubgroupCheckFailed => write!(f, "{}", ERR_SUBGROUP),
+            PointproofsError::InvalidEncoding(msg) => write!(f, "{}", msg),
+            #[cfg(feature = "std")]
+            PointproofsError::Serde(e) => write!(f, "Serialization failed: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PointproofsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PointproofsError::Serde(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for PointproofsError {
+    fn from(e: std::io::Error) -> Self {
+        // the point readers report a failed subgroup check
+        // with a SubgroupCheckFailed payload
+        let subgroup = match e.get_ref() {
+            Some(inner) => match inner.downcast_ref::<PointproofsError>() {
+                Some(PointproofsError::SubgroupCheckFailed) => true,
+                _ => false,
+            },
+            None => false,
+        };
+        if subgroup {
+            PointproofsError::SubgroupCheckFailed
+        } else {
+            PointproofsError::Serde(e)
+        }
+    }
+}
+
+/// The check that failed when verifying a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyFailure {
+    /// The ciphersuites of the proof, the commitment and the parameters do not match.
+    CiphersuiteMismatch,
+    /// The ciphersuite is not supported.
+    InvalidCiphersuite,
+    /// The index is not within [0, n).
+    IndexOutOfRange,
+    /// The commitment or the proof was made with parameters for another n.
+    MismatchedN,
+    /// The commitment is the identity element.
+    IdentityCommitment,
+    /// The pairing equation does not hold: the proof is not valid
+    /// for this commitment, value and index.
+    PairingCheck,
+    /// The commitment bytes could not be deserialized.
+    InvalidEncoding,
+    /// The commitment does not have the expected fingerprint.
+    FingerprintMismatch,
+    /// The value is longer than the maximum value length of the parameters.
+    ValueTooLong,
+}
+
+impl core::fmt::Display for VerifyFailure {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            VerifyFailure::CiphersuiteMismatch => write!(f, "{}", ERR_CIPHERSUITE),
+            VerifyFailure::InvalidCiphersuite => write!(f, "{}", ERR_CIPHERSUITE),
+            VerifyFailure::IndexOutOfRange => write!(f, "{}", ERR_INVALID_INDEX),
+            VerifyFailure::MismatchedN => write!(f, "{}", ERR_MISMATCHED_N),
+            VerifyFailure::IdentityCommitment => write!(f, "{}", ERR_IDENTITY_COMMIT),
+            VerifyFailure::PairingCheck => write!(f, "{}", ERR_PAIRING_CHECK),
+            VerifyFailure::InvalidEncoding => write!(f, "{}", ERR_COMMIT_ENCODING),
+            VerifyFailure::FingerprintMismatch => write!(f, "{}", ERR_FINGERPRINT),
+            VerifyFailure::ValueTooLong => write!(f, "{}", ERR_VALUE_TOO_LONG),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyFailure {}
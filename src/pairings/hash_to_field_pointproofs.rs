@@ -2,7 +2,10 @@
 //! It defines the hash_to_field functions that are more efficient than `bls::hash_to_field`
 //! The algorithms are described here:
 //! https://github.com/algorand/pointproofs/blob/master/SPEC.md#hashes
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use bigint::U512;
+use blake2::Blake2b;
 use ff::PrimeField;
 use pairing_plus::bls12_381::*;
 use pairing_plus::serdes::SerDes;
@@ -10,19 +13,21 @@ use pairings::err::*;
 use pairings::param::*;
 use pairings::Commitment;
 use sha2::{Digest, Sha512};
-use std::ops::Rem;
+use sha3::Sha3_512;
+use zeroize::Zeroize;
 
 // A wrapper of `hash_to_tj_repr` that outputs `Fr`s instead of `FrRepr`s.
 /// * input: a list of k commitments
 /// * input: a list of k * x indices, for which we need to generate t_j
 /// * input: Value: a list of k * x messages that is commited to
 /// * output: a list of k field elements
+#[cfg(feature = "std")]
 pub(crate) fn hash_to_tj_fr<Blob: AsRef<[u8]>>(
     commits: &[Commitment],
     set: &[Vec<usize>],
     value_sub_vector: &[Vec<Blob>],
     n: usize,
-) -> Result<Vec<Fr>, String> {
+) -> Result<Vec<Fr>, PointproofsError> {
     Ok(hash_to_tj_repr(commits, set, value_sub_vector, n)?
         .iter()
         // the hash_to_ti_repr should already produce valid Fr elements
@@ -37,21 +42,22 @@ pub(crate) fn hash_to_tj_fr<Blob: AsRef<[u8]>>(
 /// * input: a list of k * x indices, for which we need to generate t_j
 /// * input: Value: a list of k * x messages that is commited to
 /// * output: a list of k field elements
+#[cfg(feature = "std")]
 pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
     commits: &[Commitment],
     set: &[Vec<usize>],
     value_sub_vector: &[Vec<Blob>],
     n: usize,
-) -> Result<Vec<FrRepr>, String> {
+) -> Result<Vec<FrRepr>, PointproofsError> {
     // check the length are correct
     if commits.len() != set.len() || commits.len() != value_sub_vector.len() {
-        return Err(ERR_X_COM_SIZE.to_owned());
+        return Err(PointproofsError::CrossCommitSizeMismatch);
     };
 
     // check the ciphersuite is supported
     for e in commits {
         if !check_ciphersuite(e.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
     }
 
@@ -67,7 +73,7 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
         // serialize commitment
         match commits[i].serialize(&mut tmp, true) {
             Ok(_p) => _p,
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(PointproofsError::Serde(e)),
         };
         // add the set to tmp
         for j in 0..set[i].len() {
@@ -77,13 +83,13 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
 
         // if the set leng does not mathc values, return an error
         if set[i].len() != value_sub_vector[i].len() {
-            return Err(ERR_INDEX_PROOF_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexProofMismatch);
         }
 
         // add values to set; returns an error if index is out of range
         for j in 0..set[i].len() {
             if set[i][j] >= n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::IndexOutOfRange);
             }
             let t = value_sub_vector[i][j].as_ref();
             tmp.append(&mut t.to_vec());
@@ -109,12 +115,13 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
 // * input: a list of indices, for which we need to generate t_i
 // * input: Value: the messages that is commited to
 // * output: a list of field elements
+#[cfg(feature = "std")]
 pub(crate) fn hash_to_ti_fr<Blob: AsRef<[u8]>>(
     commit: &Commitment,
     set: &[usize],
     value_sub_vector: &[Blob],
     n: usize,
-) -> Result<Vec<Fr>, String> {
+) -> Result<Vec<Fr>, PointproofsError> {
     Ok(hash_to_ti_repr(commit, set, value_sub_vector, n)?
         .iter()
         // the hash_to_ti_repr should already produce valid Fr elements
@@ -129,18 +136,19 @@ pub(crate) fn hash_to_ti_fr<Blob: AsRef<[u8]>>(
 /// * input: a list of indices, for which we need to generate t_i
 /// * input: Value: the messages that is commited to
 /// * output: a list of field elements
+#[cfg(feature = "std")]
 pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
     commit: &Commitment,
     set: &[usize],
     value_sub_vector: &[Blob],
     n: usize,
-) -> Result<Vec<FrRepr>, String> {
+) -> Result<Vec<FrRepr>, PointproofsError> {
     if !check_ciphersuite(commit.ciphersuite) {
-        return Err(ERR_CIPHERSUITE.to_owned());
+        return Err(PointproofsError::InvalidCiphersuite);
     }
     // if the set leng does not mathc values, return an error
     if set.len() != value_sub_vector.len() {
-        return Err(ERR_INVALID_INDEX.to_owned());
+        return Err(PointproofsError::IndexValueMismatch);
     }
 
     // handle the case where there is only one input
@@ -152,7 +160,7 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
     // add values to set; returns an error if index is out of range
     for e in set {
         if *e >= n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         }
     }
 
@@ -161,7 +169,7 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
     // serialize commitment
     match commit.serialize(&mut tmp, true) {
         Ok(_p) => _p,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(PointproofsError::Serde(e)),
     };
     // add the set to tmp
     for index in set {
@@ -188,19 +196,124 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
 }
 
 /// A wrapper of `hash_to_field` that outputs `Fr`s instead of `FrRepr`s.
-/// hash_to_field_pointproofs use SHA 512 to hash a blob into a non-zero field element
-pub(crate) fn hash_to_field_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> Fr {
+/// hash_to_field_pointproofs use SHA 512 to hash a blob into a non-zero field element:
+///
+/// `t = OS2IP(SHA-512(input)) mod r`, or `t = 1` if this is 0,
+///
+/// where OS2IP reads the 64 bytes digest as a big-endian integer
+/// and r is the order of the BLS12-381 groups.
+/// This is the hash that maps values to the scalars being committed,
+/// and its output is stable across versions.
+///
+/// The input may have any length, including 0: it is only ever fed to
+/// SHA-512, so values longer than the field are not truncated, and two
+/// distinct values collide only if SHA-512 does (or with probability
+/// about 2^-255 after the reduction).
+/// Reducing a 512 bits digest mod the 255 bits r gives a distribution
+/// within statistical distance about 2^-257 of uniform, and the 0 to 1
+/// remapping only happens with probability about 2^-255.
+pub fn hash_to_field_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> Fr {
     // the hash_to_field_repr_pointproofs should already produce a valid Fr element
     // so it is safe to unwrap here
     Fr::from_repr(hash_to_field_repr_pointproofs(input.as_ref())).unwrap()
 }
 
+/// Hashes a blob into a non-zero field element under a domain separation tag:
+///
+/// `hash_to_field_pointproofs(I2OSP(len(dst), 8) | dst | input)`,
+///
+/// i.e., the tag is prefixed to the input together with its length
+/// as a 64 bits big-endian integer, so that (dst, input) is unambiguous.
+/// The length is prefixed for an empty tag too, so the empty tag is
+/// not ambiguous with any other one; `hash_to_field_pointproofs(input)`
+/// is kept apart as the legacy hash without a tag.
+pub fn hash_to_field_pointproofs_with_dst<Blob: AsRef<[u8]>>(dst: &[u8], input: Blob) -> Fr {
+    let mut tmp = (dst.len() as u64).to_be_bytes().to_vec();
+    tmp.extend_from_slice(dst);
+    tmp.extend_from_slice(input.as_ref());
+    let res = hash_to_field_pointproofs(&tmp);
+    tmp.zeroize();
+    res
+}
+
+/// Hashes a blob into count independent non-zero field elements.
+/// * input: the blob
+/// * input: the number of field elements
+/// * output: a list of count field elements
+///
+/// The i-th element is `hash_to_field_pointproofs(I2OSP(i, 8) | input)`,
+/// i.e., the counter i is prefixed to the input as a 64 bits big-endian integer.
+/// As in `hash_to_field_pointproofs`, the output is stable across versions.
+pub fn hash_to_field_pointproofs_n<Blob: AsRef<[u8]>>(input: Blob, count: usize) -> Vec<Fr> {
+    (0..count)
+        .map(|i| {
+            hash_to_field_pointproofs([&(i as u64).to_be_bytes()[..], input.as_ref()].concat())
+        })
+        .collect()
+}
+
 /// Hashes a blob into a non-zero field element.
 /// hash_to_field_pointproofs use SHA 512 to hash a blob into a non-zero field element.
 pub(crate) fn hash_to_field_repr_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> FrRepr {
-    let mut hasher = Sha512::new();
-    hasher.input(input);
-    let hash_output = hasher.result();
+    hash_to_field_repr_ciphersuite(0, input)
+}
+
+/// Hashes a blob into a non-zero field element, with the hash function
+/// of the ciphersuite, see `hash_to_field_with_ciphersuite`.
+/// The ciphersuite must be valid; an invalid one is hashed as 0.
+pub(crate) fn hash_to_field_ciphersuite<Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    input: Blob,
+) -> Fr {
+    // the hash_to_field_repr_ciphersuite should already produce a valid Fr element
+    // so it is safe to unwrap here
+    Fr::from_repr(hash_to_field_repr_ciphersuite(ciphersuite, input)).unwrap()
+}
+
+/// Hashes a blob into a non-zero field element, with the hash function
+/// of the ciphersuite:
+///
+/// `t = OS2IP(H(input)) mod r`, or `t = 1` if this is 0,
+///
+/// where H is SHA-512 for ciphersuite 0, SHA3-512 for ciphersuite 1
+/// and BLAKE2b-512 for ciphersuite 2.
+/// All three have 64 bytes outputs, so that the reduction mod r is
+/// close to uniform; ciphersuite 0 is `hash_to_field_pointproofs`.
+/// Returns an error if the ciphersuite is not valid.
+pub fn hash_to_field_with_ciphersuite<Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    input: Blob,
+) -> Result<Fr, PointproofsError> {
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+    Ok(hash_to_field_ciphersuite(ciphersuite, input))
+}
+
+/// Hashes a blob into a non-zero field element, with the hash function
+/// of the ciphersuite, see `hash_to_field_with_ciphersuite`.
+pub(crate) fn hash_to_field_repr_ciphersuite<Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    input: Blob,
+) -> FrRepr {
+    let mut hash_output = [0u8; 64];
+    match ciphersuite {
+        1 => {
+            let mut hasher = Sha3_512::new();
+            hasher.input(input);
+            hash_output.copy_from_slice(&hasher.result());
+        }
+        2 => {
+            let mut hasher = Blake2b::new();
+            hasher.input(input);
+            hash_output.copy_from_slice(&hasher.result());
+        }
+        _ => {
+            let mut hasher = Sha512::new();
+            hasher.input(input);
+            hash_output.copy_from_slice(&hasher.result());
+        }
+    }
     let mut t = os2ip_mod_p(&hash_output);
 
     // if we get 0, return 1
@@ -245,7 +358,7 @@ pub(crate) fn os2ip_mod_p(oct_str: &[u8]) -> FrRepr {
         0x00, 0x00, 0x01,
     ]);
     // t = r % p
-    let t_sec = r_sec.rem(p);
+    let t_sec = r_sec % p;
 
     // convert t from a U512 into a primefield object s
     let mut tslide: [u8; 64] = [0; 64];
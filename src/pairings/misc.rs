@@ -1,13 +1,20 @@
 //! this file is part of the pointproofs.
 //! It defines some misc functions.
 
-use pairing_plus::CurveAffine;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use ff::{Field, PrimeField};
+use pairing_plus::{CurveAffine, CurveProjective};
 use pairings::err::ERR_PARAM;
 use pairings::*;
-use std::collections::HashSet;
+use sha2::{Digest, Sha512};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::hash::Hash;
 
 /// checks if a slice/vector constains duplicated elements
+#[cfg(feature = "std")]
 pub(crate) fn has_unique_elements<T>(iter: T) -> bool
 where
     T: IntoIterator,
@@ -17,6 +24,109 @@ where
     iter.into_iter().all(move |x| uniq.insert(x))
 }
 
+/// collapses the repeated indices of a list of openings:
+/// an index that appears again with the same value is dropped,
+/// and one that appears again with another value is an error.
+/// Returns the remaining indices and values, and their positions in the input.
+#[cfg(feature = "std")]
+pub(crate) fn dedup_openings<'a, Blob: AsRef<[u8]>>(
+    set: &[usize],
+    value_sub_vector: &'a [Blob],
+) -> Result<(Vec<usize>, Vec<&'a [u8]>, Vec<usize>), PointproofsError> {
+    if set.len() != value_sub_vector.len() {
+        return Err(PointproofsError::IndexValueMismatch);
+    }
+    let mut first: HashMap<usize, &[u8]> = HashMap::new();
+    let mut indices = Vec::with_capacity(set.len());
+    let mut values = Vec::with_capacity(set.len());
+    let mut positions = Vec::with_capacity(set.len());
+    for (k, (index, value)) in set.iter().zip(value_sub_vector.iter()).enumerate() {
+        let value = value.as_ref();
+        match first.get(index) {
+            Some(v) if ct_eq(v, value) => continue,
+            Some(_) => return Err(PointproofsError::ConflictingValue),
+            None => (),
+        }
+        first.insert(*index, value);
+        indices.push(*index);
+        values.push(value);
+        positions.push(k);
+    }
+    Ok((indices, values, positions))
+}
+
+/// compares two byte strings in time that depends on their lengths only
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// checks that the n recorded in a commitment or a proof is the n
+/// of the parameters; an n of 0 is unknown and matches any n
+pub(crate) fn n_matches(recorded: usize, n: usize) -> bool {
+    recorded == 0 || recorded == n
+}
+
+/// checks that no value is longer than max_value_len bytes
+pub(crate) fn check_value_lens<Blob: AsRef<[u8]>>(
+    values: &[Blob],
+    max_value_len: usize,
+) -> Result<(), PointproofsError> {
+    for (index, value) in values.iter().enumerate() {
+        check_value_len(index, value.as_ref(), max_value_len)?;
+    }
+    Ok(())
+}
+
+/// checks that the value at index is not longer than max_value_len bytes
+pub(crate) fn check_value_len(
+    index: usize,
+    value: &[u8],
+    max_value_len: usize,
+) -> Result<(), PointproofsError> {
+    if value.len() > max_value_len {
+        return Err(PointproofsError::ValueTooLong {
+            index,
+            len: value.len(),
+            max: max_value_len,
+        });
+    }
+    Ok(())
+}
+
+/// checks that [lo, hi) is a non-empty range of indices below n
+pub(crate) fn check_range(n: usize, lo: usize, hi: usize) -> Result<(), PointproofsError> {
+    if lo >= hi {
+        return Err(PointproofsError::EmptyRange);
+    }
+    if hi > n {
+        return Err(PointproofsError::InvalidRange);
+    }
+    Ok(())
+}
+
+/// a short digest of some data, for Debug output:
+/// the first 8 bytes of SHA-512, in hex;
+/// this is not the `fingerprint` of the parameters
+pub(crate) fn short_digest(data: &[u8]) -> String {
+    let mut hasher = Sha512::new();
+    hasher.input(data);
+    hasher.result()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// checks if every repr is the canonical form of a field element, i.e. is below the modulus
+pub(crate) fn is_canonical_repr_slice(reprs: &[FrRepr]) -> bool {
+    reprs.iter().all(|r| Fr::from_repr(*r).is_ok())
+}
+
 /// This helper computes the sum of product:
 ///     \sum_{i=start}^{end-1}
 ///         param.generator[i]^scarlar_u64[i]
@@ -38,7 +148,7 @@ pub(crate) fn pp_sum_of_prod_helper(
             &prover_params.precomp[start * 256..end * 256],
         )
     } else {
-        PointproofsG1Affine::sum_of_products(&prover_params.generators[start..end], &scalars_u64)
+        multiexp_g1(&prover_params.generators[start..end], &scalars_u64)
     }
 }
 
@@ -57,8 +167,191 @@ pub(crate) fn pp_single_exp_helper(
             scalar,
             &prover_params.precomp[index * 256..(index + 1) * 256],
         )
-    } else {
-        assert_eq!(prover_params.precomp.len(), 0, "{}", ERR_PARAM);
+    } else if prover_params.precomp.is_empty() {
         prover_params.generators[index].mul(scalar)
+    } else {
+        // a table built by `precomp_window` with some other window
+        let window = prover_params.precomp.len() / prover_params.generators.len();
+        assert_eq!(
+            window * prover_params.generators.len(),
+            prover_params.precomp.len(),
+            "{}",
+            ERR_PARAM
+        );
+        mul_precomp_window(
+            scalar,
+            window,
+            &prover_params.precomp[index * window..(index + 1) * window],
+        )
+    }
+}
+
+/// The layout of a generic fixed-base table with `window` entries:
+/// returns (bits, rounds), where the 256 bits of the scalar are cut into
+/// digits of `bits` bits, and the digits are processed in `rounds` rounds.
+/// It uses the widest digits that fit in a single round;
+/// if even 1-bit digits do not fit, it uses several rounds.
+fn precomp_window_layout(window: usize) -> (usize, usize) {
+    for bits in (1..=8).rev() {
+        let digits = (256 + bits - 1) / bits;
+        if digits * ((1 << bits) - 1) <= window {
+            return (bits, 1);
+        }
+    }
+    (1, (256 + window - 1) / window)
+}
+
+/// Builds a generic fixed-base table of `window` entries for `base`:
+///     table[j * (2^bits - 1) + v - 1] = base^{v * 2^{j * bits * rounds}}
+/// for digit j and value v in [1, 2^bits); the remaining entries are 0.
+pub(crate) fn precomp_window_table(
+    base: &PointproofsG1Affine,
+    window: usize,
+    table: &mut [PointproofsG1Affine],
+) {
+    let (bits, rounds) = precomp_window_layout(window);
+    let digits = (256 + bits * rounds - 1) / (bits * rounds);
+    let values = (1 << bits) - 1;
+
+    let mut entries: Vec<PointproofsG1> = Vec::with_capacity(digits * values);
+    let mut digit_base = base.into_projective();
+    for _ in 0..digits {
+        // base^{v * 2^{j * bits * rounds}} for v in [1, 2^bits)
+        let mut tmp = digit_base;
+        for _ in 0..values {
+            entries.push(tmp);
+            tmp.add_assign(&digit_base);
+        }
+        // move on to the base of the next digit
+        for _ in 0..bits * rounds {
+            digit_base.double();
+        }
+    }
+    CurveProjective::batch_normalization(&mut entries);
+    for (i, e) in table.iter_mut().enumerate() {
+        *e = match entries.get(i) {
+            Some(p) => p.into_affine(),
+            None => PointproofsG1Affine::zero(),
+        };
+    }
+}
+
+/// Computes base^scalar with a table from `precomp_window_table`.
+pub(crate) fn mul_precomp_window(
+    scalar: Fr,
+    window: usize,
+    table: &[PointproofsG1Affine],
+) -> PointproofsG1 {
+    let (bits, rounds) = precomp_window_layout(window);
+    let digits = (256 + bits * rounds - 1) / (bits * rounds);
+    let values = (1 << bits) - 1;
+    let repr = scalar.into_repr();
+    let bit = |i: usize| -> usize {
+        if i >= 256 {
+            0
+        } else {
+            ((repr.0[i / 64] >> (i % 64)) & 1) as usize
+        }
+    };
+
+    let mut res = PointproofsG1::zero();
+    for r in (0..rounds).rev() {
+        for _ in 0..bits {
+            res.double();
+        }
+        for j in 0..digits {
+            // digit r of the j-th chunk of bits * rounds bits
+            let pos = (j * rounds + r) * bits;
+            let v = (0..bits).fold(0, |acc, t| acc | (bit(pos + t) << t));
+            if v != 0 {
+                res.add_assign_mixed(&table[j * values + v - 1]);
+            }
+        }
+    }
+    res
+}
+
+/// the elements an FFT can run over: a vector space over Fr
+pub(crate) trait FftElement: Copy {
+    fn fft_add_assign(&mut self, other: &Self);
+    fn fft_sub_assign(&mut self, other: &Self);
+    fn fft_mul_assign(&mut self, scalar: &Fr);
+}
+
+impl FftElement for Fr {
+    fn fft_add_assign(&mut self, other: &Self) {
+        self.add_assign(other);
+    }
+    fn fft_sub_assign(&mut self, other: &Self) {
+        self.sub_assign(other);
+    }
+    fn fft_mul_assign(&mut self, scalar: &Fr) {
+        self.mul_assign(scalar);
+    }
+}
+
+impl FftElement for PointproofsG1 {
+    fn fft_add_assign(&mut self, other: &Self) {
+        self.add_assign(other);
+    }
+    fn fft_sub_assign(&mut self, other: &Self) {
+        self.sub_assign(other);
+    }
+    fn fft_mul_assign(&mut self, scalar: &Fr) {
+        self.mul_assign(*scalar);
+    }
+}
+
+/// returns a primitive root of unity of order 2^log_size
+pub(crate) fn root_of_unity(log_size: u32) -> Fr {
+    assert!(log_size <= Fr::S, "{}", ERR_PARAM);
+    let mut omega = Fr::root_of_unity();
+    for _ in log_size..Fr::S {
+        omega.square();
+    }
+    omega
+}
+
+/// In place radix-2 FFT: a[k] becomes \sum_j a[j] * omega^{jk}.
+/// The length of `a` must be a power of 2 and omega a root of unity of that order.
+pub(crate) fn fft<T: FftElement>(a: &mut [T], omega: &Fr) {
+    let size = a.len();
+    assert!(size.is_power_of_two(), "{}", ERR_PARAM);
+    let log_size = size.trailing_zeros();
+
+    // bit reversal permutation
+    for k in 0..size {
+        let rk = if log_size == 0 {
+            0
+        } else {
+            k.reverse_bits() >> (usize::max_value().count_ones() - log_size)
+        };
+        if k < rk {
+            a.swap(k, rk);
+        }
+    }
+
+    let mut m = 1;
+    while m < size {
+        // w_m is a root of unity of order 2m
+        let mut w_m = *omega;
+        for _ in 0..(size / (2 * m)).trailing_zeros() {
+            w_m.square();
+        }
+        for k in (0..size).step_by(2 * m) {
+            let mut w = Fr::one();
+            for j in 0..m {
+                let mut t = a[k + j + m];
+                // the first twiddle factor is always one
+                if j != 0 {
+                    t.fft_mul_assign(&w);
+                }
+                a[k + j + m] = a[k + j];
+                a[k + j + m].fft_sub_assign(&t);
+                a[k + j].fft_add_assign(&t);
+                w.mul_assign(&w_m);
+            }
+        }
+        m *= 2;
     }
 }
@@ -4,19 +4,42 @@
 use self::param::Ciphersuite;
 use self::pointproofs_groups::*;
 use pairing_plus::bls12_381::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "std")]
+pub use self::envelope::{VersionedSerDes, ENVELOPE_MAGIC, ENVELOPE_VERSION};
+#[cfg(feature = "std")]
+pub use self::equality::{verify_equal, verify_not_equal};
+pub use self::err::{PointproofsError, VerifyFailure};
+pub use self::hash_to_field_pointproofs::{
+    hash_to_field_pointproofs, hash_to_field_pointproofs_n, hash_to_field_pointproofs_with_dst,
+    hash_to_field_with_ciphersuite,
+};
+pub use self::pointproofs_groups::{multi_pairing_check, multiexp_g1, multiexp_g2};
+pub use self::verify::{UNCOMPRESSED_POINT_FLAG, WITH_N_FLAG};
 
 /// Structure for porver parameters.
-#[derive(Clone, Debug)]
+/// Debug prints n, the ciphersuite and a digest; see `debug_full`.
+#[derive(Clone)]
 pub struct ProverParams {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) n: usize,
     generators: Vec<PointproofsG1Affine>,
     pp_len: usize,
     precomp: Vec<PointproofsG1Affine>,
+    max_value_len: usize,
 }
 
 /// Structure for verifier parameters.
-#[derive(Clone, Debug)]
+/// They cannot be derived from the prover parameters:
+/// the G2 generators g2^{alpha^i} are not computable from G1 points
+/// without alpha, so both sets have to be kept (see `read_param`);
+/// `write_verifier_param` extracts them for a verify-only node.
+/// Debug prints n, the ciphersuite and a digest; see `debug_full`.
+#[derive(Clone)]
 pub struct VerifierParams {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) n: usize,
@@ -24,31 +47,206 @@ pub struct VerifierParams {
     pp_len: usize,
     precomp: Vec<PointproofsG2Affine>,
     gt_elt: Fq12,
+    prepared: Vec<PointproofsG2Prepared>,
+    max_value_len: usize,
 }
 
 /// Structure to hold a commitment.
-#[derive(Clone, Debug, PartialEq)]
+/// It remembers the n of the parameters it was built with, so that
+/// updating or verifying it with parameters for another n fails;
+/// n is only serialized by `Commitment::serialize_with_n`. A commitment
+/// of unknown n takes the n of the parameters of its first update.
+/// Two commitments are equal if their ciphersuites and points are.
+#[derive(Clone, Debug)]
 pub struct Commitment {
     pub(crate) ciphersuite: Ciphersuite,
+    /// the n of the parameters, 0 if unknown (e.g., after `deserialize`
+    /// of a blob without n)
+    pub(crate) n: usize,
     pub(crate) commit: PointproofsG1,
 }
 
 /// Structure to hold a proof.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// A proof is deterministic: for a given commitment, index and value,
+/// the verification equation e(com, g2^{alpha^{N+1-index}}) =
+/// e(proof, g2) * e(g1, g2)^{alpha^{N+1} * hash(value)} has a single
+/// solution, so a valid proof cannot be rerandomized, and two valid
+/// proofs for the same opening are always equal. Unlinkable openings
+/// need a fresh commitment instead, e.g., `Commitment::new_salted`.
+///
+/// Like a `Commitment`, a proof remembers the n of its parameters,
+/// only serializes it with `Proof::serialize_with_n`, and does not
+/// compare it.
+#[derive(Clone, Debug)]
 pub struct Proof {
+    pub(crate) ciphersuite: Ciphersuite,
+    /// the n of the parameters, 0 if unknown (e.g., after `deserialize`
+    /// of a blob without n)
+    pub(crate) n: usize,
+    pub(crate) proof: PointproofsG1,
+}
+
+/// The operations performed to verify a proof;
+/// see `Proof::verify_cost_estimate` and `Proof::verify_with_cost`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerifyCost {
+    /// the number of pairings (Miller loops) in the pairing product
+    pub pairings: usize,
+    /// the number of scalar multiplications in G1
+    pub g1_muls: usize,
+    /// the number of multi-exponentiations in G2
+    pub g2_multiexps: usize,
+    /// the total number of bases of those multi-exponentiations
+    pub g2_multiexp_bases: usize,
+}
+
+/// Structure to hold a commitment over the most recent n entries of a stream.
+/// The entry at stream position p lives at index p % n.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlidingCommitment {
+    pub(crate) commitment: Commitment,
+    pub(crate) values: Vec<Vec<u8>>,
+    pub(crate) pushed: usize,
+}
+
+/// Structure to collect the values of a commitment one at a time;
+/// see `CommitmentBuilder::with_capacity`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommitmentBuilder {
+    pub(crate) n: usize,
+    pub(crate) max_value_len: Option<usize>,
+    pub(crate) values: Vec<Vec<u8>>,
+}
+
+/// Structure to hold a list of changes prepared for updating many proofs;
+/// see `Proof::plan_updates`.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct UpdatePlan<'a> {
+    pub(crate) prover_params: &'a ProverParams,
+    pub(crate) changes: Vec<(usize, FrRepr)>,
+}
+
+/// Structure to hold the changes from one commitment to another,
+/// as (index, hash(new value) - hash(old value)); see `Commitment::delta`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommitmentDelta {
+    pub(crate) ciphersuite: Ciphersuite,
+    pub(crate) changes: Vec<(usize, FrRepr)>,
+}
+
+/// Structure to hold a proof that two commitments
+/// have the same value at an index; see `Proof::prove_equal`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EqualityProof {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) proof: PointproofsG1,
 }
 
+/// Structure to hold a proof that the value at an index
+/// is not a given value; see `Proof::prove_not_equal`.
+/// It is not zero-knowledge: it reveals hash(value) - hash(forbidden value).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NotEqualProof {
+    pub(crate) ciphersuite: Ciphersuite,
+    pub(crate) proof: PointproofsG1,
+    pub(crate) difference: FrRepr,
+}
+
+/// Structure to hold a single change, as (index, hash(new value) - hash(old value)),
+/// without the values themselves; see `Commitment::update_with_witness`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpdateWitness {
+    pub(crate) ciphersuite: Ciphersuite,
+    pub(crate) index: usize,
+    pub(crate) multiplier: FrRepr,
+}
+
+/// Default bound on the length of a single value, in bytes: 64 MiB.
+/// Longer values are rejected before they are hashed;
+/// see `ProverParams::set_max_value_len`.
+pub const DEFAULT_MAX_VALUE_LEN: usize = 1 << 26;
+
+/// Size of the salt of a `SaltedCommitment`.
+pub const SALT_LEN: usize = 32;
+
+/// Structure to hold a commitment where every value is hashed
+/// together with a random per-commitment salt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaltedCommitment {
+    pub(crate) commitment: Commitment,
+    pub(crate) salt: [u8; SALT_LEN],
+}
+
+/// Size of a value hash of a `HashCommitment`.
+pub const VALUE_HASH_LEN: usize = 32;
+
+/// Structure to hold a commitment to the hashes of the values,
+/// so that the values can be revealed later.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HashCommitment {
+    pub(crate) commitment: Commitment,
+}
+
+/// Identifies a set of prover parameters within a `ParamsStore`,
+/// see `ProverParams::fingerprint`.
+pub type ParamsFingerprint = [u8; 32];
+
+/// Identifies a commitment, see `Commitment::fingerprint`.
+pub type CommitmentFingerprint = [u8; 32];
+
+/// Structure to hold many sets of prover parameters,
+/// where each distinct point is stored only once.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct ParamsStore {
+    pub(crate) points: Vec<PointproofsG1Affine>,
+    pub(crate) point_ids: HashMap<Vec<u8>, usize>,
+    pub(crate) params: HashMap<ParamsFingerprint, (Ciphersuite, usize, Vec<usize>)>,
+}
+
+// without std, only param (in part), verify and the modules they use are built
+#[cfg(feature = "std")]
+pub(crate) mod builder;
+#[cfg(feature = "std")]
 pub(crate) mod commit;
+#[cfg(feature = "std")]
+pub(crate) mod compat;
+#[cfg(feature = "std")]
+pub(crate) mod debug;
+#[cfg(feature = "std")]
+pub(crate) mod delta;
+#[cfg(feature = "std")]
+pub(crate) mod envelope;
+#[cfg(feature = "std")]
+pub(crate) mod equality;
+#[cfg(feature = "std")]
+pub(crate) mod optional;
 pub mod param;
+#[cfg(feature = "std")]
 pub(crate) mod prove;
+#[cfg(feature = "std")]
+pub(crate) mod reveal;
+#[cfg(feature = "std")]
+pub(crate) mod salted;
+#[cfg(feature = "std")]
+pub(crate) mod sliding;
+#[cfg(feature = "std")]
+pub(crate) mod store;
+#[cfg(feature = "std")]
+pub(crate) mod tweaked;
+pub(crate) mod verify;
 
 pub(crate) mod hash_to_field_pointproofs;
 pub mod pointproofs_groups;
 
 //mod c_api;
+#[cfg(feature = "std")]
 pub(crate) mod c_api;
 mod err;
-mod misc;
-mod serdes;
+pub(crate) mod misc;
+#[cfg(feature = "std")]
+pub(crate) mod serdes;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,72 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for vectors of optional values.
+//!
+//! An optional value is encoded before hashing as
+//!  * `None` => `| 0x00 |`
+//!  * `Some(v)` => `| 0x01 | v |`
+//!
+//! so a missing value never hashes like a present one,
+//! in particular not like the empty value `Some(&[])`.
+
+use pairings::*;
+
+/// the encoding of an optional value
+fn encode_optional<Blob: AsRef<[u8]>>(value: &Option<Blob>) -> Vec<u8> {
+    match value {
+        None => vec![0u8],
+        Some(v) => [&[1u8][..], v.as_ref()].concat(),
+    }
+}
+
+fn encode_optionals<Blob: AsRef<[u8]>>(values: &[Option<Blob>]) -> Vec<Vec<u8>> {
+    values.iter().map(encode_optional).collect()
+}
+
+impl Commitment {
+    /// generate a new commitment to a vector of optional values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n optional values
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters
+    /// `None` marks a missing value, which differs from an empty one;
+    /// see the encoding in this file.
+    pub fn new_optional<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Option<Blob>],
+    ) -> Result<Self, PointproofsError> {
+        Self::new(prover_params, &encode_optionals(values))
+    }
+}
+
+impl Proof {
+    /// Generate a new proof for a vector of optional values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n optional values
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters
+    pub fn new_optional<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Option<Blob>],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        Self::new(prover_params, &encode_optionals(values), index)
+    }
+
+    /// Verify the proof against an optional value and a commitment
+    /// from `Commitment::new_optional`.
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the optional value, `None` if it is missing
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. commitment/value/index
+    pub fn verify_optional<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        value: Option<Blob>,
+        index: usize,
+    ) -> bool {
+        self.verify(verifier_params, com, encode_optional(&value), index)
+    }
+}
@@ -5,55 +5,172 @@ use ff::Field;
 use pairing_plus::serdes::SerDes;
 use pairing_plus::bls12_381::*;
 use pairing_plus::{CurveAffine, CurveProjective};
-use pairings::err::*;
-use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
+use pairings::hash_to_field_pointproofs::{
+    hash_to_field_pointproofs, hash_to_field_pointproofs_with_dst,
+};
 use pairings::*;
+#[cfg(feature = "std")]
 use forfix::paramgen;
-
-const VALID_CIPHERSUITE: [u8; 1] = [0u8];
+#[cfg(feature = "std")]
+pub use forfix::paramgen::{
+    consistent, consistent_detailed, consistent_detailed_with_rng, consistent_with_rng,
+    ConsistencyFailure, ContributionProof, KzgSrs, ParamsHeader, PointproofsParams,
+};
+#[cfg(feature = "parallel")]
+pub use forfix::paramgen::{
+    consistent_detailed_parallel_with_rng, consistent_detailed_parallel_with_rng_in_pool,
+    consistent_parallel, consistent_parallel_in_pool,
+};
+#[cfg(feature = "std")]
+use rand::rngs::OsRng;
+#[cfg(feature = "std")]
+use rand::RngCore;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use zeroize::Zeroize;
+
+/// The supported ciphersuites; they differ in the hash function that maps
+/// values to scalars: 0 = SHA-512, 1 = SHA3-512, 2 = BLAKE2b-512.
+const VALID_CIPHERSUITE: [u8; 3] = [0u8, 1u8, 2u8];
+
+/// The largest window accepted by `ProverParams::precomp_window`.
+pub const MAX_PRECOMP_WINDOW: usize = 8192;
+
+/// The largest n supported by parameter generation and (de)serialization.
+pub const MAX_N: usize = 65536;
 
 /// Ciphersuite ID is a wrapper of u8.
 pub type Ciphersuite = u8;
 
-/// Checks if csid is supported. Currently only support csid = 0.
+/// Checks if csid is supported. Currently support csid = 0, 1 and 2.
 pub fn check_ciphersuite(csid: Ciphersuite) -> bool {
     VALID_CIPHERSUITE.contains(&csid)
 }
 
 /// Generate a set of parameters from a seed and a ciphersuite ID.
-/// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n == 0
+/// The parameters are a function of (seed, ciphersuite, n) only:
+/// no randomness is drawn, so every build derives the same parameters.
+/// alpha is `hash_to_field_pointproofs(seed)`, without a domain separation
+/// tag, for backward compatibility; see `paramgen_from_seed_with_dst`.
+/// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n == 0 or is too large.
 /// This function shall only be used for testing purpose.
 /// In deployment you should use `pointproofs-param` crate to ensure the
 /// security of the public parameters.
+#[cfg(feature = "std")]
 pub fn paramgen_from_seed<Blob: AsRef<[u8]>>(
     seed: Blob,
     ciphersuite: Ciphersuite,
     n: usize,
-) -> Result<(ProverParams, VerifierParams), String> {
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    check_seed_inputs(seed.as_ref(), ciphersuite, n)?;
+
+    // invoke the internal parameter generation function
+    let mut alpha = hash_to_field_pointproofs(&seed);
+    let res = paramgen_from_alpha(&alpha, ciphersuite, n);
+    alpha.zeroize();
+    Ok(res)
+}
+
+/// Generate a set of parameters from a seed, a domain separation tag and a ciphersuite ID.
+/// alpha is `hash_to_field_pointproofs_with_dst(dst, seed)`, so that deployments
+/// with distinct tags get distinct parameters from the same seed;
+/// the empty tag is a tag like any other, and does not give the legacy
+/// parameters of `paramgen_from_seed`.
+/// The tag is not passed to the hashes of the aggregation challenges:
+/// those take the commitment as input, which is already bound to the
+/// parameters, hence to the tag, and the scalars the values are hashed
+/// to must stay stable across deployments.
+/// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n == 0 or is too large.
+/// This function shall only be used for testing purpose.
+/// In deployment you should use `pointproofs-param` crate to ensure the
+/// security of the public parameters.
+#[cfg(feature = "std")]
+pub fn paramgen_from_seed_with_dst<Blob: AsRef<[u8]>>(
+    seed: Blob,
+    dst: &[u8],
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    check_seed_inputs(seed.as_ref(), ciphersuite, n)?;
+
+    // invoke the internal parameter generation function
+    let mut alpha = hash_to_field_pointproofs_with_dst(dst, &seed);
+    let res = paramgen_from_alpha(&alpha, ciphersuite, n);
+    alpha.zeroize();
+    Ok(res)
+}
+
+/// checks the inputs of `paramgen_from_seed` and `paramgen_from_seed_with_dst`
+#[cfg(feature = "std")]
+fn check_seed_inputs(
+    seed: &[u8],
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(), PointproofsError> {
     // check the length of the seed
-    if seed.as_ref().len() < 32 {
-        return Err(ERR_SEED_TOO_SHORT.to_owned());
+    if seed.len() < 32 {
+        return Err(PointproofsError::SeedTooShort);
+    }
+
+    // checks the validity of the inputs
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite);
     }
+    if n == 0 {
+        return Err(PointproofsError::InvalidN);
+    }
+    if n > MAX_N {
+        return Err(PointproofsError::NTooLarge);
+    }
+    Ok(())
+}
 
+/// Generate a set of parameters from a caller-supplied rng, a seed and a ciphersuite ID.
+/// alpha is derived from the seed together with 64 bytes drawn from the rng,
+/// hence a seeded rng (e.g. `ChaChaRng`) gives reproducible parameters.
+/// The seed may be empty; the entropy comes from the rng.
+/// Returns an error if the ciphersuite is not valid; or n == 0 or is too large.
+/// This function shall only be used for testing purpose.
+/// In deployment you should use `pointproofs-param` crate to ensure the
+/// security of the public parameters.
+#[cfg(feature = "std")]
+pub fn paramgen_from_rng<R: RngCore, Blob: AsRef<[u8]>>(
+    rng: &mut R,
+    seed: Blob,
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
     // checks the validity of the inputs
     if !check_ciphersuite(ciphersuite) {
-        return Err(ERR_CIPHERSUITE.to_owned());
+        return Err(PointproofsError::InvalidCiphersuite);
     }
-    if n > 65536 {
-        return Err(ERR_MAX_N.to_owned());
+    if n == 0 {
+        return Err(PointproofsError::InvalidN);
     }
+    if n > MAX_N {
+        return Err(PointproofsError::NTooLarge);
+    }
+
+    // alpha = hash(seed | rng_bytes)
+    let mut r: [u8; 64] = [0; 64];
+    rng.fill_bytes(&mut r[..]);
+    let mut input = [seed.as_ref(), &r[..]].concat();
+    let mut alpha = hash_to_field_pointproofs(&input);
+    r.zeroize();
+    input.zeroize();
 
     // invoke the internal parameter generation function
-    Ok(paramgen_from_alpha(
-        &hash_to_field_pointproofs(&seed),
-        ciphersuite,
-        n,
-    ))
+    let res = paramgen_from_alpha(&alpha, ciphersuite, n);
+    alpha.zeroize();
+    Ok(res)
 }
 
 /// Internal logic for parameter generation.
 /// Will always succeed.
 /// Will not be called outside this module.
+/// The powers of alpha are wiped before returning;
+/// the callers wipe alpha itself.
+#[cfg(feature = "std")]
 fn paramgen_from_alpha(
     alpha: &Fr,
     ciphersuite: Ciphersuite,
@@ -90,6 +207,9 @@ fn paramgen_from_alpha(
         g1_vec.push(PointproofsG1Affine::one().mul(alpha_power).into_affine());
     }
 
+    // the powers of alpha are as secret as alpha itself
+    alpha_power.zeroize();
+
     // verifier also gets gt^{alpha^{n+1}} in the target group
     let gt = pointproofs_pairing(g1_vec[0], g2_vec[n - 1]);
 
@@ -100,6 +220,7 @@ fn paramgen_from_alpha(
             generators: g1_vec,
             pp_len: 0,
             precomp: Vec::with_capacity(0),
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
         },
         VerifierParams {
             ciphersuite,
@@ -108,11 +229,41 @@ fn paramgen_from_alpha(
             pp_len: 0,
             precomp: Vec::with_capacity(0),
             gt_elt: gt,
+            prepared: vec![],
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
         },
     )
 }
 
 impl VerifierParams {
+    /// the number of values the parameters support
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// the ciphersuite of the parameters
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// the maximum length of a single value, in bytes;
+    /// `DEFAULT_MAX_VALUE_LEN` unless set otherwise
+    pub fn max_value_len(&self) -> usize {
+        self.max_value_len
+    }
+
+    /// set the maximum length of a single value, in bytes.
+    /// `Proof::verify` and its variants reject longer values with
+    /// `VerifyFailure::ValueTooLong` before hashing them, and the batch
+    /// verifiers, e.g. `Proof::same_commit_batch_verify`,
+    /// `Proof::batch_verify_same_commit` and `Proof::cross_aggregate_verify`,
+    /// return false for them.
+    /// `Proof::verify_fr` takes a hash, so it has no value to bound.
+    /// The bound is not serialized.
+    pub fn set_max_value_len(&mut self, max_value_len: usize) {
+        self.max_value_len = max_value_len;
+    }
+
     /// pre-process the public parameters with precomputation value set to 3
     pub fn precomp_3(&mut self) {
         self.precomp = vec![PointproofsG2Affine::zero(); 3 * self.n];
@@ -130,27 +281,179 @@ impl VerifierParams {
         }
         self.pp_len = self.n * 256;
     }
+
+    /// pre-process the public parameters for the Miller loop:
+    /// caches the prepared form of the generators and of the G2 generator,
+    /// so that `Proof::verify` does not prepare them on every call.
+    /// The cache is not serialized; call this again after deserialization.
+    pub fn precomp(&mut self) {
+        self.prepared = self
+            .generators
+            .iter()
+            .map(|g| g.prepare())
+            .chain(core::iter::once(PointproofsG2Affine::one().prepare()))
+            .collect();
+    }
 }
 
+#[cfg(feature = "std")]
 impl ProverParams {
+    /// the number of values the parameters support
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// the ciphersuite of the parameters
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// the maximum length of a single value, in bytes;
+    /// `DEFAULT_MAX_VALUE_LEN` unless set otherwise
+    pub fn max_value_len(&self) -> usize {
+        self.max_value_len
+    }
+
+    /// set the maximum length of a single value, in bytes.
+    /// The functions that hash values with these parameters reject longer
+    /// values with `PointproofsError::ValueTooLong` before hashing them:
+    /// `Commitment::new`, `Proof::new` and their batch, chunked, parallel,
+    /// streaming and update variants, as well as `ProverParams::hash_values`.
+    /// The aggregators, e.g. `Proof::same_commit_aggregate`, do not take
+    /// the parameters and do not bound the values.
+    /// The bound is not serialized.
+    pub fn set_max_value_len(&mut self, max_value_len: usize) {
+        self.max_value_len = max_value_len;
+    }
+
     /// pre-process the public parameters with precomputation value set to 3
     pub fn precomp_3(&mut self) {
+        self.fill_precomp(3);
+    }
+
+    /// pre-process the public parameters with precomputation value set to 256
+    pub fn precomp_256(&mut self) {
+        self.fill_precomp(256);
+    }
+
+    /// pre-process the public parameters with a fixed-base table of
+    /// `window_size` points per generator.
+    /// The table costs `window_size` affine points for each of the 2n generators
+    /// in memory, and `window_size * POINTPROOFSG1_LEN` bytes per generator once serialized.
+    /// A larger window makes single exponentiations (e.g. updates) faster.
+    /// Windows 3 and 256 are the same as `precomp_3` and `precomp_256`;
+    /// only the 256 one is used by multi-exponentiations (commit/prove).
+    /// Returns an error if the window is 0, 1, or larger than `MAX_PRECOMP_WINDOW`.
+    pub fn precomp_window(&mut self, window_size: usize) -> Result<(), PointproofsError> {
+        if window_size < 2 || window_size > MAX_PRECOMP_WINDOW {
+            return Err(PointproofsError::InvalidPrecompWindow);
+        }
+        self.fill_precomp(window_size);
+        Ok(())
+    }
+
+    /// the memory, in bytes, that a fixed-base table of `window_size` points
+    /// per generator takes:
+    /// `window_size * 2n * size_of::<PointproofsG1Affine>()`,
+    /// i.e., 104 bytes per point in G1 (two 48-byte coordinates and a flag,
+    /// padded), or 200 bytes per point when the groups are switched.
+    /// The allocator overhead of the single table vector is not counted.
+    /// Saturates at `usize::max_value()`.
+    pub fn precomp_memory_estimate(&self, window_size: usize) -> usize {
+        window_size
+            .checked_mul(self.generators.len())
+            .and_then(|e| e.checked_mul(std::mem::size_of::<PointproofsG1Affine>()))
+            .unwrap_or_else(usize::max_value)
+    }
+
+    /// Same as `ProverParams::precomp_window`, but refuses to build a table
+    /// whose `precomp_memory_estimate` exceeds `max_bytes`,
+    /// so that the caller can fall back to a smaller window, or none.
+    /// The current table is released before the new one is built,
+    /// so the peak memory is the new table only.
+    /// Returns `PrecompTooLarge`, with the estimate, if it exceeds the budget;
+    /// the parameters are then unchanged.
+    pub fn try_precomp_window(
+        &mut self,
+        window_size: usize,
+        max_bytes: usize,
+    ) -> Result<(), PointproofsError> {
+        if window_size < 2 || window_size > MAX_PRECOMP_WINDOW {
+            return Err(PointproofsError::InvalidPrecompWindow);
+        }
+        let estimated = self.precomp_memory_estimate(window_size);
+        if estimated > max_bytes {
+            return Err(PointproofsError::PrecompTooLarge {
+                estimated,
+                max: max_bytes,
+            });
+        }
+        self.precomp = vec![];
+        self.pp_len = 0;
+        self.fill_precomp(window_size);
+        Ok(())
+    }
+
+    /// Same as `ProverParams::precomp_window`, but the tables of generators
+    /// that also appear in one of the `sources` are copied from there,
+    /// e.g. when the parameters are combined from, or share points with,
+    /// parameters that were already pre-processed.
+    /// Only sources whose table has the same window are used, so with
+    /// mismatched windows every table is rebuilt from scratch.
+    /// Returns the number of generators whose table was copied.
+    pub fn precomp_window_from(
+        &mut self,
+        window_size: usize,
+        sources: &[&ProverParams],
+    ) -> Result<usize, PointproofsError> {
+        if window_size < 2 || window_size > MAX_PRECOMP_WINDOW {
+            return Err(PointproofsError::InvalidPrecompWindow);
+        }
+
+        // index the pre-computed tables of the sources by their generator
+        let mut tables: HashMap<Vec<u8>, &[PointproofsG1Affine]> = HashMap::new();
+        for source in sources.iter() {
+            if source.precomp.len() != window_size * source.generators.len() {
+                continue;
+            }
+            for (i, point) in source.generators.iter().enumerate() {
+                let mut buf: Vec<u8> = vec![];
+                // serializing a valid point into a vector does not fail
+                point.serialize(&mut buf, true).unwrap();
+                tables
+                    .entry(buf)
+                    .or_insert(&source.precomp[i * window_size..(i + 1) * window_size]);
+            }
+        }
+
         let twice_n = self.generators.len();
-        self.precomp = vec![PointproofsG1Affine::zero(); 3 * twice_n];
+        let mut precomp = vec![PointproofsG1Affine::zero(); window_size * twice_n];
+        let mut reused = 0;
         for i in 0..twice_n {
-            self.generators[i].precomp_3(&mut self.precomp[i * 3..(i + 1) * 3]);
+            let table = &mut precomp[i * window_size..(i + 1) * window_size];
+            let mut buf: Vec<u8> = vec![];
+            self.generators[i].serialize(&mut buf, true).unwrap();
+            match tables.get(&buf) {
+                Some(source_table) => {
+                    table.copy_from_slice(source_table);
+                    reused += 1;
+                }
+                None => fill_table(&self.generators[i], window_size, table),
+            }
         }
-        self.pp_len = self.n * 6;
+        self.precomp = precomp;
+        self.pp_len = self.n * 2 * window_size;
+        Ok(reused)
     }
 
-    /// pre-process the public parameters with precomputation value set to 256
-    pub fn precomp_256(&mut self) {
+    fn fill_precomp(&mut self, window_size: usize) {
         let twice_n = self.generators.len();
-        self.precomp = vec![PointproofsG1Affine::zero(); 256 * twice_n];
+        self.precomp = vec![PointproofsG1Affine::zero(); window_size * twice_n];
         for i in 0..twice_n {
-            self.generators[i].precomp_256(&mut self.precomp[i * 256..(i + 1) * 256]);
+            let table = &mut self.precomp[i * window_size..(i + 1) * window_size];
+            fill_table(&self.generators[i], window_size, table);
         }
-        self.pp_len = self.n * 512;
+        self.pp_len = self.n * 2 * window_size;
     }
 
     /// check if the parameters are correct -- should not be used other than testing
@@ -212,7 +515,21 @@ impl ProverParams {
     }
 }
 
-impl std::cmp::PartialEq for ProverParams {
+/// fills the fixed-base table of `window_size` points for a generator
+#[cfg(feature = "std")]
+fn fill_table(
+    generator: &PointproofsG1Affine,
+    window_size: usize,
+    table: &mut [PointproofsG1Affine],
+) {
+    match window_size {
+        3 => generator.precomp_3(table),
+        256 => generator.precomp_256(table),
+        _ => misc::precomp_window_table(generator, window_size, table),
+    }
+}
+
+impl core::cmp::PartialEq for ProverParams {
     /// Convenient function to compare secret key objects
     fn eq(&self, other: &Self) -> bool {
         self.ciphersuite == other.ciphersuite
@@ -223,7 +540,7 @@ impl std::cmp::PartialEq for ProverParams {
     }
 }
 
-impl std::cmp::PartialEq for VerifierParams {
+impl core::cmp::PartialEq for VerifierParams {
     /// Convenient function to compare secret key objects
     fn eq(&self, other: &Self) -> bool {
         self.ciphersuite == other.ciphersuite
@@ -296,22 +613,37 @@ impl std::cmp::PartialEq for VerifierParams {
 //     /// e(g2,g1)^{alpha^{N+1}}
 //     pub gt_alpha_nplus1: Fq12,
 // }
+#[cfg(feature = "std")]
 pub fn read_param<R: std::io::Read>(
     reader: &mut R,
-) -> Result<(ProverParams, VerifierParams), String> {
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    let mut rng = match OsRng::new() {
+        Err(_e) => return Err(PointproofsError::RngUnavailable),
+        Ok(p) => p,
+    };
+    read_param_with_rng(reader, &mut rng)
+}
+
+/// Same as `read_param`, but the consistency check draws
+/// its random scalars from the caller's rng.
+#[cfg(feature = "std")]
+pub fn read_param_with_rng<R: std::io::Read, Rn: RngCore>(
+    reader: &mut R,
+    rng: &mut Rn,
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
     let param = match paramgen::PointproofsParams::deserialize(reader, true) {
-        Err(e) => return Err(format!("read_param: {}", e.to_string())),
+        Err(e) => return Err(e.into()),
         Ok(p) => p,
     };
 
-    if !paramgen::consistent(&param) {
-        return Err("Input params are not consistent".to_owned());
+    if !paramgen::consistent_with_rng(&param, rng) {
+        return Err(PointproofsError::InvalidParams);
     };
 
     // groups are NOT switched
     #[cfg(not(feature = "group_switched"))]
     let pp = ProverParams {
-        ciphersuite: 0,
+        ciphersuite: param.ciphersuite,
         n: param.n,
         generators: [
             param.g1_alpha_1_to_n,
@@ -321,22 +653,25 @@ pub fn read_param<R: std::io::Read>(
         .concat(),
         pp_len: 0,
         precomp: vec![],
+        max_value_len: DEFAULT_MAX_VALUE_LEN,
     };
 
     #[cfg(not(feature = "group_switched"))]
     let vp = VerifierParams {
-        ciphersuite: 0,
+        ciphersuite: param.ciphersuite,
         n: param.n,
         generators: param.g2_alpha_1_to_n, // expected `pairing_plus::bls12_381::G2Affine`, found `pairing_plus::bls12_381::ec::g2::G2Affine`
         pp_len: 0,
         precomp: vec![],
         gt_elt: param.gt_alpha_nplus1, // expected `pairing_plus::bls12_381::Fq12`, found `pairing_plus::bls12_381::fq12::Fq12`
+        prepared: vec![],
+        max_value_len: DEFAULT_MAX_VALUE_LEN,
     };
 
     // groups switched
     #[cfg(feature = "group_switched")]
     let pp = ProverParams {
-        ciphersuite: 0,
+        ciphersuite: param.ciphersuite,
         n: param.n,
         generators: [
             param.g2_alpha_1_to_n,
@@ -346,16 +681,56 @@ pub fn read_param<R: std::io::Read>(
         .concat(),
         pp_len: 0,
         precomp: vec![],
+        max_value_len: DEFAULT_MAX_VALUE_LEN,
     };
     #[cfg(feature = "group_switched")]
     let vp = VerifierParams {
-        ciphersuite: 0,
+        ciphersuite: param.ciphersuite,
         n: param.n,
         generators: param.g1_alpha_1_to_n,
         pp_len: 0,
         precomp: vec![],
         gt_elt: param.gt_alpha_nplus1,
+        prepared: vec![],
+        max_value_len: DEFAULT_MAX_VALUE_LEN,
     };
 
     Ok((pp, vp))
 }
+
+/// Reads a parameter pair as `read_param`, and writes the verifier
+/// parameters alone, with the format of `VerifierParams::serialize`:
+/// the n powers of alpha of the verifier group and gt, without any of the
+/// prover generators. This is the file a verify-only node needs; it is about
+/// a third of the size of the full parameters, and is loaded with
+/// `VerifierParams::deserialize` or `VerifierParams::from_bytes`.
+/// The full parameters are checked for consistency before anything is
+/// written; the trimmed file cannot be checked on its own, so the verifier
+/// must obtain it from a trusted source.
+/// Returns the verifier parameters that were written.
+#[cfg(feature = "std")]
+pub fn write_verifier_param<R: std::io::Read, W: std::io::Write>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<VerifierParams, PointproofsError> {
+    let mut rng = match OsRng::new() {
+        Err(_e) => return Err(PointproofsError::RngUnavailable),
+        Ok(p) => p,
+    };
+    write_verifier_param_with_rng(reader, writer, &mut rng)
+}
+
+/// Same as `write_verifier_param`, but the consistency check draws
+/// its random scalars from the caller's rng.
+#[cfg(feature = "std")]
+pub fn write_verifier_param_with_rng<R: std::io::Read, W: std::io::Write, Rn: RngCore>(
+    reader: &mut R,
+    writer: &mut W,
+    rng: &mut Rn,
+) -> Result<VerifierParams, PointproofsError> {
+    let (_pp, vp) = read_param_with_rng(reader, rng)?;
+    if let Err(e) = vp.serialize(writer, true) {
+        return Err(PointproofsError::Serde(e));
+    }
+    Ok(vp)
+}
@@ -1,5 +1,9 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ff::Field;
 use pairing_plus::bls12_381::*;
-use pairing_plus::Engine;
+use pairing_plus::{CurveAffine, CurveProjective, Engine};
+use pairings::err::ERR_MULTIEXP_LEN;
 
 // =========================
 // the groups are NOT switched
@@ -36,6 +40,24 @@ pub(crate) fn pointproofs_pairing_product(
 ) -> Fq12 {
     Bls12::pairing_product(p1, q1, p2, q2)
 }
+/// A wrapper of BLS::G2Prepared, the Miller loop form of PointproofsG2Affine.
+/// Groups are not switched and proof/commits are in BLS::G1
+#[cfg(not(feature = "group_switched"))]
+pub type PointproofsG2Prepared = G2Prepared;
+/// A wrapper of BLS::pairing_product with prepared q1 and q2.
+/// Groups are not switched and proof/commits are in BLS::G1
+#[cfg(not(feature = "group_switched"))]
+pub(crate) fn pointproofs_pairing_product_prepared(
+    p1: PointproofsG1Affine,
+    q1: &PointproofsG2Prepared,
+    p2: PointproofsG1Affine,
+    q2: &PointproofsG2Prepared,
+) -> Fq12 {
+    let p1 = p1.prepare();
+    let p2 = p2.prepare();
+    // the Miller loop of valid points is never zero
+    Bls12::final_exponentiation(&Bls12::miller_loop([(&p1, q1), (&p2, q2)].iter())).unwrap()
+}
 /// A wrapper of BLS::pairing_multi_product. Groups are switched and proof/commits are in BLS::G1
 #[cfg(not(feature = "group_switched"))]
 pub(crate) fn pointproofs_pairing_multi_product(
@@ -89,6 +111,24 @@ pub(crate) fn pointproofs_pairing_product(
 ) -> Fq12 {
     Bls12::pairing_product(q1, p1, q2, p2)
 }
+/// A wrapper of BLS::G1Prepared, the Miller loop form of PointproofsG2Affine.
+/// Groups are switched and proof/commits are in BLS::G2
+#[cfg(feature = "group_switched")]
+pub type PointproofsG2Prepared = G1Prepared;
+/// A wrapper of BLS::pairing_product with prepared q1 and q2.
+/// Groups are switched and proof/commits are in BLS::G2
+#[cfg(feature = "group_switched")]
+pub(crate) fn pointproofs_pairing_product_prepared(
+    p1: PointproofsG1Affine,
+    q1: &PointproofsG2Prepared,
+    p2: PointproofsG1Affine,
+    q2: &PointproofsG2Prepared,
+) -> Fq12 {
+    let p1 = p1.prepare();
+    let p2 = p2.prepare();
+    // the Miller loop of valid points is never zero
+    Bls12::final_exponentiation(&Bls12::miller_loop([(q1, &p1), (q2, &p2)].iter())).unwrap()
+}
 /// A wrapper of BLS::pairing_multi_product. Groups are switched and proof/commits are in BLS::G2
 #[cfg(feature = "group_switched")]
 pub(crate) fn pointproofs_pairing_multi_product(
@@ -106,3 +146,77 @@ pub const COMMIT_LEN: usize = 97;
 /// Size for serialized proof.
 #[cfg(feature = "group_switched")]
 pub const PROOF_LEN: usize = 97;
+
+// =========================
+// common to both settings
+
+/// Checks that \prod_i e(pairs\[i\].0, pairs\[i\].1) == target,
+/// with a single final exponentiation;
+/// this is the check that the batch verifications perform.
+/// The first point of each pair is in the group of the commitments and proofs,
+/// PointproofsG1, and the second one in the group of the verifier parameters,
+/// PointproofsG2; the groups are swapped for BLS::pairing when switched.
+/// An empty list of pairs has the product one.
+pub fn multi_pairing_check(
+    pairs: &[(PointproofsG1Affine, PointproofsG2Affine)],
+    target: &Fq12,
+) -> bool {
+    if pairs.is_empty() {
+        return *target == Fq12::one();
+    }
+    let g1_vec: Vec<PointproofsG1Affine> = pairs.iter().map(|p| p.0).collect();
+    let g2_vec: Vec<PointproofsG2Affine> = pairs.iter().map(|p| p.1).collect();
+    pointproofs_pairing_multi_product(&g1_vec[..], &g2_vec[..]) == *target
+}
+
+/// Below this many bases `multiexp_g1` and `multiexp_g2` stay on the calling thread.
+#[cfg(feature = "parallel")]
+pub(crate) const PARALLEL_MULTIEXP_THRESHOLD: usize = 1024;
+
+/// Computes \prod_i points\[i\]^scalars\[i\] in PointproofsG1,
+/// where the scalars are FrRepr limbs, as `CurveAffine::sum_of_products` takes them.
+/// With the `parallel` feature and at least `PARALLEL_MULTIEXP_THRESHOLD`
+/// points, the points are split into one chunk per thread of the rayon
+/// thread pool; the output is identical to `sum_of_products` either way.
+/// An empty list gives the identity.
+/// Panics if points and scalars have different lengths.
+pub fn multiexp_g1(points: &[PointproofsG1Affine], scalars: &[&[u64; 4]]) -> PointproofsG1 {
+    multiexp(points, scalars)
+}
+
+/// Same as `multiexp_g1`, in PointproofsG2.
+pub fn multiexp_g2(points: &[PointproofsG2Affine], scalars: &[&[u64; 4]]) -> PointproofsG2 {
+    multiexp(points, scalars)
+}
+
+/// The multi-exponentiation behind `multiexp_g1` and `multiexp_g2`.
+pub(crate) fn multiexp<G: CurveAffine>(points: &[G], scalars: &[&[u64; 4]]) -> G::Projective {
+    assert_eq!(points.len(), scalars.len(), "{}", ERR_MULTIEXP_LEN);
+    if points.is_empty() {
+        return G::Projective::zero();
+    }
+    #[cfg(feature = "parallel")]
+    {
+        if points.len() >= PARALLEL_MULTIEXP_THRESHOLD && rayon::current_num_threads() > 1 {
+            return par_multiexp(points, scalars);
+        }
+    }
+    G::sum_of_products(points, scalars)
+}
+
+/// \prod_i points\[i\]^scalars\[i\], with one chunk of the points per thread
+#[cfg(feature = "parallel")]
+fn par_multiexp<G: CurveAffine>(points: &[G], scalars: &[&[u64; 4]]) -> G::Projective {
+    use rayon::prelude::*;
+
+    let num_threads = rayon::current_num_threads();
+    let chunk_size = ((points.len() + num_threads - 1) / num_threads).max(1);
+    points
+        .par_chunks(chunk_size)
+        .zip(scalars.par_chunks(chunk_size))
+        .map(|(p, s)| G::sum_of_products(p, s))
+        .reduce(G::Projective::zero, |mut acc, e| {
+            acc.add_assign(&e);
+            acc
+        })
+}
@@ -1,12 +1,15 @@
 //! this file is part of the pointproofs.
 //! It defines functions for proofs.
 use ff::{Field, PrimeField};
+use pairing_plus::serdes::SerDes;
 use pairing_plus::{bls12_381::*, CurveAffine, CurveProjective};
-use pairings::err::*;
 use pairings::hash_to_field_pointproofs::*;
 use pairings::misc::*;
 use pairings::param::*;
 use pairings::*;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use zeroize::Zeroizing;
 
 impl Proof {
     /// Generate a new proof.
@@ -14,37 +17,45 @@ impl Proof {
     ///     * input: values for the proof
     ///     * input: the index of the proof
     ///     * output: a new proof
-    ///     * error: invalid ciphersuite/parameters
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
     /// note that if the #values does not match the parameter n,
     /// an error will be returned.
     /// if one were to generate a commitment for a vector of length
     /// less than n, then the caller should pad the vector.
     /// In this scenario, the caller should define the right
     /// format for padding.
+    /// With n = 1 there is no other value to open, so the proof
+    /// is the identity; it still verifies against the commitment.
+    /// As in `Commitment::new`, the hashed scalars are zeroized when dropped.
     pub fn new<Blob: AsRef<[u8]>>(
         prover_params: &ProverParams,
         values: &[Blob],
         index: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
 
         // check index is valid
         if index >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         };
         // check param
         if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::MismatchedN);
         }
+        check_value_lens(values, prover_params.max_value_len)?;
 
         // hash into a set of scalars
-        let scalars_fr_repr: Vec<FrRepr> = values
-            .iter()
-            .map(|s| hash_to_field_repr_pointproofs(&s.as_ref()))
-            .collect();
+        let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+            values
+                .iter()
+                .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, &s.as_ref()))
+                .collect(),
+        );
         let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
 
         // generate the proof use `sum of product` function
@@ -59,16 +70,85 @@ impl Proof {
 
         Ok(Self {
             ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
+            proof,
+        })
+    }
+
+    /// Generate a new proof from the already hashed values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n scalars, in repr form
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters, non-canonical scalars
+    /// The i-th scalar is expected to be hash_to_field(values\[i\]);
+    /// the output then matches `Proof::new`.
+    pub fn new_from_repr_slice(
+        prover_params: &ProverParams,
+        reprs: &[FrRepr],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+
+        // check index is valid
+        if index >= prover_params.n {
+            return Err(PointproofsError::IndexOutOfRange);
+        };
+        // check param
+        if reprs.len() != prover_params.n {
+            return Err(PointproofsError::MismatchedN);
+        }
+        if !is_canonical_repr_slice(reprs) {
+            return Err(PointproofsError::NonCanonicalScalar);
+        }
+
+        let scalars_u64: Vec<&[u64; 4]> = reprs.iter().map(|s| &s.0).collect();
+
+        // proof = \sum_{i=prover_params.n - index}^{2 * prover_params.n - index}
+        //          param.generator[i]^scarlar_u64[i]
+        let proof = pp_sum_of_prod_helper(
+            &prover_params,
+            &scalars_u64,
+            prover_params.n - index,
+            2 * prover_params.n - index,
+        );
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
             proof,
         })
     }
 
+    /// Generate a new proof from the already hashed values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n scalars, see `ProverParams::hash_values`
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters
+    /// The output matches `Proof::new` for the values the scalars
+    /// were hashed from.
+    pub fn new_prehashed(
+        prover_params: &ProverParams,
+        scalars: &[Fr],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        let reprs: Zeroizing<Vec<FrRepr>> =
+            Zeroizing::new(scalars.iter().map(|s| s.into_repr()).collect());
+        Self::new_from_repr_slice(prover_params, &reprs, index)
+    }
+
     /// Generate a new set of proofs.
     ///     * input: prover parameter set
     ///     * input: values for the proof
     ///     * input: the list of indices of the proof
     ///     * output: a list of new proofs
-    ///     * error: invalid ciphersuite/parameters
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
     /// Note that indices.len should be within (0, n]
     /// 0 is invalid -- no proof will be generated
     /// n is valid -- generating proof for the whole vector
@@ -76,42 +156,46 @@ impl Proof {
         prover_params: &ProverParams,
         values: &[Blob],
         indices: &[usize],
-    ) -> Result<Vec<Self>, String> {
+    ) -> Result<Vec<Self>, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
 
         // check index is valid
         if indices.len() > prover_params.n || indices.is_empty() {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         };
         for e in indices {
             if *e >= prover_params.n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::IndexOutOfRange);
             }
         }
 
         if !misc::has_unique_elements(indices) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
 
         // check param
         if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::MismatchedN);
         }
+        check_value_lens(values, prover_params.max_value_len)?;
 
         // hash into a set of scalars
-        let scalars_fr_repr: Vec<FrRepr> = values
-            .iter()
-            .map(|s| hash_to_field_repr_pointproofs(&s.as_ref()))
-            .collect();
+        let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+            values
+                .iter()
+                .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, &s.as_ref()))
+                .collect(),
+        );
         let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
 
         Ok(indices
             .iter()
             .map(|e| Self {
                 ciphersuite: prover_params.ciphersuite,
+                n: prover_params.n,
                 proof: pp_sum_of_prod_helper(
                     &prover_params,
                     &scalars_u64,
@@ -122,13 +206,168 @@ impl Proof {
             .collect())
     }
 
+    /// Generate the proofs for all n indices at once.
+    ///     * input: prover parameter set
+    ///     * input: values for the proof
+    ///     * output: a list of n proofs, the i-th one is for index i
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The proofs are identical to the ones from `Proof::new`.
+    /// proof[i] = \sum_j param.generator[n - i + j]^scalar[j] is a
+    /// Toeplitz matrix-vector product, so it is computed as a convolution
+    /// with FFTs over G1: O(n log n) exponentiations instead of n sums of
+    /// products of size n. It keeps about 4n G1 points in memory.
+    pub fn new_all<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+
+        // check param
+        if values.len() != prover_params.n {
+            return Err(PointproofsError::MismatchedN);
+        }
+        check_value_lens(values, prover_params.max_value_len)?;
+
+        // with the scalars reversed, proof[i] is the (2n - 1 - i)-th
+        // coefficient of the convolution of the generators and the scalars;
+        // a cyclic convolution of size >= 2n does not wrap around
+        let n = prover_params.n;
+        let size = (2 * n).next_power_of_two();
+        let omega = root_of_unity(size.trailing_zeros());
+        let omega_inv = omega.inverse().unwrap();
+        let size_inv = Fr::from_repr(FrRepr::from(size as u64))
+            .unwrap()
+            .inverse()
+            .unwrap();
+
+        let mut basis: Vec<PointproofsG1> = vec![PointproofsG1::zero(); size];
+        for (b, g) in basis.iter_mut().zip(prover_params.generators.iter()) {
+            *b = g.into_projective();
+        }
+        let mut scalars: Zeroizing<Vec<Fr>> = Zeroizing::new(vec![Fr::zero(); size]);
+        for (j, s) in values.iter().enumerate() {
+            scalars[n - 1 - j] = hash_to_field_ciphersuite(prover_params.ciphersuite, &s.as_ref());
+        }
+
+        fft(&mut basis, &omega);
+        fft(&mut scalars[..], &omega);
+        for (b, s) in basis.iter_mut().zip(scalars.iter_mut()) {
+            // fold the 1/size of the inverse FFT into the scalars
+            s.mul_assign(&size_inv);
+            b.mul_assign(*s);
+        }
+        // the inverse FFT
+        fft(&mut basis, &omega_inv);
+
+        Ok((0..n)
+            .map(|i| Self {
+                ciphersuite: prover_params.ciphersuite,
+                n: prover_params.n,
+                proof: basis[2 * n - 1 - i],
+            })
+            .collect())
+    }
+
+    /// The working memory of `Proof::new_all` for n values, in bytes.
+    ///     * input: the dimension n
+    ///     * output: the size of the FFT buffers of G1 points and scalars
+    /// `Proof::new_all_tuned` uses the FFT from this budget on.
+    pub fn new_all_memory(n: usize) -> usize {
+        (2 * n).next_power_of_two()
+            * (std::mem::size_of::<PointproofsG1>() + std::mem::size_of::<Fr>())
+    }
+
+    /// Generate the proofs for all n indices within a memory budget.
+    ///     * input: prover parameter set
+    ///     * input: values for the proof
+    ///     * input: the working memory budget, in bytes
+    ///     * output: a list of n proofs, the i-th one is for index i
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The proofs are identical to the ones from `Proof::new`.
+    /// The budget covers the working memory, not the returned proofs.
+    /// From a budget of `Proof::new_all_memory(n)` bytes, about 4n G1
+    /// points, this is `Proof::new_all`. Below that crossover the values
+    /// are hashed in blocks that fit within the budget, and each block is
+    /// added into all n proofs before moving on: a large budget means one
+    /// sum of products per proof, a small budget means many smaller
+    /// (hence slower) ones. At least one scalar is always kept in memory.
+    pub fn new_all_tuned<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        max_memory: usize,
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+
+        // check param
+        if values.len() != prover_params.n {
+            return Err(PointproofsError::MismatchedN);
+        }
+        check_value_lens(values, prover_params.max_value_len)?;
+
+        // the FFT shares the work across all proofs, use it when it fits
+        if max_memory >= Self::new_all_memory(prover_params.n) {
+            return Self::new_all(prover_params, values);
+        }
+
+        // each scalar costs a FrRepr plus a reference to it
+        let scalar_mem = std::mem::size_of::<FrRepr>() + std::mem::size_of::<&[u64; 4]>();
+        let n = prover_params.n;
+        let block_size = (max_memory / scalar_mem).max(1).min(n);
+
+        let mut proofs: Vec<PointproofsG1> = vec![PointproofsG1::zero(); n];
+        for start in (0..n).step_by(block_size) {
+            let end = std::cmp::min(start + block_size, n);
+
+            // hash the current block into a set of scalars
+            let scalars_fr_repr: Zeroizing<Vec<FrRepr>> = Zeroizing::new(
+                values[start..end]
+                    .iter()
+                    .map(|s| hash_to_field_repr_ciphersuite(prover_params.ciphersuite, &s.as_ref()))
+                    .collect(),
+            );
+            let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+
+            // proof[index] += \sum_{j=start}^{end-1}
+            //          param.generator[n - index + j]^scarlar_u64[j]
+            for (index, proof) in proofs.iter_mut().enumerate() {
+                proof.add_assign(&pp_sum_of_prod_helper(
+                    &prover_params,
+                    &scalars_u64,
+                    n - index + start,
+                    n - index + end,
+                ));
+            }
+        }
+
+        Ok(proofs
+            .into_iter()
+            .map(|proof| Self {
+                ciphersuite: prover_params.ciphersuite,
+                n: prover_params.n,
+                proof,
+            })
+            .collect())
+    }
+
     /// Generate a new set of proofs.
     ///     * input: prover parameter set
     ///     * input: the commitment
     ///     * input: values for the proof
     ///     * input: the list of indices of the proof
     ///     * output: an aggregation of the list of new proofs
-    ///     * error: invalid ciphersuite/parameters
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
     /// Note that indices.len should be within (0, n]
     /// 0 is invalid -- no proof will be generated
     /// n is valid -- generating proof for the whole vector
@@ -137,33 +376,34 @@ impl Proof {
         commit: &Commitment,
         values: &[Blob],
         indices: &[usize],
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         if prover_params.ciphersuite != commit.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
 
         // check index is valid
         if indices.len() > prover_params.n || indices.is_empty() {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         };
         for e in indices {
             if *e >= prover_params.n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::IndexOutOfRange);
             }
         }
 
         if !misc::has_unique_elements(indices) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+            return Err(PointproofsError::DuplicatedIndex);
         }
 
         // check param
         if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::MismatchedN);
         }
+        check_value_lens(values, prover_params.max_value_len)?;
 
         // generate the list of sub_values
         let mut value_sub_vector: Vec<&[u8]> = Vec::with_capacity(values.len());
@@ -172,15 +412,18 @@ impl Proof {
         }
 
         // hash into a set of scalars
-        let scalars_fr: Vec<Fr> = values
-            .iter()
-            .map(|s| hash_to_field_pointproofs(&s.as_ref()))
-            .collect();
+        let scalars_fr: Zeroizing<Vec<Fr>> = Zeroizing::new(
+            values
+                .iter()
+                .map(|s| hash_to_field_ciphersuite(prover_params.ciphersuite, &s.as_ref()))
+                .collect(),
+        );
         // get the list of scalars for each proof
         let ti = hash_to_ti_fr(commit, indices, &value_sub_vector, prover_params.n)?;
 
         // form the final scalars, which are ti[k]*m[n - indices[k] + j] for each index
-        let mut final_scalars: Vec<Fr> = vec![Fr::zero(); 2 * prover_params.n];
+        let mut final_scalars: Zeroizing<Vec<Fr>> =
+            Zeroizing::new(vec![Fr::zero(); 2 * prover_params.n]);
         for k in 0..indices.len() {
             for j in 0..prover_params.n {
                 let mut tmp = ti[k];
@@ -192,7 +435,8 @@ impl Proof {
         // remove the generators where the scalars are 0s, to form the final basis
         // also convert Fr-s to FrRepr-s to [u64;4]-s
         let mut final_basis: Vec<PointproofsG1Affine> = Vec::with_capacity(2 * prover_params.n);
-        let mut final_scalars_repr: Vec<FrRepr> = Vec::with_capacity(2 * prover_params.n);
+        let mut final_scalars_repr: Zeroizing<Vec<FrRepr>> =
+            Zeroizing::new(Vec::with_capacity(2 * prover_params.n));
         for (i, e) in final_scalars.iter().enumerate() {
             if !e.is_zero() {
                 final_scalars_repr.push(e.into_repr());
@@ -223,16 +467,61 @@ impl Proof {
                     &final_basis_pp,
                 )
             } else {
-                PointproofsG1Affine::sum_of_products(&final_basis, &scalars_u64)
+                multiexp_g1(&final_basis, &scalars_u64)
             }
         };
 
         Ok(Proof {
             ciphersuite: prover_params.ciphersuite,
+            n: prover_params.n,
             proof: agg_proof,
         })
     }
 
+    /// Generate a single proof for the contiguous range of indices [lo, hi).
+    ///     * input: prover parameter set
+    ///     * input: values for the proof
+    ///     * input: the first index of the range
+    ///     * input: the index after the last one of the range
+    ///     * output: an aggregated proof for the indices lo..hi
+    ///     * error: invalid ciphersuite/parameters, empty or out of bound range
+    /// The proof is the same as `Proof::batch_new_aggregated` with
+    /// indices lo..hi; the commitment is recomputed from the values,
+    /// use `Proof::new_subvector_with_commit` if it is already known.
+    pub fn new_subvector<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        lo: usize,
+        hi: usize,
+    ) -> Result<Self, PointproofsError> {
+        check_range(prover_params.n, lo, hi)?;
+        let commit = Commitment::new(prover_params, values)?;
+        Self::new_subvector_with_commit(prover_params, &commit, values, lo, hi)
+    }
+
+    /// Generate a single proof for the contiguous range of indices [lo, hi),
+    /// given the commitment to the values.
+    ///     * input: prover parameter set
+    ///     * input: the commitment
+    ///     * input: values for the proof
+    ///     * input: the first index of the range
+    ///     * input: the index after the last one of the range
+    ///     * output: an aggregated proof for the indices lo..hi
+    ///     * error: invalid ciphersuite/parameters, empty or out of bound range
+    /// The proof is the same as `Proof::batch_new_aggregated` with
+    /// indices lo..hi.
+    pub fn new_subvector_with_commit<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        commit: &Commitment,
+        values: &[Blob],
+        lo: usize,
+        hi: usize,
+    ) -> Result<Self, PointproofsError> {
+        check_range(prover_params.n, lo, hi)?;
+        let indices: Vec<usize> = (lo..hi).collect();
+        Self::batch_new_aggregated(prover_params, commit, values, &indices)
+    }
+
     /// Updating an existing proof.
     ///     * input: prover parameter set
     ///     * input: the index for the proof
@@ -240,7 +529,9 @@ impl Proof {
     ///     * input: the value before the change
     ///     * input: the value after the change
     ///     * output: update self to a new proof
-    ///     * error: invalid ciphersuite/parameters
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
     pub fn update<Blob: AsRef<[u8]>>(
         &mut self,
         prover_params: &ProverParams,
@@ -248,19 +539,27 @@ impl Proof {
         changed_index: usize,
         value_before: Blob,
         value_after: Blob,
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if !n_matches(self.n, prover_params.n) {
+            return Err(PointproofsError::MismatchedN);
         }
 
         // check indices are valid
         if proof_index >= prover_params.n || changed_index >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::IndexOutOfRange);
         }
+        let max_value_len = prover_params.max_value_len;
+        check_value_len(changed_index, value_before.as_ref(), max_value_len)?;
+        check_value_len(changed_index, value_after.as_ref(), max_value_len)?;
+        // a proof of unknown n now has the n of the parameters
+        self.n = prover_params.n;
 
         // update the proof
         // For updating your proof when someone else's value changes
@@ -268,9 +567,9 @@ impl Proof {
         // proof_param may be pre-computed -- the code will determine this
         // by checking the length of pre_comp
         if proof_index != changed_index {
-            let mut multiplier = hash_to_field_pointproofs(&value_before);
+            let mut multiplier = hash_to_field_ciphersuite(self.ciphersuite, &value_before);
             multiplier.negate();
-            multiplier.add_assign(&hash_to_field_pointproofs(&value_after));
+            multiplier.add_assign(&hash_to_field_ciphersuite(self.ciphersuite, &value_after));
 
             let param_index = changed_index + prover_params.n - proof_index;
             let res = pp_single_exp_helper(&prover_params, multiplier, param_index);
@@ -282,66 +581,272 @@ impl Proof {
         Ok(())
     }
 
-    /// Verify the proof.
+    /// Same as `Proof::update`, but returns the updated proof
+    /// and leaves self untouched.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the index for the value that is being changed
+    ///     * input: the value before the change
+    ///     * input: the value after the change
+    ///     * output: the new proof
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    pub fn updated<Blob: AsRef<[u8]>>(
+        &self,
+        prover_params: &ProverParams,
+        proof_index: usize,
+        changed_index: usize,
+        value_before: Blob,
+        value_after: Blob,
+    ) -> Result<Self, PointproofsError> {
+        let mut res = self.clone();
+        res.update(
+            prover_params,
+            proof_index,
+            changed_index,
+            value_before,
+            value_after,
+        )?;
+        Ok(res)
+    }
+
+    /// Updating an existing proof with a list of changes.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the changes, as (index, old value, new value)
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters, or oversized values
+    /// The result equals calling `Proof::update` for each change in turn;
+    /// changes to proof_index itself are skipped.
+    /// Nothing is updated if any of the changes is invalid.
+    pub fn batch_update<Blob: AsRef<[u8]>>(
+        &mut self,
+        prover_params: &ProverParams,
+        proof_index: usize,
+        changes: &[(usize, Blob, Blob)],
+    ) -> Result<(), PointproofsError> {
+        let plan = Self::plan_updates(prover_params, changes)?;
+        self.apply_plan(&plan, proof_index)
+    }
+
+    /// Prepares a list of changes for updating many proofs.
+    ///     * input: prover parameter set
+    ///     * input: the changes, as (index, old value, new value)
+    ///     * output: the update plan
+    ///     * error: invalid ciphersuite/parameters, or
+    ///     `PointproofsError::ValueTooLong` for a value longer than
+    ///     `ProverParams::max_value_len`
+    /// The per-change scalars hash(new value) - hash(old value) do not
+    /// depend on the proof, so they are computed once here and shared by
+    /// every `Proof::apply_plan`.
+    pub fn plan_updates<'a, Blob: AsRef<[u8]>>(
+        prover_params: &'a ProverParams,
+        changes: &[(usize, Blob, Blob)],
+    ) -> Result<UpdatePlan<'a>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+
+        // check indices are valid
+        for (changed_index, value_before, value_after) in changes.iter() {
+            if *changed_index >= prover_params.n {
+                return Err(PointproofsError::IndexOutOfRange);
+            }
+            let max_value_len = prover_params.max_value_len;
+            check_value_len(*changed_index, value_before.as_ref(), max_value_len)?;
+            check_value_len(*changed_index, value_after.as_ref(), max_value_len)?;
+        }
+
+        let changes = changes
+            .iter()
+            .map(|(changed_index, value_before, value_after)| {
+                // multiplier = hash(new_value) - hash(old_value)
+                let mut multiplier =
+                    hash_to_field_ciphersuite(prover_params.ciphersuite, value_before);
+                multiplier.negate();
+                multiplier.add_assign(&hash_to_field_ciphersuite(
+                    prover_params.ciphersuite,
+                    value_after,
+                ));
+                (*changed_index, multiplier.into_repr())
+            })
+            .collect();
+        Ok(UpdatePlan {
+            prover_params,
+            changes,
+        })
+    }
+
+    /// Updating an existing proof with a plan of changes.
+    ///     * input: the update plan
+    ///     * input: the index for the proof
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters
+    /// Same as `Proof::batch_update` for the changes of the plan.
+    pub fn apply_plan(
+        &mut self,
+        plan: &UpdatePlan,
+        proof_index: usize,
+    ) -> Result<(), PointproofsError> {
+        let prover_params = plan.prover_params;
+        if self.ciphersuite != prover_params.ciphersuite {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        if !n_matches(self.n, prover_params.n) {
+            return Err(PointproofsError::MismatchedN);
+        }
+        if proof_index >= prover_params.n {
+            return Err(PointproofsError::IndexOutOfRange);
+        }
+        // a proof of unknown n now has the n of the parameters
+        self.n = prover_params.n;
+
+        // the scalars and the bases for the changes of other indices
+        let mut scalars_u64: Vec<&[u64; 4]> = Vec::with_capacity(plan.changes.len());
+        let mut param_index: Vec<usize> = Vec::with_capacity(plan.changes.len());
+        for (changed_index, multiplier) in plan.changes.iter() {
+            if *changed_index != proof_index {
+                scalars_u64.push(&multiplier.0);
+                param_index.push(changed_index + prover_params.n - proof_index);
+            }
+        }
+        if param_index.is_empty() {
+            return Ok(());
+        }
+        let basis: Vec<PointproofsG1Affine> = param_index
+            .iter()
+            .map(|i| prover_params.generators[*i])
+            .collect();
+
+        // compute delta = \prod g[changed_index + n - proof_index]^multiplier
+        let delta = if prover_params.precomp.len() == 256 * prover_params.generators.len() {
+            let mut pre: Vec<PointproofsG1Affine> = Vec::with_capacity(param_index.len() * 256);
+            for e in param_index.iter() {
+                pre.extend_from_slice(&prover_params.precomp[*e * 256..(*e + 1) * 256]);
+            }
+            PointproofsG1Affine::sum_of_products_precomp_256(&basis, &scalars_u64, &pre)
+        } else {
+            multiexp_g1(&basis, &scalars_u64)
+        };
+        self.proof.add_assign(&delta);
+        Ok(())
+    }
+
+    /// Checks that updating a proof gives the same proof as
+    /// generating it from scratch with the updated values.
+    ///     * input: prover parameter set
+    ///     * input: the values before the change
+    ///     * input: the index for the proof
+    ///     * input: the index for the value that is being changed
+    ///     * input: the value before the change
+    ///     * input: the value after the change
+    ///     * output: if `Proof::update` matches `Proof::new`
+    /// Returns false if any of the inputs is invalid, including
+    /// when old_values\[changed_index\] is not the value before the change.
+    #[cfg(feature = "debug-verify")]
+    pub fn verify_update_consistency<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        old_values: &[Blob],
+        proof_index: usize,
+        changed_index: usize,
+        value_before: Blob,
+        value_after: Blob,
+    ) -> bool {
+        if changed_index >= old_values.len()
+            || !ct_eq(old_values[changed_index].as_ref(), value_before.as_ref())
+        {
+            return false;
+        }
+
+        // proof updated from the old values
+        let mut proof = match Self::new(prover_params, old_values, proof_index) {
+            Err(_e) => return false,
+            Ok(p) => p,
+        };
+        if proof
+            .update(
+                prover_params,
+                proof_index,
+                changed_index,
+                value_before.as_ref(),
+                value_after.as_ref(),
+            )
+            .is_err()
+        {
+            return false;
+        }
+
+        // proof generated from the new values
+        let mut new_values: Vec<&[u8]> = old_values.iter().map(|s| s.as_ref()).collect();
+        new_values[changed_index] = value_after.as_ref();
+        match Self::new(prover_params, &new_values, proof_index) {
+            Err(_e) => false,
+            Ok(p) => p == proof,
+        }
+    }
+
+    /// Deserialize a commitment, check it against a known fingerprint,
+    /// and verify the proof for it.
     ///     * input: the proof
     ///     * input: verifier parameter set
-    ///     * input: the commitment
+    ///     * input: the serialized commitment
+    ///     * input: the expected fingerprint of the commitment
     ///     * input: the value
     ///     * input: the index
-    ///     * output: if the proof is valid w.r.t. the rest of inputs
-    pub fn verify<Blob: AsRef<[u8]>>(
+    ///     * output: hash_to_field(value), if the proof is valid w.r.t.
+    ///     the rest of inputs
+    ///     * error: `InvalidEncoding` if the bytes are not a commitment,
+    ///     `FingerprintMismatch` if the commitment is not the expected one,
+    ///     and otherwise the check of `Proof::verify_and_open` that failed
+    /// The bytes must hold exactly one commitment.
+    pub fn verify_bytes_checked<Blob: AsRef<[u8]>>(
         &self,
         verifier_params: &VerifierParams,
-        com: &Commitment,
+        commitment_bytes: &[u8],
+        expected_fingerprint: &CommitmentFingerprint,
         value: Blob,
         index: usize,
-    ) -> bool {
-        if self.ciphersuite != verifier_params.ciphersuite || self.ciphersuite != com.ciphersuite {
-            #[cfg(debug_assertions)]
-            println!(
-                " ciphersuite fails {}, {}, {}",
-                self.ciphersuite, verifier_params.ciphersuite, com.ciphersuite
-            );
-            return false;
+    ) -> Result<Fr, VerifyFailure> {
+        let mut reader = commitment_bytes;
+        let com = match Commitment::deserialize(&mut reader, true) {
+            Ok(com) => com,
+            Err(_) => return Err(VerifyFailure::InvalidEncoding),
+        };
+        if !reader.is_empty() {
+            return Err(VerifyFailure::InvalidEncoding);
         }
-
-        if !check_ciphersuite(com.ciphersuite) {
-            return false;
+        if !ct_eq(&com.fingerprint(), expected_fingerprint) {
+            return Err(VerifyFailure::FingerprintMismatch);
         }
+        self.verify_and_open(verifier_params, &com, value, index)
+    }
 
-        if index >= verifier_params.n {
-            return false;
+    /// Deserialize a proof without the subgroup check, and verify it.
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the serialized proof
+    ///     * input: the value
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// The bytes must hold exactly one proof, see `Proof::deserialize_unchecked`.
+    ///
+    /// # Security
+    /// Unsound on untrusted input: only use it for proofs from a trusted
+    /// source that already checked they are in the group.
+    pub fn verify_unchecked<Blob: AsRef<[u8]>>(
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        proof_bytes: &[u8],
+        value: Blob,
+        index: usize,
+    ) -> bool {
+        let mut reader = proof_bytes;
+        match Self::deserialize_unchecked(&mut reader) {
+            Ok(proof) => reader.is_empty() && proof.verify(verifier_params, com, value, index),
+            Err(_) => false,
         }
-
-        // verification formula: e(com, param[n-index-1]) = gt_elt ^ hash(value) * e(proof, generator_of_g2)
-        // which is to check
-        //  e(com^hash_inverse,  param[n-index-1]) * e(proof^{-hash_inverse}, generator_of_g2)
-        //  ?= gt_elt
-        // We modify the formula as above in order to avoid slow exponentation in the target group (which is Fq12)
-        // and perform two scalar multiplication by to 1/hash(value) in G1 instead, which is considerably faster.
-        // We also move the pairing from the right-hand-side to the left-hand-side in order
-        // to take advantage of the pairing product computation, which is faster than two pairings.
-
-        // step 1. compute hash_inverse
-        let hash = hash_to_field_pointproofs(&value);
-        // we can safely assume that hash is invertible
-        // see `hash_to_field` function
-        let hash_inverse = hash.inverse().unwrap();
-
-        // step 2, compute com^hash_inverse and proof^{-hash_inverse}
-        let mut com_mut = com.commit;
-        let mut proof_mut = self.proof;
-        proof_mut.negate();
-        com_mut.mul_assign(hash_inverse);
-        proof_mut.mul_assign(hash_inverse);
-
-        // step 3. check pairing product
-        pointproofs_pairing_product(
-            com_mut.into_affine(),
-            verifier_params.generators[verifier_params.n - index - 1],
-            proof_mut.into_affine(),
-            PointproofsG2Affine::one(),
-        ) == verifier_params.gt_elt
     }
 
     /// Aggregates a vector of proofs from a same commitment into a single one.
@@ -351,53 +856,96 @@ impl Proof {
     ///     * input: the list of the values of the proofs
     ///     * input: parameter n (size of the vector)
     ///     * output: the aggregated proof
-    ///     * error: invalid ciphersuite/length, or hash to scalars failes
+    ///     * error: invalid ciphersuite/length, empty set of indices,
+    ///     an index repeated with different values, or hash to scalars failes
     ///     * Note:
+    ///         an index repeated with the same value is only counted once.
     ///         the aggregator does not check the validity of individual commit/proofs.
     ///         The caller may need to check them if they care for it.
+    ///         The scalars t_i are hashed from the commitment, the indices and the values,
+    ///         so the aggregated proof cannot be moved to another commitment.
     pub fn same_commit_aggregate<Blob: AsRef<[u8]>>(
         commit: &Commitment,
         proofs: &[Self],
         set: &[usize],
         value_sub_vector: &[Blob],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // check that the csids are valid/match
         let csid = commit.ciphersuite;
         if !check_ciphersuite(csid) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         for e in proofs.iter() {
             if e.ciphersuite != csid {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         // check that the length of proofs and sets match
         if proofs.len() != set.len() || proofs.len() != value_sub_vector.len() {
-            return Err(ERR_INDEX_PROOF_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexProofMismatch);
         }
-
-        if !misc::has_unique_elements(set) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+        if set.is_empty() {
+            return Err(PointproofsError::EmptySet);
         }
 
+        // collapse the repeated openings, so that each index is counted once
+        let (set, value_sub_vector, positions) = misc::dedup_openings(set, value_sub_vector)?;
+
         // get the list of scalas
-        let ti = hash_to_ti_repr(commit, set, value_sub_vector, n)?;
+        let ti = hash_to_ti_repr(commit, &set, &value_sub_vector, n)?;
         let scalars_u64: Vec<&[u64; 4]> = ti.iter().map(|s| &s.0).collect();
 
-        let mut bases: Vec<PointproofsG1> = proofs.iter().map(|s| s.proof).collect();
+        let mut bases: Vec<PointproofsG1> = positions.iter().map(|k| proofs[*k].proof).collect();
         CurveProjective::batch_normalization(&mut bases);
         let bases_affine: Vec<PointproofsG1Affine> =
             bases.iter().map(|s| s.into_affine()).collect();
         // proof = \prod proofs[i]^ti[i]
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = multiexp_g1(&bases_affine[..], &scalars_u64);
 
         Ok(Proof {
             ciphersuite: csid,
+            n,
             proof,
         })
     }
 
+    /// Aggregates proofs from a same commitment into a single proof.
+    ///     * input: the commitment
+    ///     * input: the list of proofs
+    ///     * input: the list of the values of the proofs
+    ///     * input: the list of the indices of the proofs
+    ///     * output: the aggregated proof
+    ///     * error: the errors of `Proof::same_commit_aggregate`,
+    ///     a repeated index, or `PointproofsError::MismatchedN` if the n
+    ///     recorded in the commitment and the proofs is unknown or not the same
+    /// Same as `Proof::same_commit_aggregate` with that n, except that
+    /// an index may not appear twice, even with the same value.
+    /// The result is checked with `Proof::verify_aggregated`.
+    pub fn aggregate<Blob: AsRef<[u8]>>(
+        com: &Commitment,
+        proofs: &[Self],
+        values: &[Blob],
+        indices: &[usize],
+    ) -> Result<Self, PointproofsError> {
+        if !misc::has_unique_elements(indices) {
+            return Err(PointproofsError::DuplicatedIndex);
+        }
+        // the n of the parameters, from the first of them that knows it
+        let mut n = com.n;
+        for proof in proofs.iter() {
+            if n == 0 {
+                n = proof.n;
+            } else if !n_matches(proof.n, n) {
+                return Err(PointproofsError::MismatchedN);
+            }
+        }
+        if n == 0 {
+            return Err(PointproofsError::MismatchedN);
+        }
+        Self::same_commit_aggregate(com, proofs, indices, values, n)
+    }
+
     /// Aggregate an array of proofs, each
     /// proof is a same-commit aggregated proof.
     ///     * input: a list of commitments
@@ -408,6 +956,8 @@ impl Proof {
     ///     * Note:
     ///         * The aggregator does not check the validity of the proof.
     ///         * The proofs are already aggregated within each commitment.
+    ///         * An index repeated with the same value is only counted once,
+    ///         and one repeated with different values is an error.
     ///     * Steps:
     ///         * t\[j\] = hash_to_tj(...)
     ///         * return prod proofs\[j\]^t\[j\]
@@ -417,29 +967,26 @@ impl Proof {
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // check ciphersuite
         let ciphersuite = commits[0].ciphersuite;
         if !check_ciphersuite(ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         for e in commits.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         for e in proofs.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         for e in set.iter() {
-            if !misc::has_unique_elements(e) {
-                return Err(ERR_DUPLICATED_INDEX.to_owned());
-            }
             for ee in e.iter() {
                 if *ee >= n {
-                    return Err(ERR_INVALID_INDEX.to_owned());
+                    return Err(PointproofsError::IndexOutOfRange);
                 }
             }
         }
@@ -458,9 +1005,20 @@ impl Proof {
                 set.len(),
                 value_sub_vector.len()
             );
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::CrossCommitSizeMismatch);
         };
 
+        // collapse the repeated openings, so that each index is counted once
+        let mut dedup_set: Vec<Vec<usize>> = Vec::with_capacity(set.len());
+        let mut dedup_values: Vec<Vec<&[u8]>> = Vec::with_capacity(set.len());
+        for j in 0..set.len() {
+            let (e, v, _) = misc::dedup_openings(&set[j], &value_sub_vector[j])?;
+            dedup_set.push(e);
+            dedup_values.push(v);
+        }
+        let set = dedup_set;
+        let value_sub_vector = dedup_values;
+
         // if commit.len() == 1, return the aggregated proof
         if commits.len() == 1 {
             return Ok(proofs[0].clone());
@@ -469,7 +1027,7 @@ impl Proof {
         // start aggregation
         let scalars = hash_to_tj_repr(&commits, &set, &value_sub_vector, n)?;
         if scalars.len() != proofs.len() {
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::CrossCommitSizeMismatch);
         }
 
         let scalars_u64: Vec<&[u64; 4]> = scalars.iter().map(|s| &s.0).collect();
@@ -480,9 +1038,13 @@ impl Proof {
             bases.iter().map(|s| s.into_affine()).collect();
 
         // proof = \prod pi[i] ^ tj[i]
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = multiexp_g1(&bases_affine[..], &scalars_u64);
 
-        Ok(Proof { ciphersuite, proof })
+        Ok(Proof {
+            ciphersuite,
+            n,
+            proof,
+        })
     }
 
     /// Aggregate a 2-dim array of proofs, each row corresponding to a
@@ -493,10 +1055,12 @@ impl Proof {
     ///     * input: a 2-dim array of values for the proofs, each value for a proof
     ///     * input: parameter n (size of the vector)
     ///     * output: an aggregated proof
-    ///     * error: invalid ciphersuite, input vectors length does not match
+    ///     * error: invalid ciphersuite, input vectors length does not match,
+    ///     an index repeated with different values
     ///     * Note:
     ///         1. The aggregator does not check the validity of the proof.
     ///         2. The proofs are already aggregated within each commitment.
+    ///         3. An index repeated with the same value is only counted once.
     ///     * Steps:
     ///         1. t[j] = hash_to_tj(...)
     ///         2. pi[j] = same_commit_aggregate(...)
@@ -507,31 +1071,28 @@ impl Proof {
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // check ciphersuite
         let ciphersuite = commits[0].ciphersuite;
         if !check_ciphersuite(ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite);
         }
         for e in commits.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::InvalidCiphersuite);
             }
         }
         for e in proofs.iter() {
             for ee in e.iter() {
                 if ee.ciphersuite != ciphersuite {
-                    return Err(ERR_CIPHERSUITE.to_owned());
+                    return Err(PointproofsError::InvalidCiphersuite);
                 }
             }
         }
         for e in set.iter() {
-            if !misc::has_unique_elements(e) {
-                return Err(ERR_DUPLICATED_INDEX.to_owned());
-            }
             for ee in e.iter() {
                 if *ee >= n {
-                    return Err(ERR_INVALID_INDEX.to_owned());
+                    return Err(PointproofsError::IndexOutOfRange);
                 }
             }
         }
@@ -550,9 +1111,30 @@ impl Proof {
                 set.len(),
                 value_sub_vector.len()
             );
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::CrossCommitSizeMismatch);
         };
 
+        // collapse the repeated openings, so that each index is counted once
+        let mut dedup_set: Vec<Vec<usize>> = Vec::with_capacity(set.len());
+        let mut dedup_values: Vec<Vec<&[u8]>> = Vec::with_capacity(set.len());
+        let mut positions: Vec<Vec<usize>> = Vec::with_capacity(set.len());
+        for j in 0..set.len() {
+            if proofs[j].len() != set[j].len() {
+                return Err(PointproofsError::IndexProofMismatch);
+            }
+            let (e, v, k) = misc::dedup_openings(&set[j], &value_sub_vector[j])?;
+            dedup_set.push(e);
+            dedup_values.push(v);
+            positions.push(k);
+        }
+        let set = dedup_set;
+        let value_sub_vector = dedup_values;
+        let proofs: Vec<Vec<Proof>> = positions
+            .iter()
+            .enumerate()
+            .map(|(j, k)| k.iter().map(|e| proofs[j][*e].clone()).collect())
+            .collect();
+
         // if commit.len() == 1, call normal aggregation
         if commits.len() == 1 {
             return Self::same_commit_aggregate(
@@ -597,9 +1179,139 @@ impl Proof {
             bases.iter().map(|s| s.into_affine()).collect();
 
         // proof = \prod pi[i] ^ {tj[i] * ti[i,j]}
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = multiexp_g1(&bases_affine[..], &scalars_u64);
 
-        Ok(Proof { ciphersuite, proof })
+        Ok(Proof {
+            ciphersuite,
+            n,
+            proof,
+        })
+    }
+
+    /// Aggregate proofs across commitments that may have different parameters,
+    /// hence different n.
+    ///     * input: a list of commitments
+    ///     * input: a 2-dim array of proofs, the j-th vector for commitment j
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of values
+    ///     * input: the parameter n of each commitment
+    ///     * output: an aggregated proof
+    ///     * error: invalid ciphersuite/length, out of range indices,
+    ///     an index repeated with different values, or no opening at all
+    ///     * Note:
+    ///         * An index repeated with the same value is only counted once.
+    ///         * The aggregator does not check the validity of the proofs.
+    ///         * A commitment without openings is skipped.
+    ///         * The scalars t_i,j are hashed from the commitments, indices and values,
+    ///         so a proof for one commitment cannot be replayed against another.
+    /// With a single n and no empty set, the output is the same
+    /// as `Proof::cross_commit_aggregate_full`.
+    pub fn cross_aggregate<Blob: AsRef<[u8]>>(
+        commits: &[Commitment],
+        proofs: &[Vec<Self>],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+        n: &[usize],
+    ) -> Result<Self, PointproofsError> {
+        // check the length are correct
+        if commits.len() != proofs.len()
+            || commits.len() != set.len()
+            || commits.len() != value_sub_vector.len()
+            || commits.len() != n.len()
+        {
+            return Err(PointproofsError::CrossCommitSizeMismatch);
+        };
+        if commits.is_empty() {
+            return Err(PointproofsError::EmptySet);
+        }
+
+        // check ciphersuite
+        let ciphersuite = commits[0].ciphersuite;
+        if !check_ciphersuite(ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        for e in commits.iter() {
+            if e.ciphersuite != ciphersuite {
+                return Err(PointproofsError::InvalidCiphersuite);
+            }
+        }
+        for e in proofs.iter() {
+            for ee in e.iter() {
+                if ee.ciphersuite != ciphersuite {
+                    return Err(PointproofsError::InvalidCiphersuite);
+                }
+            }
+        }
+
+        // check the indices against the n of their own commitment
+        for j in 0..commits.len() {
+            if proofs[j].len() != set[j].len() || value_sub_vector[j].len() != set[j].len() {
+                return Err(PointproofsError::IndexProofMismatch);
+            }
+            for e in set[j].iter() {
+                if *e >= n[j] {
+                    return Err(PointproofsError::IndexOutOfRange);
+                }
+            }
+        }
+
+        // skip the commitments without openings
+        let active: Vec<usize> = (0..commits.len()).filter(|j| !set[*j].is_empty()).collect();
+        if active.is_empty() {
+            return Err(PointproofsError::EmptySet);
+        }
+        if active.len() == 1 {
+            let j = active[0];
+            return Self::same_commit_aggregate(
+                &commits[j],
+                &proofs[j],
+                &set[j],
+                &value_sub_vector[j],
+                n[j],
+            );
+        }
+        let commits: Vec<Commitment> = active.iter().map(|j| commits[*j].clone()).collect();
+        // collapse the repeated openings, so that each index is counted once
+        let mut dedup_set: Vec<Vec<usize>> = Vec::with_capacity(active.len());
+        let mut values: Vec<Vec<&[u8]>> = Vec::with_capacity(active.len());
+        let mut positions: Vec<Vec<usize>> = Vec::with_capacity(active.len());
+        for j in active.iter() {
+            let (e, v, k) = misc::dedup_openings(&set[*j], &value_sub_vector[*j])?;
+            dedup_set.push(e);
+            values.push(v);
+            positions.push(k);
+        }
+        let set = dedup_set;
+        // the indices are already checked against their own n
+        let max_n = active.iter().map(|j| n[*j]).max().unwrap_or(0);
+
+        // scalars = tj[j] * ti[j,k]
+        let tj = hash_to_tj_fr(&commits, &set, &values, max_n)?;
+        let mut scalars_repr: Vec<FrRepr> = vec![];
+        let mut bases: Vec<PointproofsG1> = vec![];
+        for (k, j) in active.iter().enumerate() {
+            let ti = hash_to_ti_fr(&commits[k], &set[k], &values[k], n[*j])?;
+            for e in ti.iter() {
+                let mut tmp = *e;
+                tmp.mul_assign(&tj[k]);
+                scalars_repr.push(tmp.into_repr());
+            }
+            bases.extend(positions[k].iter().map(|e| proofs[*j][*e].proof));
+        }
+        let scalars_u64: Vec<&[u64; 4]> = scalars_repr.iter().map(|s| &s.0).collect();
+        CurveProjective::batch_normalization(&mut bases);
+        let bases_affine: Vec<PointproofsG1Affine> =
+            bases.iter().map(|s| s.into_affine()).collect();
+
+        // proof = \prod pi[j,k] ^ {tj[j] * ti[j,k]}
+        let proof = multiexp_g1(&bases_affine[..], &scalars_u64);
+
+        // the commitments may be for different n
+        Ok(Proof {
+            ciphersuite,
+            n: 0,
+            proof,
+        })
     }
 
     /// batch verify a proof for a list of values/indices
@@ -609,12 +1321,54 @@ impl Proof {
     ///     * input: the list of indices
     ///     * input: the list of values
     ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// An index repeated with the same value is only counted once,
+    /// and one repeated with different values fails verification.
+    /// A value longer than `VerifierParams::max_value_len` fails
+    /// verification before any value is hashed.
     pub fn same_commit_batch_verify<Blob: AsRef<[u8]>>(
         &self,
         verifier_params: &VerifierParams,
         com: &Commitment,
         set: &[usize],
         value_sub_vector: &[Blob],
+    ) -> bool {
+        self.same_commit_batch_verify_counted(
+            verifier_params,
+            com,
+            set,
+            value_sub_vector,
+            &mut VerifyCost::default(),
+        )
+    }
+
+    /// Verify a proof from `Proof::aggregate`.
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the list of values
+    ///     * input: the list of indices
+    ///     * input: the aggregated proof
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// Same as `Proof::same_commit_batch_verify`, except that
+    /// a repeated index fails verification, even with the same value.
+    pub fn verify_aggregated<Blob: AsRef<[u8]>>(
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        values: &[Blob],
+        indices: &[usize],
+        agg_proof: &Self,
+    ) -> bool {
+        misc::has_unique_elements(indices)
+            && agg_proof.same_commit_batch_verify(verifier_params, com, indices, values)
+    }
+
+    /// `Proof::same_commit_batch_verify`, adding the operations it performs to cost.
+    fn same_commit_batch_verify_counted<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        set: &[usize],
+        value_sub_vector: &[Blob],
+        cost: &mut VerifyCost,
     ) -> bool {
         // we want to check if
         //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i})
@@ -630,6 +1384,9 @@ impl Proof {
         if !check_ciphersuite(com.ciphersuite) {
             return false;
         }
+        if com.commit.is_zero() {
+            return false;
+        }
         if com.ciphersuite != verifier_params.ciphersuite || com.ciphersuite != self.ciphersuite {
             #[cfg(debug_assertions)]
             println!(
@@ -639,9 +1396,23 @@ impl Proof {
 
             return false;
         }
-        if set.len() != value_sub_vector.len() {
+        if !n_matches(self.n, verifier_params.n) || !n_matches(com.n, verifier_params.n) {
             return false;
         }
+        if set.len() != value_sub_vector.len() || set.is_empty() {
+            return false;
+        }
+        // reject oversized values before hashing them
+        if check_value_lens(value_sub_vector, verifier_params.max_value_len).is_err() {
+            return false;
+        }
+        // collapse the repeated openings, as the aggregator does
+        let (dedup_set, dedup_values, _) = match misc::dedup_openings(set, value_sub_vector) {
+            Err(_e) => return false,
+            Ok(p) => p,
+        };
+        let set = &dedup_set[..];
+        let value_sub_vector = &dedup_values[..];
         if value_sub_vector.len() > verifier_params.n {
             return false;
         }
@@ -650,13 +1421,18 @@ impl Proof {
                 return false;
             }
         }
-        if !misc::has_unique_elements(set) {
-            return false;
-        }
 
         // if the length == 1, call normal verification method
         if set.len() == 1 {
-            return self.verify(&verifier_params, &com, value_sub_vector[0].as_ref(), set[0]);
+            return self
+                .verify_counted(
+                    &verifier_params,
+                    &com,
+                    value_sub_vector[0].as_ref(),
+                    set[0],
+                    cost,
+                )
+                .is_ok();
         }
         // 1. compute tmp
         // 1.1 get the list of scalas, return false if this failed
@@ -668,7 +1444,7 @@ impl Proof {
         // 1.2 tmp = 1/\sum value_i*t_i
         let mut tmp = Fr::zero();
         for k in 0..set.len() {
-            let mut mi = hash_to_field_pointproofs(value_sub_vector[k].as_ref());
+            let mut mi = hash_to_field_ciphersuite(com.ciphersuite, value_sub_vector[k].as_ref());
             mi.mul_assign(&ti[k]);
             tmp.add_assign(&mi);
         }
@@ -696,6 +1472,8 @@ impl Proof {
             .map(|index| verifier_params.generators[verifier_params.n - index - 1])
             .collect();
         let scalars_u64: Vec<&[u64; 4]> = ti_repr.iter().map(|s| &s.0).collect();
+        cost.g2_multiexps += 1;
+        cost.g2_multiexp_bases += bases.len();
         let param_subset_sum = {
             // the second condition `n <= 1024` comes from benchmarking
             // pre-computation is faster only when the #basis is <1024
@@ -717,7 +1495,7 @@ impl Proof {
                     &bases_precomp,
                 )
             } else {
-                PointproofsG2Affine::sum_of_products(&bases, &scalars_u64)
+                multiexp_g2(&bases, &scalars_u64)
             }
         };
 
@@ -725,8 +1503,10 @@ impl Proof {
         let mut proof_mut = self.proof;
         tmp.negate();
         proof_mut.mul_assign(tmp);
+        cost.g1_muls += 1;
 
         // 3 pairing product
+        cost.pairings += 2;
         pointproofs_pairing_product(
             com.commit.into_affine(),
             param_subset_sum.into_affine(),
@@ -735,6 +1515,190 @@ impl Proof {
         ) == verifier_params.gt_elt
     }
 
+    /// Verify many proofs under the same commitment at once.
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: a list of (index, value, proof)
+    ///     * output: if every proof is valid w.r.t. the commitment,
+    ///     its value and its index
+    /// Unlike `Proof::same_commit_aggregate`, this does not produce a proof
+    /// for a third party: the equations of the openings are combined with
+    /// random scalars r_i and checked with a single pairing product
+    /// of two pairings.
+    /// The r_i are drawn from OsRng; returns false if OsRng is not available.
+    /// A value longer than `VerifierParams::max_value_len` fails
+    /// verification before any value is hashed.
+    pub fn batch_verify_same_commit(
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        openings: &[(usize, &[u8], &Proof)],
+    ) -> bool {
+        match OsRng::new() {
+            Ok(mut rng) => {
+                Self::batch_verify_same_commit_with_rng(verifier_params, com, openings, &mut rng)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Same as `Proof::batch_verify_same_commit`, but the random scalars
+    /// are drawn from the caller's rng.
+    /// A batch with an invalid opening passes only if the rng output is
+    /// a root of a fixed non-zero linear form, i.e., with probability about 1/r;
+    /// the scalars must therefore be unpredictable to whoever made the proofs.
+    pub fn batch_verify_same_commit_with_rng<R: RngCore>(
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        openings: &[(usize, &[u8], &Proof)],
+        rng: &mut R,
+    ) -> bool {
+        // we want to check if, for every opening i,
+        //   e(com, g2^{\alpha^{N+1-index_i}}) = e(proof_i, g2) * e(g1, g2)^{alpha^{N+1} value_i}
+        // which is implied (except with probability ~1/r) by
+        //   e(com, g2^{\sum_i \alpha^{N+1-index_i} r_i * tmp})
+        //    * e(\prod_i proof_i^{-r_i * tmp}, g2)
+        //    ?= e(g1, g2)^{alpha^N+1}
+        // where
+        //   tmp = 1/ \sum value_i*r_i
+
+        // 0. check the validity of the inputs: csid, indices, etc
+        if openings.is_empty() || com.commit.is_zero() {
+            return false;
+        }
+        if !check_ciphersuite(com.ciphersuite) || com.ciphersuite != verifier_params.ciphersuite {
+            return false;
+        }
+        if !n_matches(com.n, verifier_params.n) {
+            return false;
+        }
+        for opening in openings {
+            if opening.0 >= verifier_params.n || opening.2.ciphersuite != com.ciphersuite {
+                return false;
+            }
+            if !n_matches(opening.2.n, verifier_params.n) {
+                return false;
+            }
+            // reject oversized values before hashing them
+            if opening.1.len() > verifier_params.max_value_len {
+                return false;
+            }
+        }
+
+        // 1. draw the r_i and compute tmp = 1/\sum value_i*r_i
+        let mut ri: Vec<Fr> = Vec::with_capacity(openings.len());
+        let mut tmp = Fr::zero();
+        for opening in openings {
+            let mut r_bytes = [0u8; 64];
+            rng.fill_bytes(&mut r_bytes);
+            let r = hash_to_field_pointproofs(&r_bytes[..]);
+            let mut mi = hash_to_field_ciphersuite(com.ciphersuite, opening.1);
+            mi.mul_assign(&r);
+            tmp.add_assign(&mi);
+            ri.push(r);
+        }
+        // tmp == 0 happens with probability ~1/r
+        let tmp = match tmp.inverse() {
+            Some(p) => p,
+            None => return false,
+        };
+        let mut neg_tmp = tmp;
+        neg_tmp.negate();
+
+        // 2. g2^{\sum_i \alpha^{N+1-index_i} r_i * tmp}
+        let g2_scalars: Vec<FrRepr> = ri
+            .iter()
+            .map(|r| {
+                let mut s = *r;
+                s.mul_assign(&tmp);
+                s.into_repr()
+            })
+            .collect();
+        let g2_bases: Vec<PointproofsG2Affine> = openings
+            .iter()
+            .map(|opening| verifier_params.generators[verifier_params.n - opening.0 - 1])
+            .collect();
+        let g2_scalars_u64: Vec<&[u64; 4]> = g2_scalars.iter().map(|s| &s.0).collect();
+        let param_subset_sum = multiexp_g2(&g2_bases, &g2_scalars_u64);
+
+        // 3. \prod_i proof_i^{-r_i * tmp}
+        let g1_scalars: Vec<FrRepr> = ri
+            .iter()
+            .map(|r| {
+                let mut s = *r;
+                s.mul_assign(&neg_tmp);
+                s.into_repr()
+            })
+            .collect();
+        let mut g1_bases: Vec<PointproofsG1> =
+            openings.iter().map(|opening| opening.2.proof).collect();
+        CurveProjective::batch_normalization(&mut g1_bases);
+        let g1_bases_affine: Vec<PointproofsG1Affine> =
+            g1_bases.iter().map(|s| s.into_affine()).collect();
+        let g1_scalars_u64: Vec<&[u64; 4]> = g1_scalars.iter().map(|s| &s.0).collect();
+        let proof_sum = multiexp_g1(&g1_bases_affine, &g1_scalars_u64);
+
+        // 4. pairing product
+        pointproofs_pairing_product(
+            com.commit.into_affine(),
+            param_subset_sum.into_affine(),
+            proof_sum.into_affine(),
+            PointproofsG2Affine::one(),
+        ) == verifier_params.gt_elt
+    }
+
+    /// Verify many proofs under the same commitment, given as an iterator
+    /// of (index, value, proof), e.g., the entries of a map of openings.
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the (index, value, proof) entries
+    ///     * output: if every proof is valid w.r.t. the commitment,
+    ///     its value and its index
+    /// The entries are checked with `Proof::batch_verify_same_commit`;
+    /// an empty iterator is valid.
+    /// Returns false at the first entry whose index is out of range or whose
+    /// ciphersuite does not match, without consuming the rest of the iterator.
+    pub fn verify_pairs<'a, I>(verifier_params: &VerifierParams, com: &Commitment, pairs: I) -> bool
+    where
+        I: IntoIterator<Item = (usize, &'a [u8], &'a Proof)>,
+    {
+        let mut openings: Vec<(usize, &[u8], &Proof)> = vec![];
+        for opening in pairs {
+            if opening.0 >= verifier_params.n || opening.2.ciphersuite != com.ciphersuite {
+                return false;
+            }
+            openings.push(opening);
+        }
+        if openings.is_empty() {
+            return true;
+        }
+        Self::batch_verify_same_commit(verifier_params, com, &openings)
+    }
+
+    /// Verify a proof for the contiguous range of indices [lo, hi)
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the values at indices lo..hi
+    ///     * input: the first index of the range
+    ///     * input: the index after the last one of the range
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// An empty or out of bound range is never valid.
+    pub fn verify_subvector<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        values_slice: &[Blob],
+        lo: usize,
+        hi: usize,
+    ) -> bool {
+        if lo >= hi || hi > verifier_params.n || values_slice.len() != hi - lo {
+            return false;
+        }
+
+        let indices: Vec<usize> = (lo..hi).collect();
+        self.same_commit_batch_verify(verifier_params, com, &indices, values_slice)
+    }
+
     /// Verify a proof which was aggregated from 2-dim array of proofs
     ///     * input: the proof
     ///     * input: verifier parameter set
@@ -742,6 +1706,10 @@ impl Proof {
     ///     * input: a 2-dim array of indices
     ///     * input: a 2-dim array of values
     ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// An index repeated with the same value is only counted once,
+    /// and one repeated with different values fails verification.
+    /// A value longer than `VerifierParams::max_value_len` fails
+    /// verification before any value is hashed.
     pub fn cross_commit_batch_verify<Blob: AsRef<[u8]>>(
         &self,
         verifier_params: &VerifierParams,
@@ -749,12 +1717,96 @@ impl Proof {
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
     ) -> bool {
-        // check ciphersuite
+        self.cross_commit_batch_verify_counted(
+            verifier_params,
+            com,
+            set,
+            value_sub_vector,
+            &mut VerifyCost::default(),
+        )
+    }
+
+    /// Same as `Proof::cross_commit_batch_verify`, and
+    /// reports the operations that the verification performed.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the list of commitments
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of values
+    ///     * output: if the proof is valid w.r.t. the rest of inputs,
+    ///     and the cost of the verification
+    /// For valid inputs, the cost matches `Proof::verify_cost_estimate`;
+    /// inputs that are rejected early cost less.
+    pub fn verify_with_cost<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &[Commitment],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+    ) -> (bool, VerifyCost) {
+        let mut cost = VerifyCost::default();
+        let res = self.cross_commit_batch_verify_counted(
+            verifier_params,
+            com,
+            set,
+            value_sub_vector,
+            &mut cost,
+        );
+        (res, cost)
+    }
+
+    /// Estimates the cost of verifying an aggregated proof, without verifying it.
+    ///     * input: the number of commitments
+    ///     * input: the total number of openings, over all commitments
+    ///     * output: the operations `Proof::cross_commit_batch_verify` performs
+    /// A single commitment is verified with `same_commit_batch_verify`,
+    /// and a single opening with `verify`.
+    /// Returns zero for inputs that cannot be valid,
+    /// i.e., no commitment or fewer openings than commitments.
+    pub fn verify_cost_estimate(num_commitments: usize, num_openings: usize) -> VerifyCost {
+        if num_commitments == 0 || num_openings < num_commitments {
+            return VerifyCost::default();
+        }
+        if num_openings == 1 {
+            // e(com^{1/h}, g2^{alpha^{n-i}}) * e(proof^{-1/h}, g2)
+            return VerifyCost {
+                pairings: 2,
+                g1_muls: 2,
+                g2_multiexps: 0,
+                g2_multiexp_bases: 0,
+            };
+        }
+        // one G2 multi-exponentiation per commitment, over its openings;
+        // one pairing per commitment, and one for the proof
+        VerifyCost {
+            pairings: num_commitments + 1,
+            g1_muls: 1,
+            g2_multiexps: num_commitments,
+            g2_multiexp_bases: num_openings,
+        }
+    }
+
+    /// `Proof::cross_commit_batch_verify`, adding the operations it performs to cost.
+    fn cross_commit_batch_verify_counted<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &[Commitment],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+        cost: &mut VerifyCost,
+    ) -> bool {
+        // check ciphersuite and n
         if self.ciphersuite != verifier_params.ciphersuite {
             return false;
         }
+        if !n_matches(self.n, verifier_params.n) {
+            return false;
+        }
         for e in com {
-            if self.ciphersuite != e.ciphersuite {
+            if self.ciphersuite != e.ciphersuite || e.commit.is_zero() {
+                return false;
+            }
+            if !n_matches(e.n, verifier_params.n) {
                 return false;
             }
         }
@@ -765,29 +1817,40 @@ impl Proof {
             // length does not match
             return false;
         }
+        // collapse the repeated openings, as the aggregator does
+        let mut dedup_set: Vec<Vec<usize>> = Vec::with_capacity(num_commit);
+        let mut dedup_values: Vec<Vec<&[u8]>> = Vec::with_capacity(num_commit);
         for j in 0..num_commit {
-            if set[j].len() != value_sub_vector[j].len()
-                || set[j].is_empty()
-                || set[j].len() > verifier_params.n
-            {
+            if set[j].len() != value_sub_vector[j].len() || set[j].is_empty() {
                 // length does not match
                 return false;
             }
-        }
-        for e in set.iter() {
-            if !misc::has_unique_elements(e) {
+            // reject oversized values before hashing them
+            if check_value_lens(&value_sub_vector[j], verifier_params.max_value_len).is_err() {
+                return false;
+            }
+            let (e, v, _) = match misc::dedup_openings(&set[j], &value_sub_vector[j]) {
+                Err(_e) => return false,
+                Ok(p) => p,
+            };
+            if e.len() > verifier_params.n {
                 return false;
             }
+            dedup_set.push(e);
+            dedup_values.push(v);
         }
+        let set = dedup_set;
+        let value_sub_vector = dedup_values;
 
         // handled the case where there is only 1 commit
         if num_commit == 1 {
             // call normal batch verification
-            return self.same_commit_batch_verify(
+            return self.same_commit_batch_verify_counted(
                 &verifier_params,
                 &com[0],
                 &set[0],
                 &value_sub_vector[0],
+                cost,
             );
         }
 
@@ -818,7 +1881,8 @@ impl Proof {
             // tmp2 = sum_i m_ij * t_ij
             for k in 0..ti_s[j].len() {
                 let mut tmp3 = ti_s[j][k];
-                let mij = hash_to_field_pointproofs(value_sub_vector[j][k].as_ref());
+                let mij =
+                    hash_to_field_ciphersuite(self.ciphersuite, value_sub_vector[j][k].as_ref());
                 tmp3.mul_assign(&mij);
                 tmp2.add_assign(&tmp3);
             }
@@ -849,6 +1913,7 @@ impl Proof {
         let mut tmp2 = self.proof;
         tmp2.negate();
         tmp2.mul_assign(tmp_inverse);
+        cost.g1_muls += 1;
         g1_proj.push(tmp2);
 
         // convert g1_proj into g1_affine
@@ -876,6 +1941,8 @@ impl Proof {
                 scalars_u64.push(t.into_repr().0);
             }
             let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+            cost.g2_multiexps += 1;
+            cost.g2_multiexp_bases += bases.len();
 
             let param_subset_sum = {
                 // the second condition `n <= 1024` comes from benchmarking
@@ -898,7 +1965,7 @@ impl Proof {
                         &bases_precomp,
                     )
                 } else {
-                    PointproofsG2Affine::sum_of_products(&bases, &scalars_u64_ref)
+                    multiexp_g2(&bases, &scalars_u64_ref)
                 }
             };
             g2_proj.push(param_subset_sum);
@@ -909,6 +1976,198 @@ impl Proof {
         PointproofsG2::batch_normalization(&mut g2_proj);
         let g2_vec: Vec<PointproofsG2Affine> = g2_proj.iter().map(|s| s.into_affine()).collect();
         // now check the pairing product ?= verifier_params.gt_elt
+        cost.pairings += g1_vec.len();
         pointproofs_pairing_multi_product(&g1_vec[..], &g2_vec[..]) == verifier_params.gt_elt
     }
+
+    /// Verify a proof from `Proof::cross_aggregate`.
+    ///     * input: the proof
+    ///     * input: the verifier parameters of each commitment
+    ///     * input: the list of commitments
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of values
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// A commitment without openings is skipped.
+    /// An index repeated with the same value is only counted once,
+    /// and one repeated with different values fails verification.
+    /// A value longer than the `VerifierParams::max_value_len` of its
+    /// commitment fails verification before any value is hashed.
+    /// Since the parameters differ, the right hand side costs one
+    /// exponentiation in the target group per commitment,
+    /// on top of one pairing per commitment.
+    pub fn cross_aggregate_verify<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &[&VerifierParams],
+        com: &[Commitment],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+    ) -> bool {
+        // check length
+        let num_commit = com.len();
+        if num_commit != verifier_params.len()
+            || num_commit != set.len()
+            || num_commit != value_sub_vector.len()
+        {
+            return false;
+        }
+
+        // check ciphersuite and indices
+        if !check_ciphersuite(self.ciphersuite) {
+            return false;
+        }
+        for j in 0..num_commit {
+            if com[j].ciphersuite != self.ciphersuite
+                || verifier_params[j].ciphersuite != self.ciphersuite
+                || com[j].commit.is_zero()
+            {
+                return false;
+            }
+            // a proof from `Proof::cross_aggregate` records no n,
+            // as its commitments may be for several n
+            if !n_matches(com[j].n, verifier_params[j].n)
+                || !n_matches(self.n, verifier_params[j].n)
+            {
+                return false;
+            }
+            if set[j].len() != value_sub_vector[j].len() {
+                return false;
+            }
+            // reject oversized values before hashing them
+            if check_value_lens(&value_sub_vector[j], verifier_params[j].max_value_len).is_err() {
+                return false;
+            }
+            for e in set[j].iter() {
+                if *e >= verifier_params[j].n {
+                    return false;
+                }
+            }
+        }
+
+        // skip the commitments without openings
+        let active: Vec<usize> = (0..num_commit).filter(|j| !set[*j].is_empty()).collect();
+        if active.is_empty() {
+            return false;
+        }
+        if active.len() == 1 {
+            let j = active[0];
+            return self.same_commit_batch_verify(
+                verifier_params[j],
+                &com[j],
+                &set[j],
+                &value_sub_vector[j],
+            );
+        }
+        let commits: Vec<Commitment> = active.iter().map(|j| com[*j].clone()).collect();
+        // collapse the repeated openings, as the aggregator does
+        let mut dedup_set: Vec<Vec<usize>> = Vec::with_capacity(active.len());
+        let mut values: Vec<Vec<&[u8]>> = Vec::with_capacity(active.len());
+        for j in active.iter() {
+            let (e, v, _) = match misc::dedup_openings(&set[*j], &value_sub_vector[*j]) {
+                Err(_e) => return false,
+                Ok(p) => p,
+            };
+            if e.len() > verifier_params[*j].n {
+                return false;
+            }
+            dedup_set.push(e);
+            values.push(v);
+        }
+        let set = dedup_set;
+        let max_n = active
+            .iter()
+            .map(|j| verifier_params[*j].n)
+            .max()
+            .unwrap_or(0);
+        let tj = match hash_to_tj_fr(&commits, &set, &values, max_n) {
+            Err(_e) => return false,
+            Ok(p) => p,
+        };
+
+        // we want to check
+        //  \prod_j e(com[j], g2^{\sum_i alpha_j^{n_j + 1 - i} * t_i,j * t_j})
+        //      * e(proof^{-1}, g2)
+        //      ?= \prod_j gt_j^{t_j * \sum_i m_i,j * t_i,j}
+        // where alpha_j, n_j and gt_j come from the parameters of commitment j
+        let mut g1_proj: Vec<PointproofsG1> = Vec::with_capacity(active.len() + 1);
+        let mut g2_proj: Vec<PointproofsG2> = Vec::with_capacity(active.len() + 1);
+        let mut rhs = Fq12::one();
+        for (k, j) in active.iter().enumerate() {
+            let vp = verifier_params[*j];
+            let ti = match hash_to_ti_fr(&commits[k], &set[k], &values[k], vp.n) {
+                Err(_e) => return false,
+                Ok(p) => p,
+            };
+
+            let mut exponent = Fr::zero();
+            let mut scalars_repr: Vec<FrRepr> = Vec::with_capacity(ti.len());
+            for (i, e) in ti.iter().enumerate() {
+                let mut t = *e;
+                t.mul_assign(&tj[k]);
+                let mut m = hash_to_field_ciphersuite(self.ciphersuite, values[k][i]);
+                m.mul_assign(&t);
+                exponent.add_assign(&m);
+                scalars_repr.push(t.into_repr());
+            }
+            let scalars_u64: Vec<&[u64; 4]> = scalars_repr.iter().map(|s| &s.0).collect();
+            let bases: Vec<PointproofsG2Affine> = set[k]
+                .iter()
+                .map(|index| vp.generators[vp.n - index - 1])
+                .collect();
+
+            g1_proj.push(commits[k].commit);
+            g2_proj.push(multiexp_g2(&bases, &scalars_u64));
+            rhs.mul_assign(&vp.gt_elt.pow(exponent.into_repr()));
+        }
+        let mut proof = self.proof;
+        proof.negate();
+        g1_proj.push(proof);
+        g2_proj.push(PointproofsG2::one());
+
+        PointproofsG1::batch_normalization(&mut g1_proj);
+        let g1_vec: Vec<PointproofsG1Affine> = g1_proj.iter().map(|s| s.into_affine()).collect();
+        PointproofsG2::batch_normalization(&mut g2_proj);
+        let g2_vec: Vec<PointproofsG2Affine> = g2_proj.iter().map(|s| s.into_affine()).collect();
+        pointproofs_pairing_multi_product(&g1_vec[..], &g2_vec[..]) == rhs
+    }
+
+    /// Verify, one by one, the proofs that went into an aggregated proof.
+    ///     * input: verifier parameter set
+    ///     * input: the list of commitments
+    ///     * input: a 2-dim array of the individual proofs
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of values
+    ///     * output: for each (commitment, index) pair, in order,
+    ///     the position of the commitment, the index, and
+    ///     if the individual proof is valid
+    /// This is meant for diagnosing an aggregated proof that fails
+    /// `cross_commit_batch_verify` (or `same_commit_batch_verify` with a single
+    /// commitment), and costs two pairings per (commitment, index) pair;
+    /// it shall not be used in place of batch verification.
+    /// A pair with a missing proof or value is reported as invalid.
+    pub fn decompose_aggregate<Blob: AsRef<[u8]>>(
+        verifier_params: &VerifierParams,
+        com: &[Commitment],
+        proofs: &[Vec<Self>],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+    ) -> Vec<(usize, usize, bool)> {
+        let mut res = vec![];
+        for (j, indices) in set.iter().enumerate() {
+            for (k, index) in indices.iter().enumerate() {
+                let proof = proofs.get(j).and_then(|e| e.get(k));
+                let value = value_sub_vector.get(j).and_then(|e| e.get(k));
+                let valid = match (com.get(j), proof, value) {
+                    (Some(c), Some(p), Some(v)) => p.verify(verifier_params, c, v, *index),
+                    _ => false,
+                };
+                res.push((j, *index, valid));
+            }
+        }
+        res
+    }
+
+    /// the ciphersuite of the proof
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
 }
@@ -0,0 +1,100 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for commit-reveal: commit to the value hashes first,
+//! reveal the values later.
+
+use pairings::misc::ct_eq;
+use pairings::*;
+use sha2::{Digest, Sha256};
+
+impl HashCommitment {
+    /// the hash of a value, as committed by a `HashCommitment`:
+    /// SHA-256 of the value.
+    pub fn hash_value<Blob: AsRef<[u8]>>(value: Blob) -> [u8; VALUE_HASH_LEN] {
+        let mut hasher = Sha256::new();
+        hasher.input(value.as_ref());
+        let mut res = [0u8; VALUE_HASH_LEN];
+        res.copy_from_slice(&hasher.result());
+        res
+    }
+
+    /// generate a new commitment to the hashes of the values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * output: a hash commitment
+    ///     * error: invalid ciphersuite/parameters
+    pub fn new<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+    ) -> Result<Self, PointproofsError> {
+        let hashes: Vec<[u8; VALUE_HASH_LEN]> = values.iter().map(Self::hash_value).collect();
+        Self::new_from_hashes(prover_params, &hashes)
+    }
+
+    /// generate a new commitment from the value hashes,
+    /// when the values are not known yet.
+    ///     * input: prover parameter set
+    ///     * input: a list of n value hashes
+    ///     * output: a hash commitment
+    ///     * error: invalid ciphersuite/parameters
+    pub fn new_from_hashes(
+        prover_params: &ProverParams,
+        hashes: &[[u8; VALUE_HASH_LEN]],
+    ) -> Result<Self, PointproofsError> {
+        let commitment = Commitment::new(prover_params, hashes)?;
+        Ok(Self { commitment })
+    }
+
+    /// the underlying commitment, to the value hashes.
+    pub fn commitment(&self) -> &Commitment {
+        &self.commitment
+    }
+}
+
+impl Proof {
+    /// Generate a proof that reveals the value at an index
+    /// of a hash commitment.
+    ///     * input: prover parameter set
+    ///     * input: the n value hashes of the commitment
+    ///     * input: the index of the proof
+    ///     * input: the revealed value
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters, or the value
+    ///       does not match hashes\[index\]
+    /// Only the hashes are needed, so the other values remain hidden.
+    pub fn reveal<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        hashes: &[[u8; VALUE_HASH_LEN]],
+        index: usize,
+        value: Blob,
+    ) -> Result<Self, PointproofsError> {
+        if index >= hashes.len() {
+            return Err(PointproofsError::IndexOutOfRange);
+        }
+        // the comparison does not leak how much of the hash matches
+        if !ct_eq(&HashCommitment::hash_value(value), &hashes[index]) {
+            return Err(PointproofsError::HashMismatch);
+        }
+        Self::new(prover_params, hashes, index)
+    }
+
+    /// Verify a revealed value against a hash commitment.
+    ///     * input: verifier parameter set
+    ///     * input: the hash commitment
+    ///     * input: the index
+    ///     * input: the revealed value
+    ///     * output: if the hash of the value is committed at the index
+    pub fn verify_reveal<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &HashCommitment,
+        index: usize,
+        value: Blob,
+    ) -> bool {
+        self.verify(
+            verifier_params,
+            &com.commitment,
+            HashCommitment::hash_value(value),
+            index,
+        )
+    }
+}
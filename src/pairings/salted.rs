@@ -0,0 +1,119 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for commitments with a salted value hash.
+
+use pairings::*;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// prefixes the value with the salt, so that the value is hashed as
+/// hash_to_field(salt | value); the salt has a fixed length,
+/// hence the encoding is unambiguous
+fn salt_value<Blob: AsRef<[u8]>>(salt: &[u8; SALT_LEN], value: Blob) -> Vec<u8> {
+    [&salt[..], value.as_ref()].concat()
+}
+
+fn salt_values<Blob: AsRef<[u8]>>(salt: &[u8; SALT_LEN], values: &[Blob]) -> Vec<Vec<u8>> {
+    values.iter().map(|v| salt_value(salt, v)).collect()
+}
+
+impl Commitment {
+    /// generate a new commitment with a fresh random salt.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * output: a salted commitment
+    ///     * error: invalid ciphersuite/parameters, OsRng is not available
+    /// Every value is hashed together with the salt, so that values
+    /// from a small space cannot be recovered with a dictionary.
+    /// The salt is part of the serialized commitment.
+    pub fn new_salted<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+    ) -> Result<SaltedCommitment, PointproofsError> {
+        let mut rng = match OsRng::new() {
+            Ok(rng) => rng,
+            Err(_) => return Err(PointproofsError::RngUnavailable),
+        };
+        Self::new_salted_with_rng(prover_params, values, &mut rng)
+    }
+
+    /// Same as `Commitment::new_salted`, but the salt is drawn from the caller's rng.
+    pub fn new_salted_with_rng<Blob: AsRef<[u8]>, R: RngCore>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        rng: &mut R,
+    ) -> Result<SaltedCommitment, PointproofsError> {
+        let mut salt = [0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+        SaltedCommitment::new_with_salt(prover_params, values, salt)
+    }
+}
+
+impl SaltedCommitment {
+    /// generate a new commitment with the given salt.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the salt
+    ///     * output: a salted commitment
+    ///     * error: invalid ciphersuite/parameters
+    /// The salt should be random and used for a single commitment;
+    /// see `Commitment::new_salted`.
+    pub fn new_with_salt<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        salt: [u8; SALT_LEN],
+    ) -> Result<Self, PointproofsError> {
+        let commitment = Commitment::new(prover_params, &salt_values(&salt, values))?;
+        Ok(Self { commitment, salt })
+    }
+
+    /// the underlying commitment, to the salted values.
+    pub fn commitment(&self) -> &Commitment {
+        &self.commitment
+    }
+
+    /// the salt
+    pub fn salt(&self) -> &[u8; SALT_LEN] {
+        &self.salt
+    }
+}
+
+impl Proof {
+    /// Generate a new proof for a salted commitment.
+    ///     * input: prover parameter set
+    ///     * input: the salted commitment
+    ///     * input: values for the proof
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters
+    /// The values are salted with the salt of the commitment.
+    pub fn new_salted<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        com: &SaltedCommitment,
+        values: &[Blob],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        Self::new(prover_params, &salt_values(&com.salt, values), index)
+    }
+
+    /// Verify the proof against the value and a salted commitment;
+    /// the salt is read from the commitment.
+    ///     * input: verifier parameter set
+    ///     * input: the salted commitment
+    ///     * input: the value
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. commitment/value/index
+    pub fn verify_salted<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &SaltedCommitment,
+        value: Blob,
+        index: usize,
+    ) -> bool {
+        self.verify(
+            verifier_params,
+            &com.commitment,
+            salt_value(&com.salt, value),
+            index,
+        )
+    }
+}
@@ -1,95 +1,690 @@
 //! this file is part of the pointproofs.
 //! It defines APIs for (de)serialization.
 
+use ff::{PrimeField, PrimeFieldRepr};
 use pairing_plus::bls12_381::*;
 use pairing_plus::serdes::SerDes;
-use pairing_plus::CurveProjective;
+use pairing_plus::{CurveAffine, CurveProjective, EncodedPoint};
 use pairings::err::*;
 use pairings::param::*;
 use pairings::pointproofs_groups::*;
 use pairings::*;
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
 
 type Compressed = bool;
-impl SerDes for Commitment {
-    /// Convert a pop into a blob:
+
+/// reads n from `bytes[offset..offset + 4]`, and checks it is in [1, MAX_N]
+fn read_n_from_slice(bytes: &[u8], offset: usize) -> std::io::Result<usize> {
+    if bytes.len() < offset + 4 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            ERR_TRUNCATED,
+        ));
+    }
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(&bytes[offset..offset + 4]);
+    let n = u32::from_le_bytes(buf) as usize;
+    if n > MAX_N || n == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_MAX_N,
+        ));
+    }
+    Ok(n)
+}
+
+/// deserializes a whole slice, after checking it holds at least `min_len` bytes
+pub(crate) fn deserialize_whole_slice<T: SerDes>(
+    bytes: &[u8],
+    min_len: usize,
+) -> std::io::Result<T> {
+    if bytes.len() < min_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            ERR_TRUNCATED,
+        ));
+    }
+    let mut reader = bytes;
+    let res = T::deserialize(&mut reader, true)?;
+    if !reader.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_TRAILING,
+        ));
+    }
+    Ok(res)
+}
+
+impl ProverParams {
+    /// Convert a byte slice into a ProverParam, with the format of `deserialize`.
+    /// n is read first and the length of the slice is checked against it,
+    /// so a short blob claiming a large n fails before any point is read.
+    /// Returns an error if the slice is too short or too long.
+    pub fn deserialize_from_slice(bytes: &[u8]) -> std::io::Result<Self> {
+        // `|ciphersuite id | n | generators | pp_len | [pre_compute]`
+        let n = read_n_from_slice(bytes, 1)?;
+        deserialize_whole_slice(bytes, 1 + 4 + 2 * n * POINTPROOFSG1_LEN + 4)
+    }
+
+    /// Load a ProverParam from a file, with the format of `deserialize`,
+    /// through a read-only memory map of the file.
+    /// The file is parsed in place, so the serialized points are never copied
+    /// into an owned buffer: the only heap allocations are the parsed points,
+    /// and the mapped pages are backed by the file, so the OS can drop them.
+    /// Every point goes through the same checks as in `deserialize`,
+    /// including the subgroup check. The map is dropped before returning;
+    /// the parameters do not reference the file afterwards.
+    /// Returns an error if the file cannot be mapped, or if its content
+    /// is not exactly one ProverParam.
     ///
-    /// `|ciphersuite id| commit |` => bytes
+    /// Safety caveat: the file must not be modified or truncated while
+    /// this function runs. A concurrent truncation may raise SIGBUS,
+    /// and a concurrent write may be observed half-way; the checks then
+    /// reject the points that are not valid, but not a valid point
+    /// that replaced another one.
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // the map is read-only and private to this function,
+        // see the safety caveat above
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::deserialize_from_slice(&map[..])
+    }
+}
+
+impl VerifierParams {
+    /// Convert a byte slice into a VerifierParams, with the format of `deserialize`.
+    /// n is read first and the length of the slice is checked against it,
+    /// so a short blob claiming a large n fails before any point is read.
+    /// Returns an error if the slice is too short or too long.
+    pub fn deserialize_from_slice(bytes: &[u8]) -> std::io::Result<Self> {
+        // `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element`
+        let n = read_n_from_slice(bytes, 1)?;
+        deserialize_whole_slice(bytes, 1 + 4 + n * POINTPROOFSG2_LEN + 4)
+    }
+}
+/// serializes into a new vector, with compressed = true
+fn serialize_to_vec<T: SerDes>(t: &T) -> Vec<u8> {
+    let mut buf: Vec<u8> = vec![];
+    // the constructors and deserializers only output valid ciphersuites and n,
+    // so serializing into a vector does not fail
+    t.serialize(&mut buf, true).unwrap();
+    buf
+}
+
+impl Commitment {
+    /// Convert a byte slice into a commitment, with the format of `deserialize`
+    /// and compressed = true. The point is checked to be in the group.
+    /// Returns an error if the slice is not exactly one commitment.
+    pub fn from_bytes(bytes: &[u8]) -> std::io::Result<Self> {
+        deserialize_whole_slice(bytes, 0)
+    }
+
+    /// Convert a commitment into bytes, with the format of `serialize`
+    /// and compressed = true.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_to_vec(self)
+    }
+
+    /// The number of bytes `serialize` writes for a commitment:
+    /// the ciphersuite id and the point, in the requested encoding.
+    /// This is `COMMIT_LEN` with compressed = true.
+    pub fn serialized_len(compressed: bool) -> usize {
+        1 + point_len(compressed)
+    }
+
+    /// Same as `Commitment::serialize`, but also writes the n of the parameters
+    /// of the commitment, and sets `WITH_N_FLAG` in the ciphersuite id:
     ///
-    /// Returns an error if ciphersuite id is invalid or serialization fails.
-    fn serialize<W: std::io::Write>(
+    /// `|ciphersuite id | n | commit |` => bytes
+    ///
+    /// so that `Commitment::deserialize` recovers n, and updating or verifying
+    /// the result with parameters for another n fails.
+    /// These bytes are 4 bytes longer and not in the v0 format;
+    /// `Commitment::serialize` is unchanged.
+    ///
+    /// Returns an error if n is unknown, e.g., for a commitment deserialized
+    /// from a blob without n, or if serialization fails.
+    pub fn serialize_with_n<W: std::io::Write>(
         &self,
         writer: &mut W,
         compressed: Compressed,
     ) -> std::io::Result<()> {
-        // check the cipher suite id
-        if !check_ciphersuite(self.ciphersuite) {
+        if self.n == 0 {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                ERR_UNKNOWN_N,
             ));
         }
+        write_point(writer, self.ciphersuite, self.n, &self.commit, compressed)
+    }
 
-        // compressed must be true
-        if !compressed {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
+    /// Convert a commitment into bytes, with the format of `serialize_with_n`
+    /// and compressed = true.
+    /// Returns an error if n is unknown.
+    pub fn to_bytes_with_n(&self) -> std::io::Result<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![];
+        self.serialize_with_n(&mut buf, true)?;
+        Ok(buf)
+    }
 
-        let mut buf: Vec<u8> = vec![self.ciphersuite];
-        self.commit.into_affine().serialize(&mut buf, compressed)?;
+    /// Same as `Commitment::deserialize`, but the blob must be in the
+    /// encoding given by compressed, i.e., `UNCOMPRESSED_POINT_FLAG` is set
+    /// if and only if compressed = false. Each commitment then has exactly
+    /// one valid blob, as needed to compare or deduplicate them as bytes;
+    /// a blob from `Commitment::serialize_with_n` is another one with its n.
+    ///
+    /// Returns an error if deserialization fails, if the blob is in the
+    /// other encoding, or on the errors of `Commitment::deserialize`.
+    pub fn deserialize_strict<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        let (ciphersuite, n, commit) = read_point(reader, false, true, Some(compressed))?;
+        Ok(Commitment {
+            ciphersuite,
+            n,
+            commit,
+        })
+    }
+}
 
-        // format the output
-        writer.write_all(&buf)?;
-        Ok(())
+impl Proof {
+    /// Convert a byte slice into a proof, with the format of `deserialize`
+    /// and compressed = true. The point is checked to be in the group.
+    /// Returns an error if the slice is not exactly one proof.
+    pub fn from_bytes(bytes: &[u8]) -> std::io::Result<Self> {
+        deserialize_whole_slice(bytes, 0)
     }
 
-    /// Convert a blob into a PoP:
+    /// Convert a proof into bytes, with the format of `serialize`
+    /// and compressed = true.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_to_vec(self)
+    }
+
+    /// The number of bytes `serialize` writes for a proof:
+    /// the ciphersuite id and the point, in the requested encoding.
+    /// This is `PROOF_LEN` with compressed = true.
+    pub fn serialized_len(compressed: bool) -> usize {
+        1 + point_len(compressed)
+    }
+
+    /// Same as `Proof::serialize`, but also writes the n of the parameters
+    /// of the proof, and sets `WITH_N_FLAG` in the ciphersuite id:
     ///
-    /// bytes => `|ciphersuite id | commit |`
+    /// `|ciphersuite id | n | proof |` => bytes
     ///
-    /// Returns an error if deserialization fails, or if
-    /// the commit is not compressed.
-    fn deserialize<R: std::io::Read>(
-        reader: &mut R,
+    /// so that `Proof::deserialize` recovers n, and updating or verifying
+    /// the result with parameters for another n fails.
+    /// These bytes are 4 bytes longer and not in the v0 format;
+    /// `Proof::serialize` is unchanged.
+    ///
+    /// Returns an error if n is unknown, e.g., for a proof deserialized
+    /// from a blob without n, or if serialization fails.
+    pub fn serialize_with_n<W: std::io::Write>(
+        &self,
+        writer: &mut W,
         compressed: Compressed,
-    ) -> std::io::Result<Self> {
-        // compressed must be true
-        if !compressed {
+    ) -> std::io::Result<()> {
+        if self.n == 0 {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
+                ERR_UNKNOWN_N,
             ));
         }
-        // constants stores id and the number of ssk-s
-        let mut constants: [u8; 1] = [0u8; 1];
+        write_point(writer, self.ciphersuite, self.n, &self.proof, compressed)
+    }
 
-        reader.read_exact(&mut constants)?;
+    /// Convert a proof into bytes, with the format of `serialize_with_n`
+    /// and compressed = true.
+    /// Returns an error if n is unknown.
+    pub fn to_bytes_with_n(&self) -> std::io::Result<Vec<u8>> {
+        let mut buf: Vec<u8> = vec![];
+        self.serialize_with_n(&mut buf, true)?;
+        Ok(buf)
+    }
 
-        // check the ciphersuite id in the blob
-        if !check_ciphersuite(constants[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
+    /// Same as `Proof::deserialize`, but the blob must be in the encoding
+    /// given by compressed, see `Commitment::deserialize_strict`.
+    ///
+    /// Returns an error if deserialization fails, if the blob is in the
+    /// other encoding, or on the errors of `Proof::deserialize`.
+    pub fn deserialize_strict<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        let (ciphersuite, n, proof) = read_point(reader, true, true, Some(compressed))?;
+        Ok(Proof {
+            ciphersuite,
+            n,
+            proof,
+        })
+    }
+
+    /// Same as `Proof::deserialize`, but a compressed proof is not checked
+    /// to be in the prime order subgroup.
+    ///
+    /// # Security
+    /// This is only a performance escape hatch for proofs from a trusted
+    /// source that already checked them. It is unsound on untrusted input:
+    /// a point outside the subgroup can make `Proof::verify` accept
+    /// a forged opening. The point is still on the curve and canonical;
+    /// uncompressed proofs are read with the full checks.
+    ///
+    /// Returns an error if deserialization fails.
+    pub fn deserialize_unchecked<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let (ciphersuite, n, proof) = read_point(reader, true, false, None)?;
+        Ok(Proof {
+            ciphersuite,
+            n,
+            proof,
+        })
+    }
+}
+
+impl VerifierParams {
+    /// Same as `VerifierParams::deserialize_from_slice`.
+    pub fn from_bytes(bytes: &[u8]) -> std::io::Result<Self> {
+        Self::deserialize_from_slice(bytes)
+    }
+
+    /// Convert a VerifierParams into bytes, with the format of `serialize`
+    /// and compressed = true.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_to_vec(self)
+    }
+}
+
+/// the fingerprint of the parameters:
+/// the SHA-256 of their serialization with compressed = true.
+/// The serialization is canonical, so two parameters serialize to
+/// the same bytes if and only if their fingerprints match;
+/// it includes the pre-computed table, so `precomp_3` and
+/// `precomp_256` change the fingerprint.
+/// The Debug output prints a shorter digest of the points only,
+/// which is not a fingerprint.
+fn fingerprint_of<T: SerDes>(t: &T) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.input(&serialize_to_vec(t));
+    let mut fingerprint = [0u8; 32];
+    fingerprint.copy_from_slice(&hasher.result());
+    fingerprint
+}
+
+impl ProverParams {
+    /// The SHA-256 of the compressed serialization, pre-computed table included.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint_of(self)
+    }
+}
+
+impl VerifierParams {
+    /// The SHA-256 of the compressed serialization, pre-computed table included.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint_of(self)
+    }
+}
+
+impl Hash for Commitment {
+    /// Hashes `| ciphersuite id | commit |`, as `serialize` writes them,
+    /// so that equal commitments have equal hashes.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        // serializing a valid point into a vector does not fail
+        self.commit.into_affine().serialize(&mut buf, true).unwrap();
+        buf.hash(state);
+    }
+}
+
+impl Hash for Proof {
+    /// Hashes `| ciphersuite id | proof |`, as `serialize` writes them,
+    /// so that equal proofs have equal hashes.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        // serializing a valid point into a vector does not fail
+        self.proof.into_affine().serialize(&mut buf, true).unwrap();
+        buf.hash(state);
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Commitment {
+    type Error = std::io::Error;
+
+    fn try_from(bytes: &'a [u8]) -> std::io::Result<Self> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Proof {
+    type Error = std::io::Error;
+
+    fn try_from(bytes: &'a [u8]) -> std::io::Result<Self> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for VerifierParams {
+    type Error = std::io::Error;
+
+    fn try_from(bytes: &'a [u8]) -> std::io::Result<Self> {
+        Self::from_bytes(bytes)
+    }
+}
+
+/// writes `|ciphersuite id | point |`, with `UNCOMPRESSED_POINT_FLAG`
+/// set in the id if the point is not compressed;
+/// if n is not 0, writes `|ciphersuite id | n | point |`
+/// with `WITH_N_FLAG` set in the id
+fn write_point<W: std::io::Write>(
+    writer: &mut W,
+    ciphersuite: Ciphersuite,
+    n: usize,
+    point: &PointproofsG1,
+    compressed: Compressed,
+) -> std::io::Result<()> {
+    // check the cipher suite id
+    if !check_ciphersuite(ciphersuite) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_CIPHERSUITE,
+        ));
+    }
+    if n > MAX_N {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_MAX_N,
+        ));
+    }
+
+    let mut constants = ciphersuite;
+    if !compressed {
+        constants |= UNCOMPRESSED_POINT_FLAG;
+    }
+    if n != 0 {
+        constants |= WITH_N_FLAG;
+    }
+    let mut buf: Vec<u8> = vec![constants];
+    if n != 0 {
+        buf.extend_from_slice(&(n as u32).to_le_bytes());
+    }
+    point.into_affine().serialize(&mut buf, compressed)?;
+
+    // format the output
+    writer.write_all(&buf)?;
+    Ok(())
+}
+
+/// reads what `write_point` writes, in either encoding unless encoding
+/// requires one of them; the identity is rejected unless allow_identity
+/// is set, and the subgroup check is skipped if subgroup_check is not.
+/// Returns the ciphersuite, n (0 if it is not in the blob) and the point.
+fn read_point<R: std::io::Read>(
+    reader: &mut R,
+    allow_identity: bool,
+    subgroup_check: bool,
+    encoding: Option<Compressed>,
+) -> std::io::Result<(Ciphersuite, usize, PointproofsG1)> {
+    let mut constants: [u8; 1] = [0u8; 1];
+    reader.read_exact(&mut constants)?;
+    let ciphersuite = constants[0] & !UNCOMPRESSED_POINT_FLAG & !WITH_N_FLAG;
+    if !check_ciphersuite(ciphersuite) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_CIPHERSUITE,
+        ));
+    }
+    let compressed = constants[0] & UNCOMPRESSED_POINT_FLAG == 0;
+    if encoding.is_some() && encoding != Some(compressed) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_POINT_ENCODING,
+        ));
+    }
+    let n = if constants[0] & WITH_N_FLAG != 0 {
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf)?;
+        read_n_from_slice(&buf, 0)?
+    } else {
+        0
+    };
+    let point = if subgroup_check || !compressed {
+        read_canonical_point(reader, compressed)?
+    } else {
+        read_canonical_point_unchecked(reader)?
+    };
+    if point.is_zero() && !allow_identity {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_IDENTITY_POINT,
+        ));
+    }
+    Ok((ciphersuite, n, point))
+}
+
+/// the length of a serialized PointproofsG1 point
+fn point_len(compressed: Compressed) -> usize {
+    if compressed {
+        POINTPROOFSG1_LEN
+    } else {
+        2 * POINTPROOFSG1_LEN
+    }
+}
+
+/// reads a point, and checks that it is in the group and that its encoding
+/// is canonical, i.e., it is the one `serialize` writes for the point:
+/// coordinates above the modulus, or stray bits with the infinity flag,
+/// are rejected, so that equal points are always read from equal bytes
+pub(crate) fn read_canonical_point<R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+) -> std::io::Result<PointproofsG1> {
+    let len = point_len(compressed);
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+
+    // the point is checked to be in the group by the deserialization
+    let point = match PointproofsG1::deserialize(&mut bytes[..].as_ref(), compressed) {
+        Ok(p) => p,
+        Err(e) => return Err(point_error::<PointproofsG1Affine>(&bytes, compressed, e)),
+    };
+    let mut canonical: Vec<u8> = Vec::with_capacity(len);
+    point.into_affine().serialize(&mut canonical, compressed)?;
+    if canonical != bytes {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_NON_CANONICAL_POINT,
+        ));
+    }
+    Ok(point)
+}
+
+/// reads a point of either group, checked to be on the curve and in the group;
+/// a point that is on the curve but not in the group is reported with
+/// a `PointproofsError::SubgroupCheckFailed` payload, which the
+/// conversion into `PointproofsError` recovers
+pub(crate) fn read_group_point<G: CurveAffine + SerDes, R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+) -> std::io::Result<G> {
+    let len = if compressed {
+        G::Compressed::size()
+    } else {
+        G::Uncompressed::size()
+    };
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    match G::deserialize(&mut bytes[..].as_ref(), compressed) {
+        Ok(p) => Ok(p),
+        Err(e) => Err(point_error::<G>(&bytes, compressed, e)),
+    }
+}
+
+/// the error for bytes that failed to deserialize into a point:
+/// `PointproofsError::SubgroupCheckFailed` if they encode a point on
+/// the curve that is not in the group, the original error otherwise
+fn point_error<G: CurveAffine>(
+    bytes: &[u8],
+    compressed: Compressed,
+    e: std::io::Error,
+) -> std::io::Error {
+    let point: G = if compressed {
+        // decompressing the point puts it on the curve
+        let mut encoded = G::Compressed::empty();
+        encoded.as_mut().copy_from_slice(bytes);
+        match encoded.into_affine_unchecked() {
+            Ok(p) => p,
+            Err(_) => return e,
+        }
+    } else {
+        let mut encoded = G::Uncompressed::empty();
+        encoded.as_mut().copy_from_slice(bytes);
+        let p = match encoded.into_affine_unchecked() {
+            Ok(p) => p,
+            Err(_) => return e,
+        };
+        // the point is on the curve iff decompressing its x gives it back
+        match p.into_compressed().into_affine_unchecked() {
+            Ok(q) if q == p => p,
+            _ => return e,
         }
+    };
+    if point.mul(<G::Scalar as PrimeField>::char()).is_zero() {
+        e
+    } else {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            PointproofsError::SubgroupCheckFailed,
+        )
+    }
+}
 
-        // read into commit
-        let commit = PointproofsG1::deserialize(reader, compressed)?;
+/// `read_canonical_point` for the compressed encoding, without the
+/// subgroup check: decompressing the point still puts it on the curve,
+/// but it may have a small order component
+fn read_canonical_point_unchecked<R: std::io::Read>(
+    reader: &mut R,
+) -> std::io::Result<PointproofsG1> {
+    let mut encoded = <PointproofsG1Affine as CurveAffine>::Compressed::empty();
+    reader.read_exact(encoded.as_mut())?;
+    let point = match encoded.into_affine_unchecked() {
+        Ok(p) => p,
+        Err(e) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+    };
+    let mut canonical: Vec<u8> = Vec::with_capacity(POINTPROOFSG1_LEN);
+    point.serialize(&mut canonical, true)?;
+    if canonical[..] != encoded.as_ref()[..] {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            ERR_NON_CANONICAL_POINT,
+        ));
+    }
+    Ok(point.into_projective())
+}
 
-        // finished
+impl SerDes for Commitment {
+    /// Convert a commitment into a blob:
+    ///
+    /// `|ciphersuite id| commit |` => bytes
+    ///
+    /// With compressed = false the commit is written uncompressed,
+    /// and `UNCOMPRESSED_POINT_FLAG` is set in the ciphersuite id.
+    ///
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+    ) -> std::io::Result<()> {
+        write_point(writer, self.ciphersuite, 0, &self.commit, compressed)
+    }
+
+    /// Convert a blob into a commitment:
+    ///
+    /// bytes => `|ciphersuite id | commit |`
+    ///
+    /// The encoding of the commit is read from the ciphersuite id,
+    /// so either value of compressed reads both encodings;
+    /// a blob without `UNCOMPRESSED_POINT_FLAG` is in the v0 format.
+    /// A blob with `WITH_N_FLAG`, from `serialize_with_n`, also gives
+    /// the n of the parameters; n is unknown otherwise.
+    /// The encoding must be canonical, so two blobs of the same commit
+    /// in the same encoding are byte-for-byte equal; the commit still has
+    /// one blob per encoding, see `Commitment::deserialize_strict` to pin one.
+    ///
+    /// Returns an error if deserialization fails, if the commit is not
+    /// in the group or is the identity, if its encoding is not canonical,
+    /// or if the n in the blob is 0 or larger than `MAX_N`.
+    /// Any other point, including the generator, is accepted.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        _compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        // no commitment is the identity, except with negligible probability
+        let (ciphersuite, n, commit) = read_point(reader, false, true, None)?;
         Ok(Commitment {
-            ciphersuite: constants[0],
+            ciphersuite,
+            n,
             commit,
         })
     }
 }
 
 impl SerDes for Proof {
-    /// Convert a pop into a blob:
+    /// Convert a proof into a blob:
     ///
-    /// `|ciphersuite id| commit |` => bytes
+    /// `|ciphersuite id| proof |` => bytes
+    ///
+    /// With compressed = false the proof is written uncompressed,
+    /// and `UNCOMPRESSED_POINT_FLAG` is set in the ciphersuite id.
+    ///
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+    ) -> std::io::Result<()> {
+        write_point(writer, self.ciphersuite, 0, &self.proof, compressed)
+    }
+
+    /// Convert a blob into a proof:
+    ///
+    /// bytes => `|ciphersuite id | proof |`
+    ///
+    /// The encoding of the proof is read from the ciphersuite id,
+    /// so either value of compressed reads both encodings;
+    /// a blob without `UNCOMPRESSED_POINT_FLAG` is in the v0 format.
+    /// A blob with `WITH_N_FLAG`, from `serialize_with_n`, also gives
+    /// the n of the parameters; n is unknown otherwise.
+    /// The encoding must be canonical, so two blobs of the same proof
+    /// in the same encoding are byte-for-byte equal; the proof still has
+    /// one blob per encoding, see `Proof::deserialize_strict` to pin one.
+    ///
+    /// Returns an error if deserialization fails, if the proof is not
+    /// in the group, if its encoding is not canonical, or if the n
+    /// in the blob is 0 or larger than `MAX_N`.
+    /// Unlike a commitment, the proof may be the identity: it is the
+    /// honest proof when n = 1, and the verification stays sound.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        _compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        // with n = 1 the proof is the identity, see `Proof::new`
+        let (ciphersuite, n, proof) = read_point(reader, true, true, None)?;
+        Ok(Proof {
+            ciphersuite,
+            n,
+            proof,
+        })
+    }
+}
+
+impl SerDes for SaltedCommitment {
+    /// Convert a salted commitment into a blob:
+    ///
+    /// `|ciphersuite id| commit | salt |` => bytes
     ///
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn serialize<W: std::io::Write>(
@@ -97,74 +692,243 @@ impl SerDes for Proof {
         writer: &mut W,
         compressed: Compressed,
     ) -> std::io::Result<()> {
-        // compressed must be true
+        self.commitment.serialize(writer, compressed)?;
+        writer.write_all(&self.salt)?;
+        Ok(())
+    }
+
+    /// Convert a blob into a salted commitment:
+    ///
+    /// bytes => `|ciphersuite id | commit | salt |`
+    ///
+    /// Returns an error if deserialization fails.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        let commitment = Commitment::deserialize(reader, compressed)?;
+        let mut salt = [0u8; SALT_LEN];
+        reader.read_exact(&mut salt)?;
+        Ok(SaltedCommitment { commitment, salt })
+    }
+}
+
+impl CommitmentDelta {
+    /// Writes `|ciphersuite id | k | (index | scalar) * k |`,
+    /// or `|ciphersuite id | (index | scalar) * k |` if k is implied.
+    fn serialize_changes<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+        with_len: bool,
+    ) -> std::io::Result<()> {
+        if !check_ciphersuite(self.ciphersuite) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_CIPHERSUITE,
+            ));
+        }
         if !compressed {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 ERR_COMPRESS,
             ));
         }
-        // check the cipher suite id
-        if !check_ciphersuite(self.ciphersuite) {
+        if self.changes.len() > MAX_N {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
+                ERR_MAX_N,
             ));
         }
-        let mut buf: Vec<u8> = vec![self.ciphersuite];
-        self.proof.into_affine().serialize(&mut buf, compressed)?;
 
-        // format the output
+        let mut buf: Vec<u8> = vec![self.ciphersuite];
+        if with_len {
+            buf.extend_from_slice(&(self.changes.len() as u32).to_le_bytes());
+        }
+        for (index, scalar) in self.changes.iter() {
+            if *index >= MAX_N {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    ERR_INVALID_INDEX,
+                ));
+            }
+            buf.extend_from_slice(&(*index as u32).to_le_bytes());
+            scalar.write_be(&mut buf)?;
+        }
         writer.write_all(&buf)?;
         Ok(())
     }
 
-    /// Convert a blob into a PoP:
-    ///
-    /// bytes => `|ciphersuite id | commit |`
-    ///
-    /// Returns an error if deserialization fails, or if
-    /// the commit is not compressed.
-    fn deserialize<R: std::io::Read>(
+    /// Reads what `serialize_changes` writes; k is read from the blob if not given.
+    fn deserialize_changes<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
+        k: Option<usize>,
     ) -> std::io::Result<Self> {
-        // compressed must be true
         if !compressed {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 ERR_COMPRESS,
             ));
         }
-        // constants stores ciphersuite id
-        let mut constants: [u8; 1] = [0u8; 1];
-
-        reader.read_exact(&mut constants)?;
-
-        // check the ciphersuite id in the blob
-        if !check_ciphersuite(constants[0]) {
+        let mut csid = [0u8; 1];
+        reader.read_exact(&mut csid)?;
+        if !check_ciphersuite(csid[0]) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 ERR_CIPHERSUITE,
             ));
         }
+        let k = match k {
+            Some(k) => k,
+            None => {
+                let mut k = [0u8; 4];
+                reader.read_exact(&mut k)?;
+                u32::from_le_bytes(k) as usize
+            }
+        };
+        if k > MAX_N {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_MAX_N,
+            ));
+        }
 
-        // read into proof
-        let proof = PointproofsG1::deserialize(reader, compressed)?;
+        let mut changes: Vec<(usize, FrRepr)> = vec![];
+        for _ in 0..k {
+            let mut index = [0u8; 4];
+            reader.read_exact(&mut index)?;
+            let index = u32::from_le_bytes(index) as usize;
+            if index >= MAX_N {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    ERR_INVALID_INDEX,
+                ));
+            }
+            let mut scalar = FrRepr::default();
+            scalar.read_be(&mut *reader)?;
+            if Fr::from_repr(scalar).is_err() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    ERR_NON_CANONICAL,
+                ));
+            }
+            changes.push((index, scalar));
+        }
+        Ok(CommitmentDelta {
+            ciphersuite: csid[0],
+            changes,
+        })
+    }
+}
 
-        // finished
-        Ok(Proof {
-            ciphersuite: constants[0],
-            proof,
+impl SerDes for CommitmentDelta {
+    /// Convert a commitment delta into a blob:
+    ///
+    /// `|ciphersuite id | k | (index | scalar) * k |` => bytes
+    ///
+    /// with k and the indices as u32 in little endian,
+    /// and the scalars as 32 bytes in big endian.
+    ///
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+    ) -> std::io::Result<()> {
+        self.serialize_changes(writer, compressed, true)
+    }
+
+    /// Convert a blob into a commitment delta:
+    ///
+    /// bytes => `|ciphersuite id | k | (index | scalar) * k |`
+    ///
+    /// Returns an error if deserialization fails, if k or an index
+    /// is too large, or if a scalar is not canonical.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        Self::deserialize_changes(reader, compressed, None)
+    }
+}
+
+impl SerDes for UpdateWitness {
+    /// Convert an update witness into a blob:
+    ///
+    /// `|ciphersuite id | index | scalar |` => bytes
+    ///
+    /// with the index as u32 in little endian,
+    /// and the scalar as 32 bytes in big endian.
+    ///
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+    ) -> std::io::Result<()> {
+        CommitmentDelta {
+            ciphersuite: self.ciphersuite,
+            changes: vec![(self.index, self.multiplier)],
+        }
+        .serialize_changes(writer, compressed, false)
+    }
+
+    /// Convert a blob into an update witness:
+    ///
+    /// bytes => `|ciphersuite id | index | scalar |`
+    ///
+    /// Returns an error if deserialization fails, if the index
+    /// is too large, or if the scalar is not canonical.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        let delta = CommitmentDelta::deserialize_changes(reader, compressed, Some(1))?;
+        Ok(UpdateWitness {
+            ciphersuite: delta.ciphersuite,
+            index: delta.changes[0].0,
+            multiplier: delta.changes[0].1,
         })
     }
 }
 
+impl SerDes for HashCommitment {
+    /// Convert a hash commitment into a blob:
+    ///
+    /// `|ciphersuite id| commit |` => bytes
+    ///
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    fn serialize<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        compressed: Compressed,
+    ) -> std::io::Result<()> {
+        self.commitment.serialize(writer, compressed)
+    }
+
+    /// Convert a blob into a hash commitment:
+    ///
+    /// bytes => `|ciphersuite id | commit |`
+    ///
+    /// Returns an error if deserialization fails.
+    fn deserialize<R: std::io::Read>(
+        reader: &mut R,
+        compressed: Compressed,
+    ) -> std::io::Result<Self> {
+        let commitment = Commitment::deserialize(reader, compressed)?;
+        Ok(HashCommitment { commitment })
+    }
+}
+
 impl SerDes for ProverParams {
     /// Convert a ProverParam into a blob:
     ///
     /// `|ciphersuite id | n | generators | pp_len | [pre_compute]` => bytes
     ///
+    /// The pre-computed table is kept, so it does not need to be rebuilt
+    /// after a reload; its window is pp_len / 2n.
+    ///
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn serialize<W: std::io::Write>(
         &self,
@@ -193,7 +957,13 @@ impl SerDes for ProverParams {
             ));
         }
 
-        if self.n > (u32::max_value() as usize) || self.pp_len > (u32::max_value() as usize) {
+        if self.n > MAX_N {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_MAX_N,
+            ));
+        }
+        if self.pp_len > (u32::max_value() as usize) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 ERR_INVALID_VALUE,
@@ -222,7 +992,8 @@ impl SerDes for ProverParams {
     ///
     /// bytes => `|ciphersuite id | n | generators | pp_len | [pre_compute]`
     ///
-    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    /// Returns an error if ciphersuite id is invalid or serialization fails;
+    /// or if pp_len is not a valid table length for n.
     fn deserialize<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
@@ -249,7 +1020,7 @@ impl SerDes for ProverParams {
         let mut buf = [0u8; 4];
         reader.read_exact(&mut buf)?;
         let n = u32::from_le_bytes(buf) as usize;
-        if n > 65536 || n == 0 {
+        if n > MAX_N || n == 0 {
             // set an upper bounded of n
             // to prevent potential DoS kind of attacks
             return Err(std::io::Error::new(
@@ -263,7 +1034,7 @@ impl SerDes for ProverParams {
 
         // write the generators
         for _i in 0..n * 2 {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
+            let g: PointproofsG1Affine = read_group_point(reader, true)?;
             generators.push(g);
         }
 
@@ -271,9 +1042,24 @@ impl SerDes for ProverParams {
         reader.read_exact(&mut buf)?;
         let pp_len = u32::from_le_bytes(buf) as usize;
 
+        // the table holds `window` points for each of the 2n generators
+        if pp_len % (2 * n) != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_PRECOMP_LEN,
+            ));
+        }
+        let window = pp_len / (2 * n);
+        if window == 1 || window > MAX_PRECOMP_WINDOW {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_PRECOMP_LEN,
+            ));
+        }
+
         let mut precomp: Vec<PointproofsG1Affine> = vec![];
         for _i in 0..pp_len {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
+            let g: PointproofsG1Affine = read_group_point(reader, true)?;
             precomp.push(g);
         }
 
@@ -284,6 +1070,7 @@ impl SerDes for ProverParams {
             generators,
             pp_len,
             precomp,
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
         })
     }
 }
@@ -293,6 +1080,8 @@ impl SerDes for VerifierParams {
     ///
     /// `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element` => bytes
     ///
+    /// The prepared generators of `VerifierParams::precomp` are not serialized.
+    ///
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn serialize<W: std::io::Write>(
         &self,
@@ -320,7 +1109,13 @@ impl SerDes for VerifierParams {
                 ERR_INVALID_VALUE,
             ));
         }
-        if self.n > (u32::max_value() as usize) || self.pp_len > (u32::max_value() as usize) {
+        if self.n > MAX_N {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_MAX_N,
+            ));
+        }
+        if self.pp_len > (u32::max_value() as usize) {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 ERR_INVALID_VALUE,
@@ -350,7 +1145,8 @@ impl SerDes for VerifierParams {
     ///
     /// bytes => `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element`
     ///
-    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    /// Returns an error if ciphersuite id is invalid or serialization fails;
+    /// or if pp_len is not a valid table length for n.
     fn deserialize<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
@@ -377,7 +1173,7 @@ impl SerDes for VerifierParams {
         let mut buf = [0u8; 4];
         reader.read_exact(&mut buf)?;
         let n = u32::from_le_bytes(buf) as usize;
-        if n > 65536 || n == 0 {
+        if n > MAX_N || n == 0 {
             // set an upper bounded of n
             // to prevent potential DoS kind of attacks
             return Err(std::io::Error::new(
@@ -389,7 +1185,7 @@ impl SerDes for VerifierParams {
         // write the generators
         let mut generators: Vec<PointproofsG2Affine> = vec![];
         for _i in 0..n {
-            let g = PointproofsG2Affine::deserialize(reader, true)?;
+            let g: PointproofsG2Affine = read_group_point(reader, true)?;
             generators.push(g);
         }
 
@@ -397,9 +1193,17 @@ impl SerDes for VerifierParams {
         reader.read_exact(&mut buf)?;
         let pp_len = u32::from_le_bytes(buf) as usize;
 
-        let mut precomp: Vec<PointproofsG1Affine> = vec![];
+        // only precomp_3 and precomp_256 exist for the verifier
+        if pp_len != 0 && pp_len != 3 * n && pp_len != 256 * n {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ERR_PRECOMP_LEN,
+            ));
+        }
+
+        let mut precomp: Vec<PointproofsG2Affine> = vec![];
         for _i in 0..pp_len {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
+            let g: PointproofsG2Affine = read_group_point(reader, true)?;
             precomp.push(g);
         }
 
@@ -410,9 +1214,11 @@ impl SerDes for VerifierParams {
             ciphersuite: csid[0],
             n,
             generators,
-            pp_len: 0,
-            precomp: vec![],
+            pp_len,
+            precomp,
             gt_elt,
+            prepared: vec![],
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
         })
     }
 }
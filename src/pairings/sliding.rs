@@ -0,0 +1,88 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for commitments over a sliding window of a stream.
+
+use pairings::*;
+
+impl SlidingCommitment {
+    /// generate a new sliding commitment, with n empty entries.
+    ///     * input: prover parameter set
+    ///     * output: a sliding commitment
+    ///     * error: invalid ciphersuite/parameters
+    pub fn new(prover_params: &ProverParams) -> Result<Self, PointproofsError> {
+        let values: Vec<Vec<u8>> = vec![vec![]; prover_params.n];
+        let commitment = Commitment::new(prover_params, &values)?;
+        Ok(Self {
+            commitment,
+            values,
+            pushed: 0,
+        })
+    }
+
+    /// push a new entry into the window
+    ///     * input: prover parameter set
+    ///     * input: the new value
+    ///     * output: the updated commitment
+    ///     * error: invalid ciphersuite, parameters
+    /// Once the window is full, the oldest entry is evicted
+    /// and the new one takes its index.
+    pub fn push<Blob: AsRef<[u8]>>(
+        &mut self,
+        prover_params: &ProverParams,
+        value: Blob,
+    ) -> Result<&Commitment, PointproofsError> {
+        if prover_params.n != self.values.len() {
+            return Err(PointproofsError::MismatchedN);
+        }
+        let index = self.pushed % self.values.len();
+        self.commitment.update(
+            prover_params,
+            index,
+            self.values[index].as_ref(),
+            value.as_ref(),
+        )?;
+        self.values[index] = value.as_ref().to_vec();
+        self.pushed += 1;
+        Ok(&self.commitment)
+    }
+
+    /// the commitment to the current window.
+    pub fn commitment(&self) -> &Commitment {
+        &self.commitment
+    }
+
+    /// the values of the current window, by index
+    pub fn values(&self) -> &[Vec<u8>] {
+        &self.values
+    }
+
+    /// the number of entries pushed so far
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+
+    /// maps a stream position to its index in the commitment;
+    /// returns None if the position is not within the window
+    pub fn index_of(&self, position: usize) -> Option<usize> {
+        if position >= self.pushed || self.pushed - position > self.values.len() {
+            return None;
+        }
+        Some(position % self.values.len())
+    }
+
+    /// generate a proof for the entry at a stream position
+    ///     * input: prover parameter set
+    ///     * input: the stream position
+    ///     * output: a proof for index `index_of(position)`
+    ///     * error: invalid ciphersuite/parameters; or the position is
+    ///     not within the window
+    pub fn prove(
+        &self,
+        prover_params: &ProverParams,
+        position: usize,
+    ) -> Result<Proof, PointproofsError> {
+        match self.index_of(position) {
+            Some(index) => Proof::new(prover_params, &self.values, index),
+            None => Err(PointproofsError::IndexOutOfRange),
+        }
+    }
+}
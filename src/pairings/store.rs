@@ -0,0 +1,77 @@
+//! this file is part of the pointproofs.
+//! It defines a store that deduplicates points across sets of parameters.
+
+use pairing_plus::serdes::SerDes;
+use pairings::*;
+
+impl ParamsStore {
+    /// an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// insert a set of prover parameters
+    ///     * input: prover parameter set
+    ///     * output: the fingerprint of the parameters
+    /// Points are addressed by their serialization, so the points that
+    /// several sets share (e.g. a master set and its truncations)
+    /// are stored once. Pre-computed tables are not stored.
+    /// The fingerprint is `ProverParams::fingerprint` of the inserted
+    /// parameters, so `get(&prover_params.fingerprint())` finds them.
+    /// It covers the pre-computed table, which the store drops:
+    /// parameters with a table are found by the fingerprint taken
+    /// with the table, and `get` returns them without it.
+    pub fn insert(&mut self, prover_params: &ProverParams) -> ParamsFingerprint {
+        let fingerprint = prover_params.fingerprint();
+
+        let mut ids: Vec<usize> = Vec::with_capacity(prover_params.generators.len());
+        for point in prover_params.generators.iter() {
+            let mut buf: Vec<u8> = vec![];
+            // serializing a valid point into a vector does not fail
+            point.serialize(&mut buf, true).unwrap();
+
+            let next_id = self.points.len();
+            let id = *self.point_ids.entry(buf).or_insert(next_id);
+            if id == next_id {
+                self.points.push(*point);
+            }
+            ids.push(id);
+        }
+
+        self.params.insert(
+            fingerprint,
+            (prover_params.ciphersuite, prover_params.n, ids),
+        );
+        fingerprint
+    }
+
+    /// the prover parameters with this fingerprint, if any;
+    /// the parameters come without pre-computed tables
+    pub fn get(&self, fingerprint: &ParamsFingerprint) -> Option<ProverParams> {
+        self.params
+            .get(fingerprint)
+            .map(|(ciphersuite, n, ids)| ProverParams {
+                ciphersuite: *ciphersuite,
+                n: *n,
+                generators: ids.iter().map(|id| self.points[*id]).collect(),
+                pp_len: 0,
+                precomp: vec![],
+                max_value_len: DEFAULT_MAX_VALUE_LEN,
+            })
+    }
+
+    /// the number of sets of parameters
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// if the store holds no parameters
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// the number of distinct points held by the store
+    pub fn num_points(&self) -> usize {
+        self.points.len()
+    }
+}
@@ -0,0 +1,87 @@
+//! this file is part of the pointproofs.
+//! It defines APIs for commitments under a public tweak.
+
+use pairings::*;
+
+/// Domain separation tag of the tweaked value hashes.
+const TWEAK_DST: &[u8] = b"Pointproofs-Tweak";
+
+/// the value is hashed as
+/// hash_to_field(TWEAK_DST | tweak length as u32 | tweak | value);
+/// the tweak length makes the encoding unambiguous
+fn tweak_value<Blob: AsRef<[u8]>>(tweak: &[u8], value: Blob) -> Vec<u8> {
+    [
+        TWEAK_DST,
+        &(tweak.len() as u32).to_be_bytes()[..],
+        tweak,
+        value.as_ref(),
+    ]
+    .concat()
+}
+
+fn tweak_values<Blob: AsRef<[u8]>>(tweak: &[u8], values: &[Blob]) -> Vec<Vec<u8>> {
+    values.iter().map(|v| tweak_value(tweak, v)).collect()
+}
+
+impl Commitment {
+    /// generate a new commitment under a public tweak.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * input: the tweak
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, or a tweak longer than u32::MAX
+    /// The tweak is part of the domain separation of the value hashes,
+    /// so commitments to the same values under different tweaks are unrelated;
+    /// each of them opens only with proofs for its own tweak.
+    /// Unlike a salt, the tweak is public and chosen by the caller.
+    pub fn new_tweaked<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        tweak: &[u8],
+    ) -> Result<Self, PointproofsError> {
+        if tweak.len() > u32::max_value() as usize {
+            return Err(PointproofsError::InvalidTweak);
+        }
+        Self::new(prover_params, &tweak_values(tweak, values))
+    }
+}
+
+impl Proof {
+    /// Generate a new proof under a public tweak.
+    ///     * input: prover parameter set
+    ///     * input: values for the proof
+    ///     * input: the index of the proof
+    ///     * input: the tweak of the commitment
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters, or a tweak longer than u32::MAX
+    pub fn new_tweaked<Blob: AsRef<[u8]>>(
+        prover_params: &ProverParams,
+        values: &[Blob],
+        index: usize,
+        tweak: &[u8],
+    ) -> Result<Self, PointproofsError> {
+        if tweak.len() > u32::max_value() as usize {
+            return Err(PointproofsError::InvalidTweak);
+        }
+        Self::new(prover_params, &tweak_values(tweak, values), index)
+    }
+
+    /// Verify the proof against the value and a commitment under a tweak.
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the value
+    ///     * input: the index
+    ///     * input: the tweak of the commitment
+    ///     * output: if the proof is valid w.r.t. commitment/value/index/tweak
+    pub fn verify_tweaked<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        value: Blob,
+        index: usize,
+        tweak: &[u8],
+    ) -> bool {
+        tweak.len() <= u32::max_value() as usize
+            && self.verify(verifier_params, com, tweak_value(tweak, value), index)
+    }
+}
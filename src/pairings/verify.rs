@@ -0,0 +1,491 @@
+//! this file is part of the pointproofs.
+//! It defines the verification of a single proof, and parsers of
+//! its inputs from byte slices. This is the part of the crate that is
+//! built without the `std` feature: it needs `alloc`, but not `std::io`.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ff::{Field, PrimeField};
+use pairing_plus::bls12_381::*;
+use pairing_plus::{CurveAffine, CurveProjective, EncodedPoint, GroupDecodingError};
+use pairings::err::*;
+use pairings::hash_to_field_pointproofs::hash_to_field_ciphersuite;
+use pairings::misc::n_matches;
+use pairings::param::*;
+use pairings::pointproofs_groups::*;
+use pairings::*;
+
+/// Set in the ciphersuite id byte of a commitment or a proof
+/// when the point is serialized uncompressed.
+/// Ciphersuite ids are small, so the bit is free, and the bytes
+/// written with compressed = true are unchanged, i.e., in the v0 format.
+pub const UNCOMPRESSED_POINT_FLAG: u8 = 0x80;
+
+/// Set in the ciphersuite id byte of a commitment or a proof
+/// when the n of its parameters follows the id, as a u32 in little endian;
+/// see `Commitment::serialize_with_n`. Blobs without it have an unknown n.
+pub const WITH_N_FLAG: u8 = 0x40;
+
+/// The length of a serialized Fq element.
+const FQ_LEN: usize = 48;
+
+impl PartialEq for Commitment {
+    /// n is left out, so that a commitment equals its deserialization.
+    fn eq(&self, other: &Self) -> bool {
+        self.ciphersuite == other.ciphersuite && self.commit == other.commit
+    }
+}
+
+impl Eq for Commitment {}
+
+impl PartialEq for Proof {
+    /// n is left out, so that a proof equals its deserialization.
+    fn eq(&self, other: &Self) -> bool {
+        self.ciphersuite == other.ciphersuite && self.proof == other.proof
+    }
+}
+
+impl Eq for Proof {}
+
+impl Proof {
+    /// Verify the proof.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the value
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// A commitment that is the identity element is always rejected;
+    /// values hash to non-zero scalars, so no vector commits to it.
+    /// So is a value longer than `VerifierParams::max_value_len`.
+    ///
+    /// Timing: the value bytes are never compared, only hashed with the hash
+    /// the ciphersuite selects (SHA-512, SHA3-512 or BLAKE2b-512, see
+    /// `hash_to_field_with_ciphersuite`), whose running time depends on
+    /// the length of the value alone. The aggregation scalars t_i and t_j
+    /// are hashed with SHA-512 under every ciphersuite.
+    /// The hash then goes through a field inversion, two scalar
+    /// multiplications and a pairing check, which are not constant time
+    /// in pairing-plus; so the timing of verify may depend on the hash of
+    /// the value, and verify should not be used on values that must stay secret.
+    pub fn verify<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        value: Blob,
+        index: usize,
+    ) -> bool {
+        self.verify_and_open(verifier_params, com, value, index)
+            .is_ok()
+    }
+
+    /// Verify the proof, and return the hash of the value on success.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the value
+    ///     * input: the index
+    ///     * output: hash_to_field(value), if the proof is valid w.r.t.
+    ///     the rest of inputs
+    ///     * error: the check that failed
+    /// The hash is the scalar the commitment opens to at index,
+    /// i.e., what was actually proven.
+    pub fn verify_and_open<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        value: Blob,
+        index: usize,
+    ) -> Result<Fr, VerifyFailure> {
+        self.verify_counted(
+            verifier_params,
+            com,
+            value,
+            index,
+            &mut VerifyCost::default(),
+        )
+    }
+
+    /// Verify the proof against an already hashed value.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the hash of the value
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    /// Same as `Proof::verify` with value_fr = hash_to_field(value),
+    /// but the hash is not computed: the caller is responsible for hashing
+    /// the value exactly as `ProverParams::hash_values` does, with the
+    /// ciphersuite of the parameters. The proof is only checked against
+    /// the scalar, so a scalar that is not the hash of the expected value
+    /// proves nothing about it. A zero scalar is never a hash, and is rejected.
+    pub fn verify_fr(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        value_fr: &Fr,
+        index: usize,
+    ) -> bool {
+        self.verify_counted_with(
+            verifier_params,
+            com,
+            index,
+            &mut VerifyCost::default(),
+            || Ok(*value_fr),
+        )
+        .is_ok()
+    }
+
+    /// `Proof::verify_and_open`, adding the operations it performs to cost.
+    pub(crate) fn verify_counted<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        value: Blob,
+        index: usize,
+        cost: &mut VerifyCost,
+    ) -> Result<Fr, VerifyFailure> {
+        self.verify_counted_with(verifier_params, com, index, cost, || {
+            // reject oversized values before hashing them
+            if value.as_ref().len() > verifier_params.max_value_len {
+                return Err(VerifyFailure::ValueTooLong);
+            }
+            Ok(hash_to_field_ciphersuite(com.ciphersuite, &value))
+        })
+    }
+
+    /// `Proof::verify_counted`, where hash returns the scalar of the value;
+    /// it is only called once the cheap checks passed.
+    fn verify_counted_with<F>(
+        &self,
+        verifier_params: &VerifierParams,
+        com: &Commitment,
+        index: usize,
+        cost: &mut VerifyCost,
+        hash: F,
+    ) -> Result<Fr, VerifyFailure>
+    where
+        F: FnOnce() -> Result<Fr, VerifyFailure>,
+    {
+        if self.ciphersuite != verifier_params.ciphersuite || self.ciphersuite != com.ciphersuite {
+            #[cfg(all(debug_assertions, feature = "std"))]
+            println!(
+                " ciphersuite fails {}, {}, {}",
+                self.ciphersuite, verifier_params.ciphersuite, com.ciphersuite
+            );
+            return Err(VerifyFailure::CiphersuiteMismatch);
+        }
+
+        if !check_ciphersuite(com.ciphersuite) {
+            return Err(VerifyFailure::InvalidCiphersuite);
+        }
+
+        if !n_matches(self.n, verifier_params.n) || !n_matches(com.n, verifier_params.n) {
+            return Err(VerifyFailure::MismatchedN);
+        }
+
+        if index >= verifier_params.n {
+            return Err(VerifyFailure::IndexOutOfRange);
+        }
+
+        // an identity commitment opens to nothing: with com = 0 the equation
+        // would require proof = g1^{-alpha^{N+1} * hash(value)}, and no
+        // valid set of values commits to 0 (the hashes are never 0)
+        if com.commit.is_zero() {
+            return Err(VerifyFailure::IdentityCommitment);
+        }
+
+        // verification formula: e(com, param[n-index-1]) = gt_elt ^ hash(value) * e(proof, generator_of_g2)
+        // which is to check
+        //  e(com^hash_inverse,  param[n-index-1]) * e(proof^{-hash_inverse}, generator_of_g2)
+        //  ?= gt_elt
+        // We modify the formula as above in order to avoid slow exponentation in the target group (which is Fq12)
+        // and perform two scalar multiplication by to 1/hash(value) in G1 instead, which is considerably faster.
+        // We also move the pairing from the right-hand-side to the left-hand-side in order
+        // to take advantage of the pairing product computation, which is faster than two pairings.
+
+        // step 1. compute hash_inverse
+        let hash = hash()?;
+        // a hash is invertible, see `hash_to_field` function;
+        // only a scalar from `Proof::verify_fr` may be 0
+        let hash_inverse = match hash.inverse() {
+            Some(h) => h,
+            None => return Err(VerifyFailure::PairingCheck),
+        };
+
+        // step 2, compute com^hash_inverse and proof^{-hash_inverse}
+        let mut com_mut = com.commit;
+        let mut proof_mut = self.proof;
+        proof_mut.negate();
+        com_mut.mul_assign(hash_inverse);
+        proof_mut.mul_assign(hash_inverse);
+        cost.g1_muls += 2;
+
+        // step 3. check pairing product
+        cost.pairings += 2;
+        let pairing = if verifier_params.prepared.is_empty() {
+            pointproofs_pairing_product(
+                com_mut.into_affine(),
+                verifier_params.generators[verifier_params.n - index - 1],
+                proof_mut.into_affine(),
+                PointproofsG2Affine::one(),
+            )
+        } else {
+            // the generators were prepared by `VerifierParams::precomp`
+            pointproofs_pairing_product_prepared(
+                com_mut.into_affine(),
+                &verifier_params.prepared[verifier_params.n - index - 1],
+                proof_mut.into_affine(),
+                &verifier_params.prepared[verifier_params.n],
+            )
+        };
+        if pairing != verifier_params.gt_elt {
+            return Err(VerifyFailure::PairingCheck);
+        }
+        Ok(hash)
+    }
+}
+
+impl Commitment {
+    /// Convert a byte slice into a commitment, with the format of
+    /// `Commitment::deserialize`, without `std::io`: the blob is in either
+    /// encoding, and may hold n, see `Commitment::serialize_with_n`.
+    /// The commit is checked to be in the group and canonically encoded,
+    /// and not to be the identity.
+    /// Returns an error if the slice is not exactly one commitment.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, PointproofsError> {
+        // no commitment is the identity, except with negligible probability
+        let (ciphersuite, n, commit) = point_from_slice(bytes, false)?;
+        Ok(Commitment {
+            ciphersuite,
+            n,
+            commit,
+        })
+    }
+}
+
+impl Proof {
+    /// Convert a byte slice into a proof, with the format of
+    /// `Proof::deserialize`, without `std::io`: the blob is in either
+    /// encoding, and may hold n, see `Proof::serialize_with_n`.
+    /// The proof is checked to be in the group and canonically encoded;
+    /// as in `Proof::deserialize`, it may be the identity.
+    /// Returns an error if the slice is not exactly one proof.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, PointproofsError> {
+        // with n = 1 the proof is the identity, see `Proof::new`
+        let (ciphersuite, n, proof) = point_from_slice(bytes, true)?;
+        Ok(Proof {
+            ciphersuite,
+            n,
+            proof,
+        })
+    }
+}
+
+impl VerifierParams {
+    /// Convert a byte slice into a VerifierParams, with the format of
+    /// `VerifierParams::deserialize`, without `std::io`:
+    ///
+    /// `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element`
+    ///
+    /// n is read first and the length of the slice is checked against it,
+    /// so a short blob claiming a large n fails before any point is read.
+    /// Every point is checked to be in the group.
+    /// Returns an error if the slice is not exactly one VerifierParams,
+    /// or if pp_len is not a valid table length for n.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, PointproofsError> {
+        let mut offset = 0;
+        let ciphersuite = take(bytes, &mut offset, 1)?[0];
+        if !check_ciphersuite(ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite);
+        }
+        let n = n_from_slice(bytes, &mut offset)?;
+        let point_len = encoded_len::<PointproofsG2Affine>(true);
+        if bytes.len() - offset < n * point_len + 4 + 12 * FQ_LEN {
+            return Err(PointproofsError::InvalidEncoding(ERR_SHORT_INPUT));
+        }
+
+        let mut generators: Vec<PointproofsG2Affine> = Vec::with_capacity(n);
+        for _ in 0..n {
+            let encoded = take(bytes, &mut offset, point_len)?;
+            generators.push(group_point_from_slice(encoded, true)?);
+        }
+
+        let pp_len = u32_from_slice(take(bytes, &mut offset, 4)?);
+        // only precomp_3 and precomp_256 exist for the verifier
+        if pp_len != 0 && pp_len != 3 * n && pp_len != 256 * n {
+            return Err(PointproofsError::InvalidEncoding(ERR_PRECOMP_LEN));
+        }
+        let remaining = pp_len * point_len + 12 * FQ_LEN;
+        if bytes.len() - offset < remaining {
+            return Err(PointproofsError::InvalidEncoding(ERR_SHORT_INPUT));
+        }
+        if bytes.len() - offset > remaining {
+            return Err(PointproofsError::InvalidEncoding(ERR_TRAILING));
+        }
+        let mut precomp: Vec<PointproofsG2Affine> = Vec::with_capacity(pp_len);
+        for _ in 0..pp_len {
+            let encoded = take(bytes, &mut offset, point_len)?;
+            precomp.push(group_point_from_slice(encoded, true)?);
+        }
+
+        let gt_elt = fq12_from_slice(take(bytes, &mut offset, 12 * FQ_LEN)?)?;
+
+        Ok(VerifierParams {
+            ciphersuite,
+            n,
+            generators,
+            pp_len,
+            precomp,
+            gt_elt,
+            prepared: vec![],
+            max_value_len: DEFAULT_MAX_VALUE_LEN,
+        })
+    }
+}
+
+/// the length of a serialized point of G, in the given encoding
+fn encoded_len<G: CurveAffine>(compressed: bool) -> usize {
+    if compressed {
+        G::Compressed::size()
+    } else {
+        G::Uncompressed::size()
+    }
+}
+
+/// the len bytes of the slice from offset, which is moved past them
+fn take<'a>(bytes: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], PointproofsError> {
+    if bytes.len() - *offset < len {
+        return Err(PointproofsError::InvalidEncoding(ERR_SHORT_INPUT));
+    }
+    let res = &bytes[*offset..*offset + len];
+    *offset += len;
+    Ok(res)
+}
+
+/// reads 4 bytes as a u32 in little endian
+fn u32_from_slice(bytes: &[u8]) -> usize {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(bytes);
+    u32::from_le_bytes(buf) as usize
+}
+
+/// reads n from the slice at offset, and checks it is in [1, MAX_N]
+fn n_from_slice(bytes: &[u8], offset: &mut usize) -> Result<usize, PointproofsError> {
+    let n = u32_from_slice(take(bytes, offset, 4)?);
+    if n == 0 {
+        return Err(PointproofsError::InvalidN);
+    }
+    if n > MAX_N {
+        return Err(PointproofsError::NTooLarge);
+    }
+    Ok(n)
+}
+
+/// reads a point of either group from exactly its encoding,
+/// checked to be on the curve and in the group
+fn group_point_from_slice<G: CurveAffine>(
+    bytes: &[u8],
+    compressed: bool,
+) -> Result<G, PointproofsError> {
+    let point = if compressed {
+        let mut encoded = G::Compressed::empty();
+        encoded.as_mut().copy_from_slice(bytes);
+        encoded.into_affine()
+    } else {
+        let mut encoded = G::Uncompressed::empty();
+        encoded.as_mut().copy_from_slice(bytes);
+        encoded.into_affine()
+    };
+    match point {
+        Ok(p) => Ok(p),
+        Err(GroupDecodingError::NotInSubgroup) => Err(PointproofsError::SubgroupCheckFailed),
+        Err(_) => Err(PointproofsError::InvalidEncoding(ERR_INVALID_POINT)),
+    }
+}
+
+/// reads `|ciphersuite id | point |` or `|ciphersuite id | n | point |`
+/// as `Commitment::deserialize` does, from the whole slice; the point must
+/// be canonically encoded, and the identity is rejected unless
+/// allow_identity is set.
+/// Returns the ciphersuite, n (0 if it is not in the blob) and the point.
+fn point_from_slice(
+    bytes: &[u8],
+    allow_identity: bool,
+) -> Result<(Ciphersuite, usize, PointproofsG1), PointproofsError> {
+    let mut offset = 0;
+    let constants = take(bytes, &mut offset, 1)?[0];
+    let ciphersuite = constants & !UNCOMPRESSED_POINT_FLAG & !WITH_N_FLAG;
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite);
+    }
+    let compressed = constants & UNCOMPRESSED_POINT_FLAG == 0;
+    let n = if constants & WITH_N_FLAG != 0 {
+        n_from_slice(bytes, &mut offset)?
+    } else {
+        0
+    };
+    let encoded = take(
+        bytes,
+        &mut offset,
+        encoded_len::<PointproofsG1Affine>(compressed),
+    )?;
+    if offset != bytes.len() {
+        return Err(PointproofsError::InvalidEncoding(ERR_TRAILING));
+    }
+
+    let point: PointproofsG1Affine = group_point_from_slice(encoded, compressed)?;
+    // equal points are always read from equal bytes
+    let canonical = if compressed {
+        point.into_compressed().as_ref() == encoded
+    } else {
+        point.into_uncompressed().as_ref() == encoded
+    };
+    if !canonical {
+        return Err(PointproofsError::InvalidEncoding(ERR_NON_CANONICAL_POINT));
+    }
+    if point.is_zero() && !allow_identity {
+        return Err(PointproofsError::InvalidEncoding(ERR_IDENTITY_POINT));
+    }
+    Ok((ciphersuite, n, point.into_projective()))
+}
+
+/// reads an Fq element in big endian, checked to be below the modulus
+fn fq_from_slice(bytes: &[u8]) -> Result<Fq, PointproofsError> {
+    let mut repr = FqRepr::default();
+    // the limbs are in little endian order
+    for (limb, chunk) in repr.as_mut().iter_mut().rev().zip(bytes.chunks(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        *limb = u64::from_be_bytes(buf);
+    }
+    match Fq::from_repr(repr) {
+        Ok(e) => Ok(e),
+        Err(_) => Err(PointproofsError::InvalidEncoding(ERR_INVALID_FIELD_ELEMENT)),
+    }
+}
+
+/// reads an Fq12 element as pairing-plus serializes it: 12 Fq elements,
+/// c0.c0.c0, c0.c0.c1, c0.c1.c0, ..., c1.c2.c1
+fn fq12_from_slice(bytes: &[u8]) -> Result<Fq12, PointproofsError> {
+    let mut coeffs = [Fq::zero(); 12];
+    for (c, chunk) in coeffs.iter_mut().zip(bytes.chunks(FQ_LEN)) {
+        *c = fq_from_slice(chunk)?;
+    }
+    let fq2 = |i: usize| Fq2 {
+        c0: coeffs[2 * i],
+        c1: coeffs[2 * i + 1],
+    };
+    Ok(Fq12 {
+        c0: Fq6 {
+            c0: fq2(0),
+            c1: fq2(1),
+            c2: fq2(2),
+        },
+        c1: Fq6 {
+            c0: fq2(3),
+            c1: fq2(4),
+            c2: fq2(5),
+        },
+    })
+}
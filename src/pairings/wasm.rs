@@ -0,0 +1,99 @@
+//! this file is part of the pointproofs.
+//! it exposes the verification APIs to JavaScript via wasm_bindgen.
+//! None of the functions here touch the random number generator,
+//! so `OsRng` is not linked into a verification-only bundle.
+
+use pairing_plus::serdes::SerDes;
+use pairings::*;
+use wasm_bindgen::prelude::*;
+
+/// Deserialized verifier parameters.
+#[wasm_bindgen]
+pub struct WasmVerifierParams {
+    inner: VerifierParams,
+}
+
+/// Deserialized commitment.
+#[wasm_bindgen]
+pub struct WasmCommitment {
+    inner: Commitment,
+}
+
+/// Deserialized proof.
+#[wasm_bindgen]
+pub struct WasmProof {
+    inner: Proof,
+}
+
+#[wasm_bindgen]
+impl WasmVerifierParams {
+    /// Loads the verifier parameters from their (compressed) serialization.
+    /// Returns undefined if the bytes are not valid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<WasmVerifierParams> {
+        let mut reader = bytes;
+        VerifierParams::deserialize(&mut reader, true)
+            .ok()
+            .map(|inner| WasmVerifierParams { inner })
+    }
+
+    /// The parameter n.
+    pub fn n(&self) -> usize {
+        self.inner.n
+    }
+}
+
+#[wasm_bindgen]
+impl WasmCommitment {
+    /// Loads a commitment from its (compressed) serialization.
+    /// Returns undefined if the bytes are not valid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<WasmCommitment> {
+        let mut reader = bytes;
+        Commitment::deserialize(&mut reader, true)
+            .ok()
+            .map(|inner| WasmCommitment { inner })
+    }
+}
+
+#[wasm_bindgen]
+impl WasmProof {
+    /// Loads a proof from its (compressed) serialization.
+    /// Returns undefined if the bytes are not valid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<WasmProof> {
+        let mut reader = bytes;
+        Proof::deserialize(&mut reader, true)
+            .ok()
+            .map(|inner| WasmProof { inner })
+    }
+
+    /// Verifies the proof against the commitment for value at index,
+    /// same as `Proof::verify`.
+    pub fn verify(
+        &self,
+        verifier_params: &WasmVerifierParams,
+        com: &WasmCommitment,
+        value: &[u8],
+        index: usize,
+    ) -> bool {
+        self.inner
+            .verify(&verifier_params.inner, &com.inner, value, index)
+    }
+}
+
+/// Deserializes the commitment and the proof, and verifies the proof.
+/// Returns false if either of them cannot be deserialized.
+#[wasm_bindgen]
+pub fn verify_bytes(
+    verifier_params: &WasmVerifierParams,
+    com: &[u8],
+    proof: &[u8],
+    value: &[u8],
+    index: usize,
+) -> bool {
+    match (
+        WasmCommitment::from_bytes(com),
+        WasmProof::from_bytes(proof),
+    ) {
+        (Some(com), Some(proof)) => proof.verify(verifier_params, &com, value, index),
+        _ => false,
+    }
+}
@@ -1,5 +1,5 @@
 use pairings::param::paramgen_from_seed;
-use pairings::{Commitment, Proof};
+use pairings::{Commitment, PointproofsError, Proof, VerifyCost};
 
 #[test]
 fn negative_test_same_commit_aggregation() {
@@ -7,7 +7,7 @@ fn negative_test_same_commit_aggregation() {
     let (prover_params, verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut verifier_params2 = verifier_params.clone();
-    verifier_params2.ciphersuite = 1;
+    verifier_params2.ciphersuite = 3;
 
     let mut init_values = Vec::with_capacity(n);
     for i in 0..n {
@@ -22,7 +22,7 @@ fn negative_test_same_commit_aggregation() {
 
     let com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 3;
 
     let set = vec![1usize, 4, 7];
     let set2 = vec![1usize, 4, 6, 7];
@@ -35,7 +35,7 @@ fn negative_test_same_commit_aggregation() {
         value_sub_vector.push(values[*index]);
     }
     let mut proofs2 = proofs.clone();
-    proofs2[0].ciphersuite = 1;
+    proofs2[0].ciphersuite = 3;
 
     assert!(
         Proof::same_commit_aggregate(&com2, &proofs, &set, &value_sub_vector, prover_params.n)
@@ -183,7 +183,7 @@ fn negative_test_cross_commit_aggregation() {
         value_sub_vector.push(tmp_value_sub_vector);
     }
 
-    commits[0].ciphersuite = 1;
+    commits[0].ciphersuite = 3;
     assert!(Proof::cross_commit_aggregate_full(
         &commits,
         &proofs,
@@ -194,7 +194,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[0].ciphersuite = 0;
 
-    commits[1].ciphersuite = 1;
+    commits[1].ciphersuite = 3;
     assert!(Proof::cross_commit_aggregate_full(
         &commits,
         &proofs,
@@ -205,7 +205,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[1].ciphersuite = 0;
 
-    proofs[0][0].ciphersuite = 1;
+    proofs[0][0].ciphersuite = 3;
     assert!(Proof::cross_commit_aggregate_full(
         &commits,
         &proofs,
@@ -236,7 +236,7 @@ fn negative_test_cross_commit_aggregation() {
     )
     .unwrap();
 
-    commits[0].ciphersuite = 1;
+    commits[0].ciphersuite = 3;
     assert!(Proof::cross_commit_aggregate_partial(
         &commits,
         &same_commit_proof,
@@ -247,7 +247,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[0].ciphersuite = 0;
 
-    commits[1].ciphersuite = 1;
+    commits[1].ciphersuite = 3;
     assert!(Proof::cross_commit_aggregate_partial(
         &commits,
         &same_commit_proof,
@@ -258,7 +258,7 @@ fn negative_test_cross_commit_aggregation() {
     .is_err());
     commits[1].ciphersuite = 0;
 
-    same_commit_proof[0].ciphersuite = 1;
+    same_commit_proof[0].ciphersuite = 3;
     assert!(Proof::cross_commit_aggregate_partial(
         &commits,
         &same_commit_proof,
@@ -520,3 +520,642 @@ fn test_cross_commit_aggregation_large() {
         ));
     }
 }
+
+#[test]
+fn test_decompose_aggregate() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut commits: Vec<Commitment> = vec![];
+    let mut proofs: Vec<Vec<Proof>> = vec![];
+    let mut value_sub_vector: Vec<Vec<Vec<u8>>> = vec![];
+    let mut set = vec![];
+    for j in 0..3 {
+        let mut init_values = Vec::with_capacity(n);
+        for i in 0..n {
+            let s = format!("this is message number {} for commit {}", i, j);
+            init_values.push(s.into_bytes());
+        }
+        commits.push(Commitment::new(&prover_params, &init_values).unwrap());
+
+        let tmp_set = vec![1usize, 3, 6];
+        let mut tmp_proofs: Vec<Proof> = vec![];
+        let mut tmp_value_sub_vector: Vec<Vec<u8>> = vec![];
+        for index in &tmp_set {
+            tmp_proofs.push(Proof::new(&prover_params, &init_values, *index).unwrap());
+            tmp_value_sub_vector.push(init_values[*index].clone());
+        }
+        set.push(tmp_set);
+        proofs.push(tmp_proofs);
+        value_sub_vector.push(tmp_value_sub_vector);
+    }
+
+    // all good
+    let res =
+        Proof::decompose_aggregate(&verifier_params, &commits, &proofs, &set, &value_sub_vector);
+    assert_eq!(res.len(), 9);
+    for (pos, e) in res.iter().enumerate() {
+        assert_eq!(*e, (pos / 3, set[pos / 3][pos % 3], true));
+    }
+
+    // a single bad opening: commitment 1, index 3
+    value_sub_vector[1][1] = b"this is a wrong message".to_vec();
+    let agg_proof = Proof::cross_commit_aggregate_full(
+        &commits,
+        &proofs,
+        &set,
+        &value_sub_vector,
+        prover_params.n,
+    )
+    .unwrap();
+    assert!(!agg_proof.cross_commit_batch_verify(
+        &verifier_params,
+        &commits,
+        &set,
+        &value_sub_vector
+    ));
+    let res =
+        Proof::decompose_aggregate(&verifier_params, &commits, &proofs, &set, &value_sub_vector);
+    let failed: Vec<(usize, usize, bool)> = res.into_iter().filter(|e| !e.2).collect();
+    assert_eq!(failed, vec![(1, 3, false)]);
+
+    // a missing proof is reported as invalid
+    proofs[2].pop();
+    let res =
+        Proof::decompose_aggregate(&verifier_params, &commits, &proofs, &set, &value_sub_vector);
+    assert_eq!(res[8], (2, 6, false));
+}
+
+#[test]
+fn test_verify_cost_estimate() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut commits: Vec<Commitment> = vec![];
+    let mut proofs: Vec<Vec<Proof>> = vec![];
+    let mut value_sub_vector: Vec<Vec<Vec<u8>>> = vec![];
+    let mut set: Vec<Vec<usize>> = vec![];
+    for (j, tmp_set) in [vec![2usize], vec![0, 5], vec![1, 3, 4, 7]]
+        .iter()
+        .enumerate()
+    {
+        let mut init_values = Vec::with_capacity(n);
+        for i in 0..n {
+            let s = format!("this is message number {} for commit {}", i, j);
+            init_values.push(s.into_bytes());
+        }
+        commits.push(Commitment::new(&prover_params, &init_values).unwrap());
+
+        let mut tmp_proofs: Vec<Proof> = vec![];
+        let mut tmp_value_sub_vector: Vec<Vec<u8>> = vec![];
+        for index in tmp_set {
+            tmp_proofs.push(Proof::new(&prover_params, &init_values, *index).unwrap());
+            tmp_value_sub_vector.push(init_values[*index].clone());
+        }
+        set.push(tmp_set.clone());
+        proofs.push(tmp_proofs);
+        value_sub_vector.push(tmp_value_sub_vector);
+    }
+
+    // a single opening
+    let (res, cost) = proofs[0][0].verify_with_cost(
+        &verifier_params,
+        &commits[..1],
+        &set[..1],
+        &value_sub_vector[..1],
+    );
+    assert!(res);
+    assert_eq!(cost, Proof::verify_cost_estimate(1, 1));
+    assert_eq!(cost.pairings, 2);
+
+    // a single commitment
+    let agg_proof =
+        Proof::same_commit_aggregate(&commits[2], &proofs[2], &set[2], &value_sub_vector[2], n)
+            .unwrap();
+    let (res, cost) = agg_proof.verify_with_cost(
+        &verifier_params,
+        &commits[2..],
+        &set[2..],
+        &value_sub_vector[2..],
+    );
+    assert!(res);
+    assert_eq!(cost, Proof::verify_cost_estimate(1, 4));
+    assert_eq!(cost.g2_multiexp_bases, 4);
+
+    // across commitments
+    let agg_proof =
+        Proof::cross_commit_aggregate_full(&commits, &proofs, &set, &value_sub_vector, n).unwrap();
+    let (res, cost) =
+        agg_proof.verify_with_cost(&verifier_params, &commits, &set, &value_sub_vector);
+    assert!(res);
+    assert_eq!(cost, Proof::verify_cost_estimate(3, 7));
+    assert_eq!(cost.pairings, 4);
+    assert_eq!(cost.g2_multiexps, 3);
+
+    // an invalid opening costs the same
+    value_sub_vector[1][0] = b"this is a wrong message".to_vec();
+    let (res, cost) =
+        agg_proof.verify_with_cost(&verifier_params, &commits, &set, &value_sub_vector);
+    assert!(!res);
+    assert_eq!(cost, Proof::verify_cost_estimate(3, 7));
+
+    // malformed inputs are rejected before any operation
+    let (res, cost) =
+        agg_proof.verify_with_cost(&verifier_params, &commits, &set[1..], &value_sub_vector);
+    assert!(!res);
+    assert_eq!(cost, VerifyCost::default());
+    assert_eq!(Proof::verify_cost_estimate(0, 3), VerifyCost::default());
+    assert_eq!(Proof::verify_cost_estimate(3, 2), VerifyCost::default());
+}
+
+#[test]
+fn test_same_commit_aggregate_inputs() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    // a second vector that agrees with the first one on the set below
+    let mut values2 = values.clone();
+    values2[0] = "this is another message".to_string();
+
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    let set = vec![1usize, 4, 7];
+    let proofs: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values, *i).unwrap())
+        .collect();
+    let proofs2: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values2, *i).unwrap())
+        .collect();
+    let value_sub_vector: Vec<&String> = set.iter().map(|i| &values[*i]).collect();
+
+    // empty input sets
+    let empty_proofs: Vec<Proof> = vec![];
+    let empty_values: Vec<&String> = vec![];
+    match Proof::same_commit_aggregate(&com, &empty_proofs, &[], &empty_values, n) {
+        Err(PointproofsError::EmptySet) => (),
+        _ => panic!("expected PointproofsError::EmptySet"),
+    }
+    let agg_proof =
+        Proof::same_commit_aggregate(&com, &proofs, &set, &value_sub_vector, n).unwrap();
+    assert!(!agg_proof.same_commit_batch_verify(&verifier_params, &com, &[], &empty_values));
+
+    // an index repeated with another value
+    let dup_set = vec![1usize, 4, 4];
+    match Proof::same_commit_aggregate(&com, &proofs, &dup_set, &value_sub_vector, n) {
+        Err(PointproofsError::ConflictingValue) => (),
+        _ => panic!("expected PointproofsError::ConflictingValue"),
+    }
+
+    // the aggregated proof is bound to its commitment
+    assert!(agg_proof.same_commit_batch_verify(&verifier_params, &com, &set, &value_sub_vector));
+    assert!(!agg_proof.same_commit_batch_verify(&verifier_params, &com2, &set, &value_sub_vector));
+    let agg_proof2 =
+        Proof::same_commit_aggregate(&com2, &proofs2, &set, &value_sub_vector, n).unwrap();
+    assert!(agg_proof2.same_commit_batch_verify(&verifier_params, &com2, &set, &value_sub_vector));
+    assert!(!agg_proof2.same_commit_batch_verify(&verifier_params, &com, &set, &value_sub_vector));
+}
+
+#[test]
+fn test_cross_aggregate_different_n() {
+    let (pp8, vp8) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 8).unwrap();
+    let (pp16, vp16) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 16).unwrap();
+    let (pp4, vp4) =
+        paramgen_from_seed("This is another very very very very long Seed", 0, 4).unwrap();
+    let pps = [&pp8, &pp16, &pp8, &pp4];
+    let vps = [&vp8, &vp16, &vp8, &vp4];
+    let sets = [vec![1usize, 3], vec![0usize, 9, 15], vec![], vec![2usize]];
+
+    let mut commits: Vec<Commitment> = vec![];
+    let mut proofs: Vec<Vec<Proof>> = vec![];
+    let mut value_sub_vector: Vec<Vec<Vec<u8>>> = vec![];
+    let mut all_values: Vec<Vec<Vec<u8>>> = vec![];
+    let mut n_list: Vec<usize> = vec![];
+    for (j, pp) in pps.iter().enumerate() {
+        let n = vps[j].n;
+        let mut init_values = Vec::with_capacity(n);
+        for i in 0..n {
+            let s = format!("this is message number {} for commit {}", i, j);
+            init_values.push(s.into_bytes());
+        }
+        commits.push(Commitment::new(pp, &init_values).unwrap());
+        proofs.push(
+            sets[j]
+                .iter()
+                .map(|i| Proof::new(pp, &init_values, *i).unwrap())
+                .collect(),
+        );
+        value_sub_vector.push(sets[j].iter().map(|i| init_values[*i].clone()).collect());
+        all_values.push(init_values);
+        n_list.push(n);
+    }
+    let set = sets.to_vec();
+
+    let agg_proof =
+        Proof::cross_aggregate(&commits, &proofs, &set, &value_sub_vector, &n_list).unwrap();
+    assert!(agg_proof.cross_aggregate_verify(&vps, &commits, &set, &value_sub_vector));
+
+    // wrong value
+    let mut wrong_values = value_sub_vector.clone();
+    wrong_values[1][2] = b"this is a wrong message".to_vec();
+    assert!(!agg_proof.cross_aggregate_verify(&vps, &commits, &set, &wrong_values));
+    // wrong parameters
+    let wrong_vps = [&vp8, &vp16, &vp8, &vp8];
+    assert!(!agg_proof.cross_aggregate_verify(&wrong_vps, &commits, &set, &value_sub_vector));
+    // the opening of commitment 0 replayed against another commitment
+    let mut other_commits = commits.clone();
+    other_commits[0] = commits[2].clone();
+    assert!(!agg_proof.cross_aggregate_verify(&vps, &other_commits, &set, &value_sub_vector));
+    let mut other_proofs = proofs.clone();
+    other_proofs[0][0] = Proof::new(&pp8, &all_values[2], 1).unwrap();
+    let bad_proof =
+        Proof::cross_aggregate(&commits, &other_proofs, &set, &value_sub_vector, &n_list).unwrap();
+    assert!(!bad_proof.cross_aggregate_verify(&vps, &commits, &set, &value_sub_vector));
+    // index out of range for its own n
+    let mut bad_set = set.clone();
+    bad_set[3][0] = 5;
+    match Proof::cross_aggregate(&commits, &proofs, &bad_set, &value_sub_vector, &n_list) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+    assert!(!agg_proof.cross_aggregate_verify(&vps, &commits, &bad_set, &value_sub_vector));
+
+    // a single commitment with openings is a same commit aggregation
+    let single_set = vec![vec![], set[1].clone(), vec![], vec![]];
+    let single_proofs = vec![vec![], proofs[1].clone(), vec![], vec![]];
+    let single_values = vec![vec![], value_sub_vector[1].clone(), vec![], vec![]];
+    let single_proof = Proof::cross_aggregate(
+        &commits,
+        &single_proofs,
+        &single_set,
+        &single_values,
+        &n_list,
+    )
+    .unwrap();
+    assert_eq!(
+        single_proof,
+        Proof::same_commit_aggregate(&commits[1], &proofs[1], &set[1], &value_sub_vector[1], 16)
+            .unwrap()
+    );
+    assert!(single_proof.cross_aggregate_verify(&vps, &commits, &single_set, &single_values));
+
+    // no openings at all
+    let empty_set: Vec<Vec<usize>> = vec![vec![]; 4];
+    let empty_proofs: Vec<Vec<Proof>> = vec![vec![]; 4];
+    let empty_values: Vec<Vec<Vec<u8>>> = vec![vec![]; 4];
+    match Proof::cross_aggregate(&commits, &empty_proofs, &empty_set, &empty_values, &n_list) {
+        Err(PointproofsError::EmptySet) => (),
+        _ => panic!("expected PointproofsError::EmptySet"),
+    }
+    assert!(!agg_proof.cross_aggregate_verify(&vps, &commits, &empty_set, &empty_values));
+
+    // with a single n, this is the usual cross commitment aggregation
+    let same_n_commits = vec![commits[0].clone(), commits[2].clone()];
+    let same_n_set = vec![vec![1usize, 3], vec![4usize]];
+    let same_n_proofs = vec![
+        proofs[0].clone(),
+        vec![Proof::new(&pp8, &all_values[2], 4).unwrap()],
+    ];
+    let same_n_values = vec![value_sub_vector[0].clone(), vec![all_values[2][4].clone()]];
+    let agg_proof = Proof::cross_aggregate(
+        &same_n_commits,
+        &same_n_proofs,
+        &same_n_set,
+        &same_n_values,
+        &[8, 8],
+    )
+    .unwrap();
+    assert_eq!(
+        agg_proof,
+        Proof::cross_commit_aggregate_full(
+            &same_n_commits,
+            &same_n_proofs,
+            &same_n_set,
+            &same_n_values,
+            8
+        )
+        .unwrap()
+    );
+    assert!(agg_proof.cross_commit_batch_verify(
+        &vp8,
+        &same_n_commits,
+        &same_n_set,
+        &same_n_values
+    ));
+    assert!(agg_proof.cross_aggregate_verify(
+        &[&vp8, &vp8],
+        &same_n_commits,
+        &same_n_set,
+        &same_n_values
+    ));
+}
+
+#[test]
+fn test_aggregate_duplicated_indices() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let values2: Vec<String> = (0..n)
+        .map(|i| format!("this is another message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    let proofs: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values, i).unwrap())
+        .collect();
+    let proofs2: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values2, i).unwrap())
+        .collect();
+
+    let set = vec![1usize, 4, 7];
+    let dup_set = vec![1usize, 4, 1, 7, 4];
+    let sub_proofs: Vec<Proof> = set.iter().map(|i| proofs[*i].clone()).collect();
+    let dup_proofs: Vec<Proof> = dup_set.iter().map(|i| proofs[*i].clone()).collect();
+    let sub_values: Vec<&String> = set.iter().map(|i| &values[*i]).collect();
+    let dup_values: Vec<&String> = dup_set.iter().map(|i| &values[*i]).collect();
+    let sub_proofs2: Vec<Proof> = set.iter().map(|i| proofs2[*i].clone()).collect();
+    let sub_values2: Vec<&String> = set.iter().map(|i| &values2[*i]).collect();
+
+    // a clean duplicate is collapsed
+    let agg = Proof::same_commit_aggregate(&com, &sub_proofs, &set, &sub_values, n).unwrap();
+    let dup_agg =
+        Proof::same_commit_aggregate(&com, &dup_proofs, &dup_set, &dup_values, n).unwrap();
+    assert_eq!(agg, dup_agg);
+    assert!(agg.same_commit_batch_verify(&verifier_params, &com, &dup_set, &dup_values));
+    assert!(dup_agg.same_commit_batch_verify(&verifier_params, &com, &set, &sub_values));
+
+    let coms = vec![com.clone(), com2.clone()];
+    let sets = vec![set.clone(), set.clone()];
+    let dup_sets = vec![dup_set.clone(), set.clone()];
+    let sub_values_2d = vec![sub_values.clone(), sub_values2.clone()];
+    let dup_values_2d = vec![dup_values.clone(), sub_values2.clone()];
+    let x_agg = Proof::cross_commit_aggregate_full(
+        &coms,
+        &[sub_proofs.clone(), sub_proofs2.clone()],
+        &sets,
+        &sub_values_2d,
+        n,
+    )
+    .unwrap();
+    let dup_x_agg = Proof::cross_commit_aggregate_full(
+        &coms,
+        &[dup_proofs.clone(), sub_proofs2.clone()],
+        &dup_sets,
+        &dup_values_2d,
+        n,
+    )
+    .unwrap();
+    assert_eq!(x_agg, dup_x_agg);
+    assert!(x_agg.cross_commit_batch_verify(&verifier_params, &coms, &dup_sets, &dup_values_2d));
+    let agg2 = Proof::same_commit_aggregate(&com2, &sub_proofs2, &set, &sub_values2, n).unwrap();
+    let partial = Proof::cross_commit_aggregate_partial(
+        &coms,
+        &[dup_agg.clone(), agg2.clone()],
+        &dup_sets,
+        &dup_values_2d,
+        n,
+    )
+    .unwrap();
+    assert_eq!(partial, x_agg);
+    let cross = Proof::cross_aggregate(
+        &coms,
+        &[dup_proofs.clone(), sub_proofs2.clone()],
+        &dup_sets,
+        &dup_values_2d,
+        &[n, n],
+    )
+    .unwrap();
+    assert_eq!(cross, x_agg);
+    let vps = [&verifier_params, &verifier_params];
+    assert!(cross.cross_aggregate_verify(&vps, &coms, &dup_sets, &dup_values_2d));
+
+    // a conflicting duplicate is rejected
+    let mut bad_values = dup_values.clone();
+    bad_values[2] = &values[2];
+    match Proof::same_commit_aggregate(&com, &dup_proofs, &dup_set, &bad_values, n) {
+        Err(PointproofsError::ConflictingValue) => (),
+        _ => panic!("expected PointproofsError::ConflictingValue"),
+    }
+    assert!(!agg.same_commit_batch_verify(&verifier_params, &com, &dup_set, &bad_values));
+    let bad_values_2d = vec![bad_values.clone(), sub_values2.clone()];
+    match Proof::cross_commit_aggregate_full(
+        &coms,
+        &[dup_proofs.clone(), sub_proofs2.clone()],
+        &dup_sets,
+        &bad_values_2d,
+        n,
+    ) {
+        Err(PointproofsError::ConflictingValue) => (),
+        _ => panic!("expected PointproofsError::ConflictingValue"),
+    }
+    match Proof::cross_commit_aggregate_partial(
+        &coms,
+        &[dup_agg.clone(), agg2.clone()],
+        &dup_sets,
+        &bad_values_2d,
+        n,
+    ) {
+        Err(PointproofsError::ConflictingValue) => (),
+        _ => panic!("expected PointproofsError::ConflictingValue"),
+    }
+    match Proof::cross_aggregate(
+        &coms,
+        &[dup_proofs.clone(), sub_proofs2.clone()],
+        &dup_sets,
+        &bad_values_2d,
+        &[n, n],
+    ) {
+        Err(PointproofsError::ConflictingValue) => (),
+        _ => panic!("expected PointproofsError::ConflictingValue"),
+    }
+    assert!(!x_agg.cross_commit_batch_verify(&verifier_params, &coms, &dup_sets, &bad_values_2d));
+    assert!(!cross.cross_aggregate_verify(&vps, &coms, &dup_sets, &bad_values_2d));
+}
+
+#[test]
+fn test_aggregate_round_trip() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let indices = vec![1usize, 4, 7];
+    let proofs: Vec<Proof> = indices
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values, *i).unwrap())
+        .collect();
+    let sub_values: Vec<&[u8]> = indices.iter().map(|i| values[*i].as_bytes()).collect();
+
+    // same as `same_commit_aggregate` with the recorded n
+    let agg = Proof::aggregate(&com, &proofs, &sub_values, &indices).unwrap();
+    assert_eq!(
+        agg,
+        Proof::same_commit_aggregate(&com, &proofs, &indices, &sub_values, n).unwrap()
+    );
+    assert!(Proof::verify_aggregated(
+        &verifier_params,
+        &com,
+        &sub_values,
+        &indices,
+        &agg
+    ));
+    let mut wrong_values = sub_values.clone();
+    wrong_values[1] = &b"this is a wrong message"[..];
+    assert!(!Proof::verify_aggregated(
+        &verifier_params,
+        &com,
+        &wrong_values,
+        &indices,
+        &agg
+    ));
+
+    // duplicated indices and empty sets are rejected
+    let dup_indices = vec![1usize, 4, 1];
+    let dup_values: Vec<&[u8]> = dup_indices.iter().map(|i| values[*i].as_bytes()).collect();
+    let dup_proofs = vec![proofs[0].clone(), proofs[1].clone(), proofs[0].clone()];
+    match Proof::aggregate(&com, &dup_proofs, &dup_values, &dup_indices) {
+        Err(PointproofsError::DuplicatedIndex) => (),
+        _ => panic!("expected PointproofsError::DuplicatedIndex"),
+    }
+    assert!(!Proof::verify_aggregated(
+        &verifier_params,
+        &com,
+        &dup_values,
+        &dup_indices,
+        &agg
+    ));
+    let no_values: Vec<&[u8]> = vec![];
+    match Proof::aggregate(&com, &[], &no_values, &[]) {
+        Err(PointproofsError::EmptySet) => (),
+        _ => panic!("expected PointproofsError::EmptySet"),
+    }
+    assert!(!Proof::verify_aggregated(
+        &verifier_params,
+        &com,
+        &no_values,
+        &[],
+        &agg
+    ));
+
+    // the aggregated proof is bound to the commitment
+    let values2: Vec<String> = (0..n)
+        .map(|i| format!("this is another message number {}", i))
+        .collect();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    assert!(!Proof::verify_aggregated(
+        &verifier_params,
+        &com2,
+        &sub_values,
+        &indices,
+        &agg
+    ));
+
+    // the n must be known from the commitment or the proofs
+    let com_recover = Commitment::from_bytes(&com.to_bytes()).unwrap();
+    assert!(Proof::aggregate(&com_recover, &proofs, &sub_values, &indices).is_ok());
+    let proofs_recover: Vec<Proof> = proofs
+        .iter()
+        .map(|p| Proof::from_bytes(&p.to_bytes()).unwrap())
+        .collect();
+    match Proof::aggregate(&com_recover, &proofs_recover, &sub_values, &indices) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+}
+
+#[test]
+fn test_mismatched_n_cross_commit_batch_verify() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let values2: Vec<String> = (0..n)
+        .map(|i| format!("this is another message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    let set = vec![1usize, 4, 7];
+    let proofs: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values, *i).unwrap())
+        .collect();
+    let proofs2: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values2, *i).unwrap())
+        .collect();
+    let sub_values: Vec<&String> = set.iter().map(|i| &values[*i]).collect();
+    let sub_values2: Vec<&String> = set.iter().map(|i| &values2[*i]).collect();
+
+    let coms = vec![com.clone(), com2.clone()];
+    let sets = vec![set.clone(), set.clone()];
+    let values_2d = vec![sub_values.clone(), sub_values2.clone()];
+    let agg = Proof::cross_commit_aggregate_full(&coms, &[proofs, proofs2], &sets, &values_2d, n)
+        .unwrap();
+    assert!(agg.cross_commit_batch_verify(&verifier_params, &coms, &sets, &values_2d));
+
+    // the recorded n of a commitment or the proof is not the n of the parameters
+    let mut wrong_coms = coms.clone();
+    wrong_coms[1].n = 2 * n;
+    assert!(!agg.cross_commit_batch_verify(&verifier_params, &wrong_coms, &sets, &values_2d));
+    let mut wrong_agg = agg.clone();
+    wrong_agg.n = 2 * n;
+    assert!(!wrong_agg.cross_commit_batch_verify(&verifier_params, &coms, &sets, &values_2d));
+}
+
+#[test]
+fn test_mismatched_n_cross_aggregate_verify() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let values2: Vec<String> = (0..n)
+        .map(|i| format!("this is another message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    let set = vec![1usize, 4, 7];
+    let proofs: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values, *i).unwrap())
+        .collect();
+    let proofs2: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values2, *i).unwrap())
+        .collect();
+    let sub_values: Vec<&String> = set.iter().map(|i| &values[*i]).collect();
+    let sub_values2: Vec<&String> = set.iter().map(|i| &values2[*i]).collect();
+
+    let coms = vec![com.clone(), com2.clone()];
+    let sets = vec![set.clone(), set.clone()];
+    let values_2d = vec![sub_values.clone(), sub_values2.clone()];
+    let agg =
+        Proof::cross_aggregate(&coms, &[proofs, proofs2], &sets, &values_2d, &[n, n]).unwrap();
+    let vps = [&verifier_params, &verifier_params];
+    assert!(agg.cross_aggregate_verify(&vps, &coms, &sets, &values_2d));
+
+    // the recorded n of a commitment is not the n of its parameters
+    let mut wrong_coms = coms.clone();
+    wrong_coms[0].n = 2 * n;
+    assert!(!agg.cross_aggregate_verify(&vps, &wrong_coms, &sets, &values_2d));
+    let mut wrong_agg = agg.clone();
+    wrong_agg.n = 2 * n;
+    assert!(!wrong_agg.cross_aggregate_verify(&vps, &coms, &sets, &values_2d));
+}
@@ -0,0 +1,90 @@
+use pairings::param::paramgen_from_seed;
+use pairings::{Commitment, CommitmentBuilder, PointproofsError, Proof};
+
+#[test]
+fn test_commitment_builder() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+
+    let mut builder = CommitmentBuilder::with_capacity(n);
+    assert!(builder.is_empty());
+    for (i, v) in values.iter().enumerate() {
+        // fewer than n values
+        match builder.finish(&prover_params) {
+            Err(PointproofsError::MismatchedN) => (),
+            _ => panic!("expected PointproofsError::MismatchedN"),
+        }
+        builder.push(v).unwrap();
+        assert_eq!(builder.len(), i + 1);
+    }
+    assert_eq!(builder.values().len(), n);
+
+    // more than n values
+    match builder.push("one value too many") {
+        Err(PointproofsError::TooManyValues { got, max }) => {
+            assert_eq!(got, n + 1);
+            assert_eq!(max, n);
+        }
+        _ => panic!("expected PointproofsError::TooManyValues"),
+    }
+    assert_eq!(builder.len(), n);
+
+    let com = builder.finish(&prover_params).unwrap();
+    assert_eq!(com, Commitment::new(&prover_params, &values).unwrap());
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[3], 3));
+
+    // parameters for another n
+    let (prover_params4, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 4).unwrap();
+    match builder.finish(&prover_params4) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+}
+
+#[test]
+fn test_commitment_builder_bounded() {
+    let n = 4usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut builder = CommitmentBuilder::with_capacity(n).with_max_value_len(16);
+    builder.push("short").unwrap();
+    match builder.push(vec![0u8; 17]) {
+        Err(PointproofsError::ValueTooLong { index, len, max }) => {
+            assert_eq!(index, 1);
+            assert_eq!(len, 17);
+            assert_eq!(max, 16);
+        }
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    // the rejected value is not kept
+    assert_eq!(builder.len(), 1);
+    builder.push(vec![0u8; 16]).unwrap();
+    builder.push("").unwrap();
+    builder.push("last").unwrap();
+
+    let values: Vec<Vec<u8>> = vec![b"short".to_vec(), vec![0u8; 16], vec![], b"last".to_vec()];
+    assert_eq!(
+        builder.finish(&prover_params).unwrap(),
+        Commitment::new_bounded(&prover_params, &values, 16).unwrap()
+    );
+}
+
+#[test]
+fn test_commitment_builder_huge_n() {
+    // a huge n does not reserve a huge buffer, and no parameters match it
+    let mut builder = CommitmentBuilder::with_capacity(usize::max_value());
+    builder.push("this is message number 0").unwrap();
+    assert_eq!(builder.len(), 1);
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 4).unwrap();
+    match builder.finish(&prover_params) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+}
@@ -291,3 +291,86 @@ fn test_c_api_aggregate() {
         ));
     }
 }
+
+#[test]
+fn test_c_api_ownership() {
+    let n = 16;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let mut init_values = Vec::with_capacity(n);
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        init_values.push(s.into_bytes());
+    }
+
+    let mut values: Vec<pointproofs_value> = vec![];
+    for e in init_values.iter().take(n) {
+        values.push(pointproofs_value {
+            data: e.as_ptr(),
+            len: e.len(),
+        });
+    }
+    let mut param = pointproofs_params::default();
+    let mut vp_bytes = pointproofs_vp_bytes::default();
+    let mut vp = pointproofs_vp::default();
+    let mut com = pointproofs_commitment::default();
+    let mut com_bytes = pointproofs_commitment_bytes::default();
+    let mut com_rec = pointproofs_commitment::default();
+    let mut proof = pointproofs_proof::default();
+    let mut proof_bytes = pointproofs_proof_bytes::default();
+    let mut proof_rec = pointproofs_proof::default();
+    unsafe {
+        assert!(pointproofs_paramgen(seed.as_ptr(), seed.len(), 0, n, &mut param) == 0);
+        assert!(pointproofs_commit(param.prover.clone(), values.as_ptr(), n, &mut com) == 0);
+        assert!(pointproofs_prove(param.prover.clone(), values.as_ptr(), n, 2, &mut proof) == 0);
+        pointproofs_free_prover_params(param.prover);
+
+        // the verifier only gets the serialized objects
+        assert!(pointproofs_vp_serial(param.verifier.clone(), &mut vp_bytes) == 0);
+        pointproofs_free_verifier_params(param.verifier);
+        assert!(pointproofs_commit_serial(com.clone(), &mut com_bytes) == 0);
+        pointproofs_free_commit(com);
+        assert!(pointproofs_proof_serial(proof.clone(), &mut proof_bytes) == 0);
+        pointproofs_free_proof(proof);
+
+        // deserialization borrows the bytes, which can be freed right after
+        assert!(pointproofs_vp_deserial(vp_bytes.clone(), &mut vp) == 0);
+        pointproofs_free_vp_string(vp_bytes);
+        assert!(pointproofs_commit_deserial(com_bytes.clone(), &mut com_rec) == 0);
+        assert!(pointproofs_proof_deserial(proof_bytes.clone(), &mut proof_rec) == 0);
+
+        assert!(pointproofs_verify(
+            vp.clone(),
+            com_rec.clone(),
+            proof_rec.clone(),
+            values[2].clone(),
+            2
+        ));
+        assert!(!pointproofs_verify(
+            vp.clone(),
+            com_rec.clone(),
+            proof_rec.clone(),
+            values[3].clone(),
+            2
+        ));
+
+        // truncated buffers are rejected
+        let mut com_fail = pointproofs_commitment::default();
+        let short_com = pointproofs_commitment_bytes {
+            data: com_bytes.data,
+            len: com_bytes.len - 1,
+        };
+        assert!(pointproofs_commit_deserial(short_com, &mut com_fail) == -1);
+        let mut proof_fail = pointproofs_proof::default();
+        let short_proof = pointproofs_proof_bytes {
+            data: proof_bytes.data,
+            len: proof_bytes.len - 1,
+        };
+        assert!(pointproofs_proof_deserial(short_proof, &mut proof_fail) == -1);
+
+        pointproofs_free_commit_string(com_bytes);
+        pointproofs_free_proof_string(proof_bytes);
+        pointproofs_free_verifier_params(vp);
+        pointproofs_free_commit(com_rec);
+        pointproofs_free_proof(proof_rec);
+    }
+}
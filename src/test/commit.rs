@@ -1,4 +1,7 @@
+use ff::{Field, PrimeField};
+use pairing_plus::bls12_381::FrRepr;
 use pairing_plus::CurveProjective;
+use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
 use pairings::param::paramgen_from_seed;
 use pairings::*;
 
@@ -8,7 +11,7 @@ fn negative_test_commit() {
     let (prover_params, _verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut prover_params2 = prover_params.clone();
-    prover_params2.ciphersuite = 1;
+    prover_params2.ciphersuite = 3;
 
     let mut init_values = Vec::with_capacity(n);
     for i in 0..n {
@@ -42,7 +45,7 @@ fn negative_test_commit() {
         .update(&prover_params2, 0, &values[0], &new_values[0][..].as_ref())
         .is_err());
 
-    com.ciphersuite = 1;
+    com.ciphersuite = 3;
     assert!(com
         .update(&prover_params2, 0, &values[0], &new_values[0][..].as_ref())
         .is_err());
@@ -69,7 +72,7 @@ fn negative_test_commit_batch_update() {
 
     let mut com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 3;
 
     // update values
     let mut new_init_values = Vec::with_capacity(n);
@@ -94,7 +97,7 @@ fn negative_test_commit_batch_update() {
         .is_err());
 
     let mut pp2 = prover_params.clone();
-    pp2.ciphersuite = 1;
+    pp2.ciphersuite = 3;
     assert!(com2
         .batch_update(&pp2, &indices, &value_before, &value_after)
         .is_err());
@@ -141,6 +144,26 @@ fn test_commit_edge_cases() {
         values.push(&e);
     }
 
+    // new commitment with value.len == n + 1
+    let extra = b"one value too many".to_vec();
+    let mut too_many_values = values.clone();
+    too_many_values.push(&extra);
+    for pp in [&prover_params, &pp256].iter() {
+        match Commitment::new(pp, &too_many_values) {
+            Err(PointproofsError::TooManyValues { got, max }) => {
+                assert_eq!(got, n + 1);
+                assert_eq!(max, n);
+            }
+            _ => panic!("expected PointproofsError::TooManyValues"),
+        }
+    }
+    assert_eq!(
+        Commitment::new(&prover_params, &too_many_values)
+            .unwrap_err()
+            .to_string(),
+        "More values than the parameters support: got 9, max 8"
+    );
+
     // commit update with value.len \in {0, n}
     let com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
@@ -470,3 +493,1098 @@ fn test_commit_full() {
         }
     }
 }
+
+#[cfg(feature = "parallel")]
+fn parallel_commit_helper(n: usize) {
+    use pairing_plus::serdes::SerDes;
+
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut init_values = Vec::with_capacity(n);
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        init_values.push(s.into_bytes());
+    }
+
+    let mut values: Vec<&[u8]> = Vec::with_capacity(n);
+    for e in init_values.iter().take(n) {
+        values.push(&e);
+    }
+
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    // force the parallel path regardless of n
+    let com_parallel = Commitment::new_parallel_with_threshold(&prover_params, &values, 0).unwrap();
+    assert_eq!(com, com_parallel);
+    assert_eq!(
+        com,
+        Commitment::new_parallel(&prover_params, &values).unwrap()
+    );
+
+    let mut buf: Vec<u8> = vec![];
+    let mut buf_parallel: Vec<u8> = vec![];
+    com.serialize(&mut buf, true).unwrap();
+    com_parallel.serialize(&mut buf_parallel, true).unwrap();
+    assert_eq!(buf, buf_parallel);
+
+    // the parallel path performs the same sanity checks
+    assert!(Commitment::new_parallel_with_threshold(&prover_params, &values[1..], 0).is_err());
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_commit_parallel() {
+    for n in [16usize, 1024].iter() {
+        parallel_commit_helper(*n);
+    }
+}
+
+#[test]
+#[ignore]
+#[cfg(feature = "parallel")]
+fn test_commit_parallel_slow() {
+    parallel_commit_helper(4096);
+}
+
+#[test]
+fn test_commit_precomp_window() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let new_value = "this is new message number 3".to_owned();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let mut com_updated = com.clone();
+    com_updated
+        .update(&prover_params, 3, &values[3], &new_value)
+        .unwrap();
+    let mut proof_updated = Proof::new(&prover_params, &values, 5).unwrap();
+    proof_updated
+        .update(&prover_params, 5, 3, &values[3], &new_value)
+        .unwrap();
+
+    // precomp_window(256) is the precomp_256 path
+    let mut pp256 = prover_params.clone();
+    pp256.precomp_256();
+    let mut pp_window256 = prover_params.clone();
+    pp_window256.precomp_window(256).unwrap();
+    assert_eq!(pp256, pp_window256);
+    assert_eq!(com, Commitment::new(&pp_window256, &values).unwrap());
+
+    let mut pp3 = prover_params.clone();
+    pp3.precomp_3();
+    let mut pp_window3 = prover_params.clone();
+    pp_window3.precomp_window(3).unwrap();
+    assert_eq!(pp3, pp_window3);
+
+    for window in [2, 3, 16, 100, 255, 256, 257, 1024].iter() {
+        let mut pp = prover_params.clone();
+        pp.precomp_window(*window).unwrap();
+
+        assert_eq!(com, Commitment::new(&pp, &values).unwrap());
+        let mut com2 = com.clone();
+        com2.update(&pp, 3, &values[3], &new_value).unwrap();
+        assert_eq!(com_updated, com2, "window = {}", window);
+
+        let mut proof = Proof::new(&pp, &values, 5).unwrap();
+        proof.update(&pp, 5, 3, &values[3], &new_value).unwrap();
+        assert_eq!(proof_updated, proof, "window = {}", window);
+    }
+
+    let mut pp = prover_params.clone();
+    assert!(pp.precomp_window(0).is_err());
+    assert!(pp.precomp_window(1).is_err());
+    assert!(pp.precomp_window(param::MAX_PRECOMP_WINDOW + 1).is_err());
+    assert_eq!(pp, prover_params);
+}
+
+#[test]
+fn test_commit_try_precomp_window() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    // window * 2n points
+    let point_len = std::mem::size_of::<pointproofs_groups::PointproofsG1Affine>();
+    assert_eq!(prover_params.precomp_memory_estimate(1), 2 * n * point_len);
+    assert_eq!(
+        prover_params.precomp_memory_estimate(256),
+        256 * 2 * n * point_len
+    );
+    assert_eq!(
+        prover_params.precomp_memory_estimate(usize::max_value()),
+        usize::max_value()
+    );
+
+    // within the budget, same as precomp_window
+    let estimate = prover_params.precomp_memory_estimate(256);
+    let mut pp = prover_params.clone();
+    pp.try_precomp_window(256, estimate).unwrap();
+    let mut pp256 = prover_params.clone();
+    pp256.precomp_256();
+    assert_eq!(pp, pp256);
+    assert_eq!(com, Commitment::new(&pp, &values).unwrap());
+
+    // over the budget: the parameters are unchanged
+    let mut pp = pp256.clone();
+    match pp.try_precomp_window(256, estimate - 1) {
+        Err(PointproofsError::PrecompTooLarge { estimated, max }) => {
+            assert_eq!(estimated, estimate);
+            assert_eq!(max, estimate - 1);
+        }
+        _ => panic!("expected PointproofsError::PrecompTooLarge"),
+    }
+    assert_eq!(pp, pp256);
+    // and a smaller window fits
+    pp.try_precomp_window(3, estimate - 1).unwrap();
+    let mut pp3 = prover_params.clone();
+    pp3.precomp_3();
+    assert_eq!(pp, pp3);
+
+    match pp.try_precomp_window(1, usize::max_value()) {
+        Err(PointproofsError::InvalidPrecompWindow) => (),
+        _ => panic!("expected PointproofsError::InvalidPrecompWindow"),
+    }
+}
+
+#[test]
+fn test_commit_precomp_window_from() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let n = 8usize;
+    let (prover_params, _verifier_params) = paramgen_from_seed(seed, 0, n).unwrap();
+    // parameters from the same seed share their generators
+    let (mut small, _) = paramgen_from_seed(seed, 0, 4).unwrap();
+    let (mut large, _) = paramgen_from_seed(seed, 0, 16).unwrap();
+    small.precomp_256();
+    large.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let mut fresh = prover_params.clone();
+    fresh.precomp_256();
+
+    // alpha^1..alpha^4, the placeholder, and alpha^6..alpha^8 are reused
+    let mut merged = prover_params.clone();
+    assert_eq!(merged.precomp_window_from(256, &[&small]).unwrap(), 8);
+    assert_eq!(merged, fresh);
+    assert_eq!(com, Commitment::new(&merged, &values).unwrap());
+
+    // every generator is found in the larger parameters
+    let mut merged = prover_params.clone();
+    assert_eq!(
+        merged.precomp_window_from(256, &[&small, &large]).unwrap(),
+        2 * n
+    );
+    assert_eq!(merged, fresh);
+
+    // tables with another window are rebuilt
+    let mut small3 = small.clone();
+    small3.precomp_3();
+    let mut merged = prover_params.clone();
+    assert_eq!(merged.precomp_window_from(256, &[&small3]).unwrap(), 0);
+    assert_eq!(merged, fresh);
+    let mut merged = prover_params.clone();
+    assert_eq!(merged.precomp_window_from(3, &[&small3]).unwrap(), 8);
+    let mut fresh3 = prover_params.clone();
+    fresh3.precomp_3();
+    assert_eq!(merged, fresh3);
+
+    let mut pp = prover_params.clone();
+    match pp.precomp_window_from(1, &[&small]) {
+        Err(PointproofsError::InvalidPrecompWindow) => (),
+        _ => panic!("expected PointproofsError::InvalidPrecompWindow"),
+    }
+}
+
+#[test]
+fn test_clear_caches() {
+    let n = 8usize;
+    let (prover_params, mut verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut prover_params256 = prover_params.clone();
+    prover_params256.precomp_256();
+    verifier_params.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params256, &values).unwrap();
+    let proof = Proof::new(&prover_params256, &values, 0).unwrap();
+
+    // all caches are instance-owned: clearing keeps the populated tables usable
+    ::clear_caches();
+    assert_eq!(Commitment::new(&prover_params256, &values).unwrap(), com);
+    assert_eq!(Proof::new(&prover_params256, &values, 0).unwrap(), proof);
+    assert!(proof.verify(&verifier_params, &com, &values[0], 0));
+
+    // and dropping the tables does not affect the results
+    drop(prover_params256);
+    ::clear_caches();
+    assert_eq!(Commitment::new(&prover_params, &values).unwrap(), com);
+    assert_eq!(Proof::new(&prover_params, &values, 0).unwrap(), proof);
+}
+
+#[test]
+fn test_commit_from_iter() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp256 = prover_params.clone();
+    pp256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    // values generated lazily
+    let lazy = (0..n).map(|i| format!("this is message number {}", i));
+    assert_eq!(
+        Commitment::new_from_iter(&prover_params, lazy).unwrap(),
+        com
+    );
+    assert_eq!(Commitment::new_from_iter(&pp256, &values).unwrap(), com);
+    assert_eq!(
+        Commitment::new_from_iter(&prover_params, values.iter().map(|s| s.as_bytes())).unwrap(),
+        com
+    );
+    let proof = Proof::new(&prover_params, &values, 1).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[1], 1));
+
+    // wrong number of values
+    assert!(Commitment::new_from_iter(&prover_params, values.iter().take(n - 1)).is_err());
+    assert!(Commitment::new_from_iter(&prover_params, Vec::<Vec<u8>>::new()).is_err());
+    let too_many = (0..n + 3).map(|i| format!("this is message number {}", i));
+    match Commitment::new_from_iter(&prover_params, too_many) {
+        Err(PointproofsError::TooManyValues { got, max }) => {
+            assert_eq!(got, n + 3);
+            assert_eq!(max, n);
+        }
+        _ => panic!("expected PointproofsError::TooManyValues"),
+    }
+
+    let mut prover_params2 = prover_params.clone();
+    prover_params2.ciphersuite = 3;
+    assert!(Commitment::new_from_iter(&prover_params2, &values).is_err());
+}
+
+#[test]
+fn test_commit_bounded() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let max_len = values.iter().map(|s| s.len()).max().unwrap();
+
+    // all values within the bound
+    assert_eq!(
+        Commitment::new_bounded(&prover_params, &values, max_len).unwrap(),
+        com
+    );
+    assert_eq!(
+        Commitment::new_bounded(&prover_params, &values, usize::max_value()).unwrap(),
+        com
+    );
+
+    // one oversized value
+    values[5] = "x".repeat(max_len + 1);
+    match Commitment::new_bounded(&prover_params, &values, max_len) {
+        Err(PointproofsError::ValueTooLong { index, len, max }) => {
+            assert_eq!(index, 5);
+            assert_eq!(len, max_len + 1);
+            assert_eq!(max, max_len);
+        }
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    assert!(Commitment::new(&prover_params, &values).is_ok());
+    assert!(Commitment::new_bounded(&prover_params, &values[1..], max_len + 1).is_err());
+}
+
+#[test]
+fn test_commit_batch_update_changes() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp256 = prover_params.clone();
+    pp256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    // indices that are not a prefix of 0..n, and not sorted
+    let index = vec![11usize, 2, 7, 14, 5];
+    let mut new_values = values.clone();
+    for i in index.iter() {
+        new_values[*i] = format!("this is new message number {}", i);
+    }
+    let changes: Vec<(usize, &[u8], &[u8])> = index
+        .iter()
+        .map(|i| (*i, values[*i].as_bytes(), new_values[*i].as_bytes()))
+        .collect();
+
+    // sequential updates
+    let mut expected = com.clone();
+    for (i, old, new) in changes.iter() {
+        expected.update(&prover_params, *i, old, new).unwrap();
+    }
+    assert_eq!(
+        expected,
+        Commitment::new(&prover_params, &new_values).unwrap()
+    );
+
+    for pp in [&prover_params, &pp256].iter() {
+        let mut com_changes = com.clone();
+        com_changes.batch_update_changes(pp, &changes).unwrap();
+        assert_eq!(com_changes, expected);
+
+        // the pre-computed table is indexed by the changed indices
+        let old_values: Vec<&String> = index.iter().map(|i| &values[*i]).collect();
+        let updated_values: Vec<&String> = index.iter().map(|i| &new_values[*i]).collect();
+        let mut com_batch = com.clone();
+        com_batch
+            .batch_update(pp, &index, &old_values, &updated_values)
+            .unwrap();
+        assert_eq!(com_batch, expected);
+    }
+    let proof = Proof::new(&prover_params, &new_values, 7).unwrap();
+    assert!(proof.verify(&verifier_params, &expected, &new_values[7], 7));
+
+    // two conflicting updates to the same slot
+    let mut dup_changes = changes.clone();
+    dup_changes.push((2, new_values[2].as_bytes(), &b"yet another value"[..]));
+    let mut com_dup = com.clone();
+    match com_dup.batch_update_changes(&prover_params, &dup_changes) {
+        Err(PointproofsError::DuplicatedIndex) => (),
+        _ => panic!("expected PointproofsError::DuplicatedIndex"),
+    }
+    assert_eq!(com_dup, com);
+
+    // no change
+    let mut com_none = com.clone();
+    let no_changes: Vec<(usize, &[u8], &[u8])> = vec![];
+    com_none
+        .batch_update_changes(&prover_params, &no_changes)
+        .unwrap();
+    assert_eq!(com_none, com);
+}
+
+#[test]
+fn test_commit_add() {
+    let n = 16usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut v: Vec<String> = vec![];
+    let mut w: Vec<String> = vec![];
+    for i in 0..n {
+        v.push(format!("this is message number {}", i));
+        w.push(format!("this is another message number {}", i));
+    }
+    // v + w, as the elementwise sum of the scalars
+    let sum: Vec<FrRepr> = v
+        .iter()
+        .zip(w.iter())
+        .map(|(a, b)| {
+            let mut s = hash_to_field_pointproofs(a);
+            s.add_assign(&hash_to_field_pointproofs(b));
+            s.into_repr()
+        })
+        .collect();
+
+    let com_v = Commitment::new(&prover_params, &v).unwrap();
+    let com_w = Commitment::new(&prover_params, &w).unwrap();
+    let com_sum = Commitment::new_from_repr_slice(&prover_params, &sum).unwrap();
+    assert_eq!(com_v.add(&com_w).unwrap(), com_sum);
+    assert_eq!(com_w.add(&com_v).unwrap(), com_sum);
+
+    // the commitments must share the ciphersuite
+    let mut other = com_w.clone();
+    other.ciphersuite = 3;
+    match com_v.add(&other) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+}
+
+#[test]
+fn test_commit_extend() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let n = 8usize;
+    // the same seed gives the same alpha
+    let (pp_small, _) = paramgen_from_seed(seed, 0, n).unwrap();
+    let (pp_large, vp_large) = paramgen_from_seed(seed, 0, 2 * n).unwrap();
+    let mut pp_large256 = pp_large.clone();
+    pp_large256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..2 * n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com_small = Commitment::new(&pp_small, &values[..n]).unwrap();
+    let com_large = Commitment::new(&pp_large, &values).unwrap();
+
+    for pp in [&pp_large, &pp_large256].iter() {
+        let mut com = com_small.clone();
+        com.extend(&pp_small, pp, &values[n..]).unwrap();
+        assert_eq!(com, com_large);
+    }
+    let proof = Proof::new(&pp_large, &values, n + 1).unwrap();
+    assert!(proof.verify(&vp_large, &com_large, &values[n + 1], n + 1));
+
+    // the appended values must fill the larger vector
+    let mut com = com_small.clone();
+    match com.extend(&pp_small, &pp_large, &values[n + 1..]) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    assert_eq!(com, com_small);
+
+    // parameters with another alpha are not a superset
+    let (pp_other, _) =
+        paramgen_from_seed("This is another very very very long Seed", 0, 2 * n).unwrap();
+    match com.extend(&pp_small, &pp_other, &values[n..]) {
+        Err(PointproofsError::InvalidParams) => (),
+        _ => panic!("expected PointproofsError::InvalidParams"),
+    }
+    // the parameters cannot shrink
+    match com.extend(&pp_large, &pp_small, &values[n..]) {
+        Err(PointproofsError::InvalidParams) => (),
+        _ => panic!("expected PointproofsError::InvalidParams"),
+    }
+    assert_eq!(com, com_small);
+}
+
+#[test]
+fn test_commit_verify_full() {
+    let n = 16usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    assert!(com.verify_full(&prover_params, &values));
+
+    // a single changed entry
+    for i in 0..n {
+        let mut claimed = values.clone();
+        claimed[i] = format!("this is new message number {}", i);
+        assert!(!com.verify_full(&prover_params, &claimed));
+    }
+    // swapped entries
+    let mut claimed = values.clone();
+    claimed.swap(0, 1);
+    assert!(!com.verify_full(&prover_params, &claimed));
+    // wrong number of values
+    assert!(!com.verify_full(&prover_params, &values[..n - 1]));
+    // the recorded n of the commitment is not the n of the parameters
+    let mut wrong_com = com.clone();
+    wrong_com.n = 2 * n;
+    assert!(!wrong_com.verify_full(&prover_params, &values));
+
+    // the same check, from the parameters
+    assert!(prover_params.verify_commit(&com, &values));
+    let slices: Vec<&[u8]> = values.iter().map(|v| v.as_bytes()).collect();
+    assert!(prover_params.verify_commit(&com, &slices));
+    assert!(!prover_params.verify_commit(&com, &claimed));
+    let (prover_params1, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 1, n).unwrap();
+    assert!(!prover_params1.verify_commit(&com, &values));
+}
+
+#[test]
+fn test_commit_proof_updated() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let mut new_values = values.clone();
+    new_values[3] = "this is new message number 3".to_string();
+
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 5).unwrap();
+    let com_before = com.clone();
+    let proof_before = proof.clone();
+
+    let com_new = com
+        .updated(&prover_params, 3, &values[3], &new_values[3])
+        .unwrap();
+    let proof_new = proof
+        .updated(&prover_params, 5, 3, &values[3], &new_values[3])
+        .unwrap();
+
+    // the originals are untouched
+    assert_eq!(com, com_before);
+    assert_eq!(proof, proof_before);
+    assert!(proof.verify(&verifier_params, &com, &values[5], 5));
+
+    // the results equal the in place updates, and fresh computations
+    let mut com_mut = com.clone();
+    com_mut
+        .update(&prover_params, 3, &values[3], &new_values[3])
+        .unwrap();
+    let mut proof_mut = proof.clone();
+    proof_mut
+        .update(&prover_params, 5, 3, &values[3], &new_values[3])
+        .unwrap();
+    assert_eq!(com_new, com_mut);
+    assert_eq!(proof_new, proof_mut);
+    assert_eq!(
+        com_new,
+        Commitment::new(&prover_params, &new_values).unwrap()
+    );
+    assert!(proof_new.verify(&verifier_params, &com_new, &new_values[5], 5));
+
+    // errors are passed through, and the originals are still untouched
+    assert!(com
+        .updated(&prover_params, n, &values[3], &new_values[3])
+        .is_err());
+    assert!(proof
+        .updated(&prover_params, 5, n, &values[3], &new_values[3])
+        .is_err());
+    assert_eq!(com, com_before);
+    assert_eq!(proof, proof_before);
+}
+
+#[test]
+fn test_commit_empty() {
+    for n in [1usize, 8, 33].iter() {
+        for ciphersuite in 0..3u8 {
+            let (prover_params, verifier_params) = paramgen_from_seed(
+                "This is Leo's Favourite very very very long Seed",
+                ciphersuite,
+                *n,
+            )
+            .unwrap();
+            let defaults: Vec<&[u8]> = vec![b""; *n];
+            let mut com = Commitment::empty(&prover_params).unwrap();
+            assert_eq!(com, Commitment::new(&prover_params, &defaults).unwrap());
+
+            // update from the default vector one index at a time
+            let mut values: Vec<String> = vec![String::new(); *n];
+            for i in 0..*n {
+                let value = format!("this is message number {}", i);
+                com.update(&prover_params, i, &values[i], &value).unwrap();
+                values[i] = value;
+            }
+            assert_eq!(com, Commitment::new(&prover_params, &values).unwrap());
+            let proof = Proof::new(&prover_params, &values, 0).unwrap();
+            assert!(proof.verify(&verifier_params, &com, &values[0], 0));
+        }
+    }
+
+    // parameters whose n does not match their generators
+    let (mut prover_params, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 8).unwrap();
+    prover_params.n = 4;
+    match Commitment::empty(&prover_params) {
+        Err(PointproofsError::InvalidParams) => (),
+        _ => panic!("expected PointproofsError::InvalidParams"),
+    }
+}
+
+#[test]
+fn test_commit_new_many() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut vectors: Vec<Vec<String>> = vec![];
+    for j in 0..5 {
+        vectors.push(
+            (0..n)
+                .map(|i| format!("this is message number {} of vector {}", i, j))
+                .collect(),
+        );
+    }
+    let coms = Commitment::new_many(&prover_params, &vectors).unwrap();
+    assert_eq!(coms.len(), vectors.len());
+    for (com, values) in coms.iter().zip(vectors.iter()) {
+        assert_eq!(*com, Commitment::new(&prover_params, values).unwrap());
+    }
+
+    // borrowed slices
+    let slices: Vec<Vec<&[u8]>> = vectors
+        .iter()
+        .map(|v| v.iter().map(|s| s.as_bytes()).collect())
+        .collect();
+    let slice_refs: Vec<&[&[u8]]> = slices.iter().map(|v| &v[..]).collect();
+    assert_eq!(
+        Commitment::new_many(&prover_params, &slice_refs).unwrap(),
+        coms
+    );
+    #[cfg(feature = "parallel")]
+    assert_eq!(
+        Commitment::new_many_parallel(&prover_params, &slice_refs).unwrap(),
+        coms
+    );
+
+    let empty: Vec<Vec<String>> = vec![];
+    assert!(Commitment::new_many(&prover_params, &empty)
+        .unwrap()
+        .is_empty());
+
+    // a single faulty vector fails the whole batch
+    vectors[3].pop();
+    match Commitment::new_many(&prover_params, &vectors) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    #[cfg(feature = "parallel")]
+    assert!(Commitment::new_many_parallel(&prover_params, &vectors).is_err());
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_commit_parallel_in_pool() {
+    let n = 64usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    for num_threads in [1usize, 3].iter() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(*num_threads)
+            .build()
+            .unwrap();
+        assert_eq!(
+            Commitment::new_parallel_in_pool(&prover_params, &values, &pool).unwrap(),
+            com
+        );
+        // the pool is the one in use
+        assert_eq!(pool.install(rayon::current_num_threads), *num_threads);
+
+        let vectors = vec![values.clone(), values.clone()];
+        assert_eq!(
+            Commitment::new_many_parallel_in_pool(&prover_params, &vectors, &pool).unwrap(),
+            vec![com.clone(), com.clone()]
+        );
+        assert!(Commitment::new_parallel_in_pool(&prover_params, &values[1..], &pool).is_err());
+    }
+}
+
+#[test]
+fn test_commit_value_lengths() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let long = vec![0x5au8; 1 << 16];
+    let mut long_tweaked = long.clone();
+    *long_tweaked.last_mut().unwrap() ^= 1;
+    let values: Vec<Vec<u8>> = vec![
+        vec![],
+        vec![0u8; 48],
+        vec![0xffu8; 48],
+        vec![0xffu8; 64],
+        vec![0xffu8; 1000],
+        long,
+        long_tweaked,
+        b"this is message number 7".to_vec(),
+    ];
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    for i in 0..n {
+        let proof = Proof::new(&prover_params, &values, i).unwrap();
+        assert!(proof.verify(&verifier_params, &com, &values[i], i));
+        // the long values only differ in their last byte
+        let other = if i == 5 { &values[6] } else { &values[5] };
+        assert!(!proof.verify(&verifier_params, &com, other, i));
+    }
+}
+
+#[test]
+fn test_commit_new_with_proof() {
+    let n = 8usize;
+    for ciphersuite in 0..3u8 {
+        let (prover_params, verifier_params) = paramgen_from_seed(
+            "This is Leo's Favourite very very very long Seed",
+            ciphersuite,
+            n,
+        )
+        .unwrap();
+        let values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        for i in 0..n {
+            let (com2, proof) = Commitment::new_with_proof(&prover_params, &values, i).unwrap();
+            assert_eq!(com2, com);
+            assert_eq!(proof, Proof::new(&prover_params, &values, i).unwrap());
+            assert!(proof.verify(&verifier_params, &com2, &values[i], i));
+        }
+
+        match Commitment::new_with_proof(&prover_params, &values, n) {
+            Err(PointproofsError::IndexOutOfRange) => (),
+            _ => panic!("expected PointproofsError::IndexOutOfRange"),
+        }
+        match Commitment::new_with_proof(&prover_params, &values[1..], 0) {
+            Err(PointproofsError::MismatchedN) => (),
+            _ => panic!("expected PointproofsError::MismatchedN"),
+        }
+    }
+}
+
+#[test]
+fn test_max_value_len() {
+    let n = 8usize;
+    let (mut prover_params, mut verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    assert_eq!(prover_params.max_value_len(), DEFAULT_MAX_VALUE_LEN);
+    assert_eq!(verifier_params.max_value_len(), DEFAULT_MAX_VALUE_LEN);
+
+    let mut values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+
+    // "this is message number 3" is 24 bytes long
+    prover_params.set_max_value_len(24);
+    verifier_params.set_max_value_len(24);
+    assert_eq!(Commitment::new(&prover_params, &values).unwrap(), com);
+    assert_eq!(Proof::new(&prover_params, &values, 3).unwrap(), proof);
+    assert!(proof.verify(&verifier_params, &com, &values[3], 3));
+
+    values[5] = "this is message number 55".to_string();
+    match Commitment::new(&prover_params, &values) {
+        Err(PointproofsError::ValueTooLong {
+            index: 5,
+            len: 25,
+            max: 24,
+        }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    // the bound covers every value, not only the opened one
+    match Proof::new(&prover_params, &values, 3) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+
+    verifier_params.set_max_value_len(23);
+    match proof.verify_and_open(&verifier_params, &com, &values[3], 3) {
+        Err(VerifyFailure::ValueTooLong) => (),
+        _ => panic!("expected VerifyFailure::ValueTooLong"),
+    }
+    assert!(!proof.verify(&verifier_params, &com, &values[3], 3));
+}
+
+#[test]
+fn test_max_value_len_commit_paths() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let (mut prover_params, _verifier_params) = paramgen_from_seed(seed, 0, 8).unwrap();
+    let (mut prover_params16, _verifier_params16) = paramgen_from_seed(seed, 0, 16).unwrap();
+    let values: Vec<String> = (0..16)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values[..8]).unwrap();
+
+    // "this is message number 5" is 24 bytes long, long_value is 25
+    prover_params.set_max_value_len(24);
+    prover_params16.set_max_value_len(24);
+    let long_value = "this is message number 55".to_string();
+    let mut long_values = values[..8].to_vec();
+    long_values[5] = long_value.clone();
+
+    match Commitment::new_with_proof(&prover_params, &long_values, 3) {
+        Err(PointproofsError::ValueTooLong {
+            index: 5,
+            len: 25,
+            max: 24,
+        }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match Commitment::new_from_iter(&prover_params, long_values.iter()) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match Commitment::new_chunked(&prover_params, &long_values, 3) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match Commitment::new_many(&prover_params, &[values[..8].to_vec(), long_values.clone()]) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    #[cfg(feature = "parallel")]
+    assert!(Commitment::new_parallel_with_threshold(&prover_params, &long_values, 0).is_err());
+    match prover_params.hash_values(&long_values) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+
+    // the updates bound the old and the new values, and leave self untouched
+    let mut com_mut = com.clone();
+    match com_mut.update(&prover_params, 5, &values[5], &long_value) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match com_mut.batch_update(
+        &prover_params,
+        &[2, 5],
+        &[values[2].as_str(), long_value.as_str()],
+        &[values[3].as_str(), values[5].as_str()],
+    ) {
+        Err(PointproofsError::ValueTooLong { index: 1, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    let mut appended = values[8..].to_vec();
+    appended[0] = long_value.clone();
+    match com_mut.extend(&prover_params, &prover_params16, &appended) {
+        Err(PointproofsError::ValueTooLong { index: 0, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    assert_eq!(com_mut, com);
+}
+
+#[test]
+fn test_mismatched_n() {
+    use pairing_plus::serdes::SerDes;
+
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 8).unwrap();
+    let (prover_params16, verifier_params16) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 16).unwrap();
+
+    let values: Vec<String> = (0..8)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let mut com = Commitment::new(&prover_params, &values).unwrap();
+    let mut proof = Proof::new(&prover_params, &values, 2).unwrap();
+
+    // the indices are within both n, so only the recorded n tells them apart
+    let new_value = "this is new message number 5".to_string();
+    match com.update(&prover_params16, 5, &values[5], &new_value) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    match com.batch_update(&prover_params16, &[5], &[&values[5]], &[&new_value]) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    match proof.update(&prover_params16, 2, 5, &values[5], &new_value) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    let com16 = Commitment::new(&prover_params16, &[&values[..], &values[..]].concat()).unwrap();
+    match com.add(&com16) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    match proof.verify_and_open(&verifier_params16, &com, &values[2], 2) {
+        Err(VerifyFailure::MismatchedN) => (),
+        _ => panic!("expected VerifyFailure::MismatchedN"),
+    }
+    assert!(!proof.verify(&verifier_params16, &com, &values[2], 2));
+
+    // nothing was changed by the failed updates
+    assert_eq!(com, Commitment::new(&prover_params, &values).unwrap());
+    com.update(&prover_params, 5, &values[5], &new_value)
+        .unwrap();
+    proof
+        .update(&prover_params, 2, 5, &values[5], &new_value)
+        .unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[2], 2));
+
+    // the default encoding does not carry n, and is unchanged
+    let mut buf: Vec<u8> = vec![];
+    com.serialize(&mut buf, true).unwrap();
+    assert_eq!(buf, com.to_bytes());
+    assert_eq!(buf.len(), Commitment::serialized_len(true));
+    assert_eq!(buf[0] & WITH_N_FLAG, 0);
+    let mut com_recover = Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert_eq!(com_recover, com);
+    match com_recover.to_bytes_with_n() {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        Ok(_) => panic!("expected an error for an unknown n"),
+    }
+    // the n of the unknown-n commitment is set by its first update
+    com_recover
+        .update(&prover_params, 5, &new_value, &values[5])
+        .unwrap();
+    assert_eq!(
+        com_recover,
+        Commitment::new(&prover_params, &values).unwrap()
+    );
+    match com_recover.update(&prover_params16, 5, &values[5], &new_value) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    let mut proof_recover = Proof::from_bytes(&proof.to_bytes()).unwrap();
+    proof_recover
+        .batch_update(&prover_params, 2, &[(5, &new_value, &values[5])])
+        .unwrap();
+    match proof_recover.update(&prover_params16, 2, 5, &values[5], &new_value) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+
+    // the encoding with n carries it through a round trip
+    for &compressed in [true, false].iter() {
+        let mut buf: Vec<u8> = vec![];
+        com.serialize_with_n(&mut buf, compressed).unwrap();
+        assert_eq!(buf.len(), Commitment::serialized_len(compressed) + 4);
+        assert_eq!(buf[0] & WITH_N_FLAG, WITH_N_FLAG);
+        let mut com_recover = Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(com_recover, com);
+        assert_eq!(
+            com_recover.to_bytes_with_n().unwrap(),
+            com.to_bytes_with_n().unwrap()
+        );
+        match com_recover.update(&prover_params16, 5, &new_value, &values[5]) {
+            Err(PointproofsError::MismatchedN) => (),
+            _ => panic!("expected PointproofsError::MismatchedN"),
+        }
+        let strict = Commitment::deserialize_strict(&mut buf[..].as_ref(), compressed).unwrap();
+        assert_eq!(
+            strict.to_bytes_with_n().unwrap(),
+            com.to_bytes_with_n().unwrap()
+        );
+
+        let mut buf: Vec<u8> = vec![];
+        proof.serialize_with_n(&mut buf, compressed).unwrap();
+        assert_eq!(buf.len(), Proof::serialized_len(compressed) + 4);
+        let proof_recover = Proof::deserialize(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(proof_recover, proof);
+        assert!(proof_recover.verify(&verifier_params, &com_recover, &values[2], 2));
+        assert!(!proof_recover.verify(&verifier_params16, &com_recover, &values[2], 2));
+    }
+
+    // the n in the blob is in [1, MAX_N]
+    let mut bad = com.to_bytes_with_n().unwrap();
+    bad[1..5].copy_from_slice(&0u32.to_le_bytes());
+    assert!(Commitment::from_bytes(&bad).is_err());
+    bad[1..5].copy_from_slice(&u32::max_value().to_le_bytes());
+    assert!(Commitment::from_bytes(&bad).is_err());
+}
+
+#[test]
+fn test_prehashed() {
+    let n = 8usize;
+    for ciphersuite in 0..3u8 {
+        let (prover_params, verifier_params) = paramgen_from_seed(
+            "This is Leo's Favourite very very very long Seed",
+            ciphersuite,
+            n,
+        )
+        .unwrap();
+        let values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+
+        let scalars = prover_params.hash_values(&values).unwrap();
+        assert_eq!(scalars.len(), n);
+        for (scalar, value) in scalars.iter().zip(values.iter()) {
+            assert_eq!(
+                *scalar,
+                hash_to_field_with_ciphersuite(ciphersuite, value).unwrap()
+            );
+        }
+
+        let com = Commitment::new_prehashed(&prover_params, &scalars).unwrap();
+        assert_eq!(com, Commitment::new(&prover_params, &values).unwrap());
+        for i in 0..n {
+            let proof = Proof::new_prehashed(&prover_params, &scalars, i).unwrap();
+            assert_eq!(proof, Proof::new(&prover_params, &values, i).unwrap());
+            assert!(proof.verify(&verifier_params, &com, &values[i], i));
+        }
+
+        assert!(Commitment::new_prehashed(&prover_params, &scalars[1..]).is_err());
+        assert!(Proof::new_prehashed(&prover_params, &scalars[1..], 0).is_err());
+        assert!(Proof::new_prehashed(&prover_params, &scalars, n).is_err());
+    }
+}
+
+#[test]
+fn test_zeroized_scalars() {
+    // the hashed scalars are zeroized when dropped, after they are used:
+    // every path that hashes the values still gives the same output
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    assert_eq!(
+        Commitment::new_from_iter(&prover_params, values.iter()).unwrap(),
+        com
+    );
+    let many = Commitment::new_many(&prover_params, &[&values[..], &values[..]]).unwrap();
+    assert_eq!(many, vec![com.clone(), com.clone()]);
+
+    let indices: Vec<usize> = (0..n).collect();
+    let proofs = Proof::batch_new(&prover_params, &values, &indices).unwrap();
+    assert_eq!(Proof::new_all(&prover_params, &values).unwrap(), proofs);
+    assert_eq!(
+        Proof::new_all_tuned(&prover_params, &values, 40).unwrap(),
+        proofs
+    );
+    for (i, proof) in proofs.iter().enumerate() {
+        assert_eq!(*proof, Proof::new(&prover_params, &values, i).unwrap());
+        assert!(proof.verify(&verifier_params, &com, &values[i], i));
+    }
+}
+
+#[test]
+fn test_commit_new_chunked() {
+    let n = 4096usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    for &chunk in [0, 1, 7, 1000, 4096, usize::max_value()].iter() {
+        assert_eq!(
+            Commitment::new_chunked(&prover_params, &values, chunk).unwrap(),
+            com
+        );
+    }
+
+    match Commitment::new_chunked(&prover_params, &values[1..], 1000) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+
+    let mut invalid_params = prover_params.clone();
+    invalid_params.ciphersuite = 3;
+    match Commitment::new_chunked(&invalid_params, &values, 1000) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+}
@@ -0,0 +1,171 @@
+use pairing_plus::serdes::SerDes;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_commitment_delta() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let mut new_values = values.clone();
+    let changed = [2usize, 7, 11];
+    let mut changes: Vec<(usize, String, String)> = vec![];
+    for i in changed.iter() {
+        new_values[*i] = format!("this is new message number {}", i);
+        changes.push((*i, values[*i].clone(), new_values[*i].clone()));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let new_com = Commitment::new(&prover_params, &new_values).unwrap();
+
+    let delta = com.delta(&new_com, &prover_params, &changes).unwrap();
+
+    // the delta goes over the wire
+    let mut buf: Vec<u8> = vec![];
+    delta.serialize(&mut buf, true).unwrap();
+    assert_eq!(buf.len(), 5 + 36 * changed.len());
+    let received = CommitmentDelta::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert_eq!(received, delta);
+
+    // every proof, of a changed or an unchanged value, is updated by the delta
+    for i in 0..n {
+        let mut proof = Proof::new(&prover_params, &values, i).unwrap();
+        let mut expected = proof.clone();
+        expected.batch_update(&prover_params, i, &changes).unwrap();
+        proof.apply_delta(&prover_params, i, &received).unwrap();
+        assert_eq!(proof, expected);
+        assert_eq!(proof, Proof::new(&prover_params, &new_values, i).unwrap());
+        assert!(proof.verify(&verifier_params, &new_com, &new_values[i], i));
+    }
+
+    // no change
+    let empty: Vec<(usize, String, String)> = vec![];
+    let no_delta = com.delta(&com, &prover_params, &empty).unwrap();
+    let mut proof = Proof::new(&prover_params, &values, 0).unwrap();
+    proof.apply_delta(&prover_params, 0, &no_delta).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[0], 0));
+}
+
+#[test]
+fn test_commitment_delta_errors() {
+    let n = 8usize;
+    let (prover_params, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let (prover_params1, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 1, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let mut new_values = values.clone();
+    new_values[3] = "this is new message number 3".to_string();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let new_com = Commitment::new(&prover_params, &new_values).unwrap();
+    let changes = vec![(3usize, values[3].clone(), new_values[3].clone())];
+
+    // changes that do not explain the difference
+    let wrong = vec![(4usize, values[4].clone(), new_values[3].clone())];
+    match com.delta(&new_com, &prover_params, &wrong) {
+        Err(PointproofsError::DeltaMismatch) => (),
+        _ => panic!("expected PointproofsError::DeltaMismatch"),
+    }
+    match new_com.delta(&com, &prover_params, &changes) {
+        Err(PointproofsError::DeltaMismatch) => (),
+        _ => panic!("expected PointproofsError::DeltaMismatch"),
+    }
+    let out_of_range = vec![(n, values[3].clone(), new_values[3].clone())];
+    match com.delta(&new_com, &prover_params, &out_of_range) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+    match com.delta(&new_com, &prover_params1, &changes) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+
+    // a delta for other parameters
+    let delta = com.delta(&new_com, &prover_params, &changes).unwrap();
+    let mut proof1 = Proof::new(&prover_params1, &values, 0).unwrap();
+    match proof1.apply_delta(&prover_params1, 0, &delta) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+    let (small_params, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 2).unwrap();
+    let mut proof = Proof::new(&small_params, &values[..2], 0).unwrap();
+    match proof.apply_delta(&small_params, 0, &delta) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+
+    // malformed serializations
+    let mut buf: Vec<u8> = vec![];
+    delta.serialize(&mut buf, true).unwrap();
+    assert!(CommitmentDelta::deserialize(&mut buf[..buf.len() - 1].as_ref(), true).is_err());
+    let mut bad = buf.clone();
+    bad[0] = 3;
+    assert!(CommitmentDelta::deserialize(&mut bad[..].as_ref(), true).is_err());
+    let mut bad = buf.clone();
+    bad[8] = 0xff;
+    assert!(CommitmentDelta::deserialize(&mut bad[..].as_ref(), true).is_err());
+    let mut bad = buf.clone();
+    for b in bad[9..41].iter_mut() {
+        *b = 0xff;
+    }
+    assert!(CommitmentDelta::deserialize(&mut bad[..].as_ref(), true).is_err());
+    assert!(CommitmentDelta::deserialize(&mut buf[..].as_ref(), false).is_err());
+}
+
+#[test]
+fn test_update_witness() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let mut proofs: Vec<Proof> = vec![];
+    for i in 0..n {
+        proofs.push(Proof::new(&prover_params, &values, i).unwrap());
+    }
+
+    let new_value = "this is new message number 5".to_string();
+    let (new_com, witness) = com
+        .update_with_witness(&prover_params, 5, &values[5], &new_value)
+        .unwrap();
+    assert_eq!(
+        new_com,
+        com.updated(&prover_params, 5, &values[5], &new_value)
+            .unwrap()
+    );
+
+    // the witness goes over the wire
+    let mut buf: Vec<u8> = vec![];
+    witness.serialize(&mut buf, true).unwrap();
+    assert_eq!(buf.len(), 37);
+    let received = UpdateWitness::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert_eq!(received, witness);
+    assert!(UpdateWitness::deserialize(&mut buf[..36].as_ref(), true).is_err());
+
+    values[5] = new_value;
+    for (i, proof) in proofs.iter_mut().enumerate() {
+        proof
+            .update_from_witness(&prover_params, i, &received)
+            .unwrap();
+        assert!(proof.verify(&verifier_params, &new_com, &values[i], i));
+        assert!(!proof.verify(&verifier_params, &com, &values[i], i));
+    }
+
+    match com.update_with_witness(&prover_params, n, &values[0], &values[1]) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+}
@@ -0,0 +1,238 @@
+use pairing_plus::CurveProjective;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_prove_equal() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values_a: Vec<String> = vec![];
+    let mut values_b: Vec<String> = vec![];
+    for i in 0..n {
+        values_a.push(format!("this is message number {}", i));
+        if i % 2 == 0 {
+            values_b.push(format!("this is message number {}", i));
+        } else {
+            values_b.push(format!("this is another message number {}", i));
+        }
+    }
+    let com_a = Commitment::new(&prover_params, &values_a).unwrap();
+    let com_b = Commitment::new(&prover_params, &values_b).unwrap();
+
+    for i in 0..n {
+        let res = Proof::prove_equal(&prover_params, &com_a, &values_a, &com_b, &values_b, i);
+        if i % 2 == 0 {
+            let eq_proof = res.unwrap();
+            assert!(verify_equal(&verifier_params, &com_a, &com_b, i, &eq_proof));
+            assert!(eq_proof.verify(&verifier_params, &com_a, &com_b, i));
+            // wrong index
+            assert!(!eq_proof.verify(&verifier_params, &com_a, &com_b, (i + 2) % n));
+            // wrong commitments
+            assert!(!eq_proof.verify(&verifier_params, &com_a, &com_a, i));
+            assert!(!eq_proof.verify(&verifier_params, &com_b, &com_a, i));
+        } else {
+            match res {
+                Err(PointproofsError::ValueMismatch) => (),
+                _ => panic!("expected PointproofsError::ValueMismatch"),
+            }
+            // the difference of the two proofs does not verify if the values differ
+            let mut proof_b = Proof::new(&prover_params, &values_b, i).unwrap().proof;
+            proof_b.negate();
+            let mut proof = Proof::new(&prover_params, &values_a, i).unwrap().proof;
+            proof.add_assign(&proof_b);
+            let forged = EqualityProof {
+                ciphersuite: 0,
+                proof,
+            };
+            assert!(!forged.verify(&verifier_params, &com_a, &com_b, i));
+        }
+    }
+
+    // a commitment under other parameters
+    let (prover_params1, verifier_params1) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 1, n).unwrap();
+    let com_c = Commitment::new(&prover_params1, &values_a).unwrap();
+    match Proof::prove_equal(&prover_params, &com_a, &values_a, &com_c, &values_a, 0) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+    let eq_proof =
+        Proof::prove_equal(&prover_params, &com_a, &values_a, &com_b, &values_b, 0).unwrap();
+    assert!(!eq_proof.verify(&verifier_params1, &com_a, &com_b, 0));
+    assert!(!eq_proof.verify(&verifier_params, &com_a, &com_c, 0));
+
+    // values for a different n
+    match Proof::prove_equal(
+        &prover_params,
+        &com_a,
+        &values_a,
+        &com_b,
+        &values_b[..n - 1],
+        0,
+    ) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    match Proof::prove_equal(&prover_params, &com_a, &values_a, &com_b, &values_b, n) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+}
+
+#[test]
+fn test_prove_equal_different_n() {
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 8).unwrap();
+    let (prover_params16, verifier_params16) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 16).unwrap();
+    let values: Vec<String> = (0..16)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com_a = Commitment::new(&prover_params, &values[..8]).unwrap();
+    let com_b = Commitment::new(&prover_params16, &values).unwrap();
+
+    // the commitments are for different n, under either parameters
+    match Proof::prove_equal(
+        &prover_params,
+        &com_a,
+        &values[..8],
+        &com_b,
+        &values[..8],
+        3,
+    ) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+    match Proof::prove_equal(&prover_params16, &com_a, &values, &com_b, &values, 3) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+
+    // a proof for two commitments of n = 8 does not verify
+    // once one of them is replaced by a commitment of n = 16
+    let com_a2 = com_a.clone();
+    let eq_proof = Proof::prove_equal(
+        &prover_params,
+        &com_a,
+        &values[..8],
+        &com_a2,
+        &values[..8],
+        3,
+    )
+    .unwrap();
+    assert!(eq_proof.verify(&verifier_params, &com_a, &com_a2, 3));
+    assert!(!eq_proof.verify(&verifier_params, &com_a, &com_b, 3));
+    assert!(!eq_proof.verify(&verifier_params16, &com_a, &com_b, 3));
+}
+
+#[test]
+fn test_prove_not_equal() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let forbidden = b"this is a forbidden message";
+
+    for i in 0..n {
+        // a non-matching forbidden value
+        let proof = Proof::prove_not_equal(&prover_params, &values, i, forbidden).unwrap();
+        assert!(verify_not_equal(
+            &verifier_params,
+            &com,
+            i,
+            forbidden,
+            &proof
+        ));
+        assert!(proof.verify(&verifier_params, &com, i, forbidden));
+        // the proof is bound to the forbidden value and the index
+        assert!(!proof.verify(&verifier_params, &com, i, b"another forbidden message"));
+        assert!(!proof.verify(&verifier_params, &com, (i + 1) % n, forbidden));
+        assert!(!proof.verify(&verifier_params, &com, n, forbidden));
+
+        // a matching forbidden value
+        match Proof::prove_not_equal(&prover_params, &values, i, values[i].as_bytes()) {
+            Err(PointproofsError::ForbiddenValue) => (),
+            _ => panic!("expected PointproofsError::ForbiddenValue"),
+        }
+        // nor can a proof for another value be reused for it
+        assert!(!proof.verify(&verifier_params, &com, i, values[i].as_bytes()));
+    }
+
+    // another commitment
+    let mut values2 = values.clone();
+    values2[0] = "this is another message number 0".to_string();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    let proof = Proof::prove_not_equal(&prover_params, &values, 1, forbidden).unwrap();
+    assert!(!proof.verify(&verifier_params, &com2, 1, forbidden));
+
+    // invalid inputs
+    match Proof::prove_not_equal(&prover_params, &values, n, forbidden) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+    match Proof::prove_not_equal(&prover_params, &values[1..], 0, forbidden) {
+        Err(PointproofsError::MismatchedN) => (),
+        _ => panic!("expected PointproofsError::MismatchedN"),
+    }
+
+    // the forbidden value is held to max_value_len as the values are;
+    // "this is message number 3" is 24 bytes long
+    let mut prover_params = prover_params;
+    prover_params.set_max_value_len(24);
+    match Proof::prove_not_equal(&prover_params, &values, 3, forbidden) {
+        Err(PointproofsError::ValueTooLong {
+            index: 3,
+            len: 27,
+            max: 24,
+        }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match Proof::prove_not_equal(&prover_params, &values, 3, values[3].as_bytes()) {
+        Err(PointproofsError::ForbiddenValue) => (),
+        _ => panic!("expected PointproofsError::ForbiddenValue"),
+    }
+}
+
+#[test]
+fn test_mismatched_n_equality_verify() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com_a = Commitment::new(&prover_params, &values).unwrap();
+    let com_b = com_a.clone();
+    let eq_proof = Proof::prove_equal(&prover_params, &com_a, &values, &com_b, &values, 3).unwrap();
+    assert!(eq_proof.verify(&verifier_params, &com_a, &com_b, 3));
+
+    // the recorded n of either commitment is not the n of the parameters
+    let mut wrong_com = com_a.clone();
+    wrong_com.n = 2 * n;
+    assert!(!eq_proof.verify(&verifier_params, &wrong_com, &com_b, 3));
+    assert!(!eq_proof.verify(&verifier_params, &com_a, &wrong_com, 3));
+}
+
+#[test]
+fn test_mismatched_n_not_equal_verify() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let forbidden = b"this is a forbidden message";
+    let proof = Proof::prove_not_equal(&prover_params, &values, 3, forbidden).unwrap();
+    assert!(proof.verify(&verifier_params, &com, 3, forbidden));
+
+    // the recorded n of the commitment is not the n of the parameters
+    let mut wrong_com = com.clone();
+    wrong_com.n = 2 * n;
+    assert!(!proof.verify(&verifier_params, &wrong_com, 3, forbidden));
+}
@@ -1,6 +1,9 @@
 use ff::{Field, PrimeField};
 use pairing_plus::bls12_381::*;
 use pairings::hash_to_field_pointproofs::os2ip_mod_p;
+use pairings::{
+    hash_to_field_pointproofs, hash_to_field_pointproofs_n, hash_to_field_with_ciphersuite,
+};
 
 // examples from
 // https://crypto.stackexchange.com/questions/37537/what-are-i2osp-os2ip-in-rsa-pkcs1
@@ -34,3 +37,102 @@ fn test_os2ip() {
 
     assert_eq!(Fr::from_repr(FrRepr([1, 0, 0, 0])).unwrap(), Fr::one());
 }
+
+#[test]
+fn test_hash_to_field_kat() {
+    let input = b"pointproofs hash_to_field test vector";
+    assert_eq!(
+        hash_to_field_pointproofs(&input[..]),
+        Fr::from_str(
+            "40812927897721559804451756175358863059033788862521445480246921165572421450405"
+        )
+        .unwrap()
+    );
+
+    let expected = [
+        "34440778632301784430055619859515816715661339999889527394027928572960024990901",
+        "40696630129809403810466771734370306580230354451253557682966665037117472989541",
+        "40406785139299696316507264294985117361707875513121564912804277785270586725870",
+    ];
+    let output = hash_to_field_pointproofs_n(&input[..], expected.len());
+    assert_eq!(output.len(), expected.len());
+    for (t, e) in output.iter().zip(expected.iter()) {
+        assert_eq!(*t, Fr::from_str(e).unwrap());
+    }
+    // a prefix of a longer output
+    assert_eq!(
+        hash_to_field_pointproofs_n(&input[..], 1),
+        output[..1].to_vec()
+    );
+    assert!(hash_to_field_pointproofs_n(&input[..], 0).is_empty());
+}
+
+#[test]
+fn test_hash_to_field_ciphersuites_kat() {
+    let input = b"pointproofs hash_to_field test vector";
+    let expected = [
+        // SHA-512
+        "40812927897721559804451756175358863059033788862521445480246921165572421450405",
+        // SHA3-512
+        "37861459603532990157012540002519338139393110210192812916662204728969049376260",
+        // BLAKE2b-512
+        "41129946031930567358606272662382933985205331663115808307463069592647461301579",
+    ];
+    for (csid, e) in expected.iter().enumerate() {
+        assert_eq!(
+            hash_to_field_with_ciphersuite(csid as u8, &input[..]).unwrap(),
+            Fr::from_str(e).unwrap()
+        );
+    }
+    assert_eq!(
+        hash_to_field_with_ciphersuite(0, &input[..]).unwrap(),
+        hash_to_field_pointproofs(&input[..])
+    );
+    assert!(hash_to_field_with_ciphersuite(3, &input[..]).is_err());
+}
+
+#[test]
+fn test_hash_to_field_value_lengths() {
+    // SHA-512 of the empty string, mod r
+    assert_eq!(
+        hash_to_field_pointproofs(&[] as &[u8]),
+        Fr::from_str(
+            "36837977923004598586801305053854790336569721007264186492525083490862419013340"
+        )
+        .unwrap()
+    );
+    // the largest digest is reduced below r
+    assert_eq!(
+        Fr::from_repr(os2ip_mod_p(&[0xffu8; 64])).unwrap(),
+        Fr::from_str(
+            "3294906474794265442129797520630710739278575682199800681788903916070560242796"
+        )
+        .unwrap()
+    );
+
+    let long = vec![0x5au8; 1 << 16];
+    let mut long_tweaked = long.clone();
+    *long_tweaked.last_mut().unwrap() ^= 1;
+    let long_prefix = long[..long.len() - 1].to_vec();
+    let inputs: Vec<Vec<u8>> = vec![
+        vec![],
+        vec![0u8],
+        vec![0u8; 48],
+        vec![0xffu8; 48],
+        vec![0u8; 49],
+        vec![0xffu8; 64],
+        vec![0xffu8; 1000],
+        long,
+        long_tweaked,
+        long_prefix,
+    ];
+    let outputs: Vec<Fr> = inputs.iter().map(hash_to_field_pointproofs).collect();
+    for (i, t) in outputs.iter().enumerate() {
+        assert!(!t.is_zero());
+        // the hash is deterministic
+        assert_eq!(*t, hash_to_field_pointproofs(&inputs[i]));
+        for (j, u) in outputs.iter().enumerate().skip(i + 1) {
+            assert_ne!(t, u, "inputs {} and {} collide", i, j);
+        }
+    }
+}
@@ -1,7 +1,18 @@
 mod aggregate;
+mod builder;
 mod c_api;
 mod commit;
+mod delta;
+mod equality;
 mod hashes;
+mod optional;
 mod param;
 mod prove;
+mod reveal;
+mod salted;
 mod serdes;
+mod sliding;
+mod store;
+mod tweaked;
+#[cfg(feature = "wasm")]
+mod wasm;
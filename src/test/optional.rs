@@ -0,0 +1,43 @@
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_optional_values() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let hole = 3;
+    let mut missing: Vec<Option<&[u8]>> = values.iter().map(|v| Some(v.as_bytes())).collect();
+    missing[hole] = None;
+    let mut empty = missing.clone();
+    empty[hole] = Some(&[][..]);
+
+    // a missing value is not an empty value
+    let com_missing = Commitment::new_optional(&prover_params, &missing).unwrap();
+    let com_empty = Commitment::new_optional(&prover_params, &empty).unwrap();
+    assert_ne!(com_missing, com_empty);
+    // nor a plain commitment
+    assert_ne!(
+        com_missing,
+        Commitment::new(&prover_params, &values).unwrap()
+    );
+
+    for i in 0..n {
+        let proof_missing = Proof::new_optional(&prover_params, &missing, i).unwrap();
+        assert!(proof_missing.verify_optional(&verifier_params, &com_missing, missing[i], i));
+        let proof_empty = Proof::new_optional(&prover_params, &empty, i).unwrap();
+        assert!(proof_empty.verify_optional(&verifier_params, &com_empty, empty[i], i));
+    }
+
+    // the opening at the hole tells missing and empty apart
+    let proof_missing = Proof::new_optional(&prover_params, &missing, hole).unwrap();
+    let proof_empty = Proof::new_optional(&prover_params, &empty, hole).unwrap();
+    assert!(!proof_missing.verify_optional(&verifier_params, &com_missing, Some(&[][..]), hole));
+    assert!(!proof_empty.verify_optional(&verifier_params, &com_empty, None::<&[u8]>, hole));
+    assert!(!proof_missing.verify_optional(&verifier_params, &com_empty, None::<&[u8]>, hole));
+}
@@ -1,4 +1,18 @@
-use pairings::param::paramgen_from_seed;
+use ff::Field;
+use forfix::paramgen::{CIPHERSUITE_FLAG, UNCOMPRESSED_FLAG};
+use pairing_plus::bls12_381::{Fq12, Fr, G1Affine, G2Affine};
+use pairing_plus::serdes::SerDes;
+use pairing_plus::CurveAffine;
+use pairings::param::{
+    consistent, consistent_detailed, consistent_detailed_with_rng, consistent_with_rng,
+    paramgen_from_rng, paramgen_from_seed, paramgen_from_seed_with_dst, read_param,
+    read_param_with_rng, write_verifier_param, write_verifier_param_with_rng, ConsistencyFailure,
+    ParamsHeader, PointproofsParams, MAX_N,
+};
+use pairings::{Commitment, PointproofsError, Proof, ProverParams, VerifierParams};
+use rand::prng::ChaChaRng;
+use rand::SeedableRng;
+use zeroize::Zeroize;
 
 #[test]
 fn test_param() {
@@ -76,3 +90,1035 @@ fn test_param_slow() {
         assert!(!pp2.check_parameters(&vp1));
     }
 }
+
+#[test]
+fn test_param_from_rng() {
+    let n = 16;
+    let mut rng1 = ChaChaRng::from_seed([1u8; 32]);
+    let mut rng2 = ChaChaRng::from_seed([1u8; 32]);
+    let mut rng3 = ChaChaRng::from_seed([2u8; 32]);
+
+    // same rng seed gives the same parameters
+    let (pp1, vp1) = paramgen_from_rng(&mut rng1, "", 0, n).unwrap();
+    let (pp2, vp2) = paramgen_from_rng(&mut rng2, "", 0, n).unwrap();
+    assert_eq!(pp1, pp2);
+    assert_eq!(vp1, vp2);
+    assert!(pp1.check_parameters(&vp1));
+
+    // different rng seeds, or different seeds, give different parameters
+    let (pp3, vp3) = paramgen_from_rng(&mut rng3, "", 0, n).unwrap();
+    assert!(pp3.check_parameters(&vp3));
+    assert!(!pp1.check_parameters(&vp3));
+    let mut rng1 = ChaChaRng::from_seed([1u8; 32]);
+    let (pp4, vp4) = paramgen_from_rng(&mut rng1, "some seed", 0, n).unwrap();
+    assert!(pp4.check_parameters(&vp4));
+    assert!(!pp1.check_parameters(&vp4));
+
+    assert!(paramgen_from_rng(&mut rng3, "", 3, n).is_err());
+    assert!(paramgen_from_rng(&mut rng3, "", 0, 65537).is_err());
+}
+
+#[test]
+fn test_paramgen_zero_n() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    match paramgen_from_seed(seed, 0, 0) {
+        Err(PointproofsError::InvalidN) => (),
+        _ => panic!("expected PointproofsError::InvalidN"),
+    }
+    match paramgen_from_seed_with_dst(seed, b"some tag", 0, 0) {
+        Err(PointproofsError::InvalidN) => (),
+        _ => panic!("expected PointproofsError::InvalidN"),
+    }
+    let mut rng = ChaChaRng::from_seed([1u8; 32]);
+    match paramgen_from_rng(&mut rng, seed, 0, 0) {
+        Err(PointproofsError::InvalidN) => (),
+        _ => panic!("expected PointproofsError::InvalidN"),
+    }
+    // n = 1 is the smallest valid n
+    let (pp, vp) = paramgen_from_seed(seed, 0, 1).unwrap();
+    assert!(pp.check_parameters(&vp));
+}
+
+#[test]
+fn test_param_from_seed_with_dst() {
+    let n = 16;
+    let seed = "This is Leo's Favourite very very very long Seed";
+
+    // the empty tag is not the legacy default, and it is length-prefixed
+    // too, so it is not a seed that starts with a length-prefixed tag
+    let (pp, vp) = paramgen_from_seed(seed, 0, n).unwrap();
+    let (pp0, vp0) = paramgen_from_seed_with_dst(seed, b"", 0, n).unwrap();
+    assert!(pp0.check_parameters(&vp0));
+    assert_ne!(pp, pp0);
+    assert_ne!(vp, vp0);
+    let mut prefixed = 3u64.to_be_bytes().to_vec();
+    prefixed.extend_from_slice(b"tag");
+    prefixed.extend_from_slice(seed.as_bytes());
+    let (pp6, _) = paramgen_from_seed_with_dst(&prefixed, b"", 0, n).unwrap();
+    let (pp7, _) = paramgen_from_seed_with_dst(seed, b"tag", 0, n).unwrap();
+    assert_ne!(pp6, pp7);
+
+    // different tags give different parameters
+    let (pp1, vp1) = paramgen_from_seed_with_dst(seed, b"deployment 1", 0, n).unwrap();
+    let (pp2, vp2) = paramgen_from_seed_with_dst(seed, b"deployment 2", 0, n).unwrap();
+    assert!(pp1.check_parameters(&vp1));
+    assert!(pp2.check_parameters(&vp2));
+    assert_ne!(pp1, pp);
+    assert_ne!(pp1, pp2);
+    assert!(!pp1.check_parameters(&vp2));
+    assert!(!pp.check_parameters(&vp1));
+
+    // the same tag gives the same parameters
+    let (pp3, vp3) = paramgen_from_seed_with_dst(seed, b"deployment 1", 0, n).unwrap();
+    assert_eq!(pp1, pp3);
+    assert_eq!(vp1, vp3);
+
+    // moving bytes between the tag and the seed changes the parameters
+    let (pp4, _) = paramgen_from_seed_with_dst(&seed[1..], &seed.as_bytes()[..1], 0, n).unwrap();
+    let (pp5, _) = paramgen_from_seed_with_dst(&seed[2..], &seed.as_bytes()[..2], 0, n).unwrap();
+    assert_ne!(pp4, pp5);
+
+    assert!(paramgen_from_seed_with_dst("too short", b"tag", 0, n).is_err());
+    assert!(paramgen_from_seed_with_dst(seed, b"tag", 3, n).is_err());
+}
+
+#[test]
+fn test_consistent_with_rng() {
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    let (pp, vp) = read_param_with_rng(&mut f, &mut rng).unwrap();
+    assert!(pp.check_parameters(&vp));
+
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let mut params = PointproofsParams::deserialize(&mut f, true).unwrap();
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    assert!(consistent_with_rng(&params, &mut rng));
+    assert!(consistent(&params));
+
+    // swap two powers of alpha
+    params.g1_alpha_1_to_n.swap(1, 2);
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    assert!(!consistent_with_rng(&params, &mut rng));
+    assert!(!consistent(&params));
+}
+
+#[test]
+fn test_to_kzg_srs() {
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let params = PointproofsParams::deserialize(&mut f, true).unwrap();
+    let n = params.n;
+
+    let srs = params.to_kzg_srs();
+    assert_eq!(srs.g1_powers.len(), n + 1);
+    assert_eq!(srs.g2_powers.len(), n + 1);
+    assert_eq!(srs.g1_powers[0], G1Affine::one());
+    assert_eq!(srs.g2_powers[0], G2Affine::one());
+    assert_eq!(srs.g1_powers[1..], params.g1_alpha_1_to_n[..]);
+    assert_eq!(srs.g2_powers[1..], params.g2_alpha_1_to_n[..]);
+
+    for degree in 0..=n {
+        let srs_d = params.to_kzg_srs_with_degree(degree).unwrap();
+        assert_eq!(srs_d.g1_powers[..], srs.g1_powers[..=degree]);
+        assert_eq!(srs_d.g2_powers[..], srs.g2_powers[..=degree]);
+    }
+
+    // alpha^{n+1} is the hole
+    assert!(params.to_kzg_srs_with_degree(n + 1).is_err());
+    assert!(params.to_kzg_srs_with_degree(2 * n).is_err());
+}
+
+#[test]
+fn test_param_error_kinds() {
+    match paramgen_from_seed("seed too short", 0, 16) {
+        Err(PointproofsError::SeedTooShort) => (),
+        _ => panic!("expected PointproofsError::SeedTooShort"),
+    }
+    match paramgen_from_seed("This is Leo's Favourite very very very long Seed", 3, 16) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+    match paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 65537) {
+        Err(PointproofsError::NTooLarge) => (),
+        _ => panic!("expected PointproofsError::NTooLarge"),
+    }
+
+    // truncated params are reported as (de)serialization errors
+    let mut buf = std::fs::read("crs.param").unwrap();
+    buf.truncate(100);
+    match read_param(&mut buf[..].as_ref()) {
+        Err(PointproofsError::Serde(e)) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof)
+        }
+        _ => panic!("expected PointproofsError::Serde"),
+    }
+    let e: PointproofsError = std::io::Error::new(std::io::ErrorKind::InvalidData, "oops").into();
+    assert_eq!(e.to_string(), "Serialization failed: oops");
+    assert_eq!(
+        PointproofsError::IndexOutOfRange.to_string(),
+        "Invalid index"
+    );
+    // a mismatched n has its own message
+    assert_eq!(
+        PointproofsError::MismatchedN.to_string(),
+        "The n of the commitment, proof or values does not match the parameters"
+    );
+    assert_eq!(
+        pairings::VerifyFailure::MismatchedN.to_string(),
+        PointproofsError::MismatchedN.to_string()
+    );
+}
+
+#[test]
+fn test_consistent_detailed() {
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let params = PointproofsParams::deserialize(&mut f, true).unwrap();
+    assert_eq!(consistent_detailed(&params), Ok(()));
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    assert_eq!(consistent_detailed_with_rng(&params, &mut rng), Ok(()));
+
+    let read = || {
+        let mut f = std::fs::File::open("crs.param").unwrap();
+        PointproofsParams::deserialize(&mut f, true).unwrap()
+    };
+
+    // identity/generator pre-checks report the offending index
+    let mut p = read();
+    p.g1_alpha_1_to_n[3] = G1Affine::one();
+    assert_eq!(
+        consistent_detailed(&p),
+        Err(ConsistencyFailure::G1Alpha1ToN(3))
+    );
+    assert!(!consistent(&p));
+    let mut p = read();
+    p.g1_alpha_nplus2_to_2n[2] = G1Affine::zero();
+    assert_eq!(
+        consistent_detailed(&p),
+        Err(ConsistencyFailure::G1AlphaNplus2To2N(2))
+    );
+    let mut p = read();
+    p.g2_alpha_1_to_n[0] = G2Affine::zero();
+    assert_eq!(
+        consistent_detailed(&p),
+        Err(ConsistencyFailure::G2Alpha1ToN(0))
+    );
+    let mut p = read();
+    p.g2_alpha_nplus2_to_2n[1] = G2Affine::one();
+    assert_eq!(
+        consistent_detailed(&p),
+        Err(ConsistencyFailure::G2AlphaNplus2To2N(1))
+    );
+
+    // g1 and g2 powers differ
+    let mut p = read();
+    p.g1_alpha_1_to_n.swap(1, 2);
+    assert_eq!(consistent_detailed(&p), Err(ConsistencyFailure::G1G2Powers));
+
+    // g1 and g2 agree, but are not successive powers
+    let mut p = read();
+    p.g1_alpha_1_to_n[2] = p.g1_alpha_1_to_n[1];
+    p.g2_alpha_1_to_n[2] = p.g2_alpha_1_to_n[1];
+    assert_eq!(consistent_detailed(&p), Err(ConsistencyFailure::AlphaShift));
+
+    // wrong gt
+    let mut p = read();
+    let gt = p.gt_alpha_nplus1;
+    p.gt_alpha_nplus1.mul_assign(&gt);
+    assert_eq!(
+        consistent_detailed(&p),
+        Err(ConsistencyFailure::GtAlphaNplus1)
+    );
+
+    // wrong powers N+2..2N
+    let mut p = read();
+    p.g1_alpha_nplus2_to_2n.swap(0, 1);
+    assert_eq!(
+        consistent_detailed(&p),
+        Err(ConsistencyFailure::AlphaNplus2To2N)
+    );
+}
+
+#[test]
+fn test_param_deserialize_from_slice() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert_eq!(
+        PointproofsParams::deserialize_from_slice(&buf).unwrap(),
+        param
+    );
+
+    // trailing bytes
+    let mut long_buf = buf.clone();
+    long_buf.push(0);
+    assert!(PointproofsParams::deserialize_from_slice(&long_buf).is_err());
+
+    // a 12-byte blob claiming n = 65535 fails before reading any point
+    let mut header = vec![0u8; 12];
+    header[..4].copy_from_slice(&65535u32.to_le_bytes());
+    match PointproofsParams::deserialize_from_slice(&header) {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+        _ => panic!("expected an error"),
+    }
+    assert!(PointproofsParams::deserialize(&mut header[..].as_ref(), true).is_err());
+
+    // n above the bound is rejected in both directions
+    header[..4].copy_from_slice(&((MAX_N + 1) as u32).to_le_bytes());
+    assert!(PointproofsParams::deserialize_from_slice(&header).is_err());
+    assert!(PointproofsParams::deserialize(&mut header[..].as_ref(), true).is_err());
+    let too_large = PointproofsParams {
+        ciphersuite: 0,
+        n: MAX_N + 1,
+        g1_alpha_1_to_n: vec![],
+        g1_alpha_nplus2_to_2n: vec![],
+        g2_alpha_1_to_n: vec![],
+        g2_alpha_nplus2_to_2n: vec![],
+        gt_alpha_nplus1: param.gt_alpha_nplus1,
+    };
+    let mut out: Vec<u8> = vec![];
+    assert!(too_large.serialize(&mut out, true).is_err());
+}
+
+#[test]
+fn test_paramgen_zeroize() {
+    // alpha and its powers are Fr, which can be wiped
+    fn assert_zeroize<T: Zeroize>() {}
+    assert_zeroize::<Fr>();
+
+    // wiping the secrets does not change the public parameters
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let (pp1, vp1) = paramgen_from_seed(seed, 0, 8).unwrap();
+    let (pp2, vp2) = paramgen_from_seed(seed, 0, 8).unwrap();
+    assert_eq!(pp1, pp2);
+    assert_eq!(vp1, vp2);
+    assert!(pp1.check_parameters(&vp1));
+
+    let (pp3, vp3) = paramgen_from_rng(&mut ChaChaRng::from_seed([1u8; 32]), seed, 0, 8).unwrap();
+    let (pp4, vp4) = paramgen_from_rng(&mut ChaChaRng::from_seed([1u8; 32]), seed, 0, 8).unwrap();
+    assert_eq!(pp3, pp4);
+    assert_eq!(vp3, vp4);
+    assert!(pp3.check_parameters(&vp3));
+}
+
+#[test]
+fn test_param_deserialize_single_read() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    assert_eq!(
+        PointproofsParams::deserialize_single_read(&mut f).unwrap(),
+        param
+    );
+    // the reader is left right after the params
+    let mut long_buf = buf.clone();
+    long_buf.push(42);
+    let mut reader = &long_buf[..];
+    assert_eq!(
+        PointproofsParams::deserialize_single_read(&mut reader).unwrap(),
+        param
+    );
+    assert_eq!(reader, &[42u8][..]);
+
+    // truncated params
+    for len in [2, 12, 100, buf.len() - 1].iter() {
+        assert!(PointproofsParams::deserialize_single_read(&mut buf[..*len].as_ref()).is_err());
+    }
+}
+
+#[test]
+fn test_param_deserialize_parts() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    let header = PointproofsParams::peek_header(&mut buf[..].as_ref()).unwrap();
+    let n = header.n;
+
+    // the header and each of the five sections
+    let ends = [
+        header.header_len() + n * 48,
+        header.header_len() + (2 * n - 1) * 48,
+        header.header_len() + (2 * n - 1) * 48 + n * 96,
+        header.header_len() + (2 * n - 1) * (48 + 96),
+        buf.len(),
+    ];
+    let mut sections: Vec<&[u8]> = vec![];
+    let mut start = 0;
+    for end in ends.iter() {
+        sections.push(&buf[start..*end]);
+        start = *end;
+    }
+    assert_eq!(
+        PointproofsParams::deserialize_parts(&mut sections.clone()).unwrap(),
+        param
+    );
+
+    // the G1 and G2 blocks, as two shards; or shards of arbitrary sizes
+    let mut shards: Vec<&[u8]> = vec![&buf[..ends[1]], &buf[ends[1]..]];
+    assert_eq!(
+        PointproofsParams::deserialize_parts(&mut shards).unwrap(),
+        param
+    );
+    let mut shards: Vec<&[u8]> = buf.chunks(1000).collect();
+    shards.insert(2, &[]);
+    assert_eq!(
+        PointproofsParams::deserialize_parts(&mut shards).unwrap(),
+        param
+    );
+    let mut files = vec![std::fs::File::open("crs.param").unwrap()];
+    assert_eq!(
+        PointproofsParams::deserialize_parts(&mut files).unwrap(),
+        param
+    );
+
+    // missing, swapped or extra sections
+    let mut parts: Vec<&[u8]> = sections[..4].to_vec();
+    assert!(PointproofsParams::deserialize_parts(&mut parts).is_err());
+    let mut parts: Vec<&[u8]> = vec![
+        sections[0],
+        sections[1],
+        sections[3],
+        sections[2],
+        sections[4],
+    ];
+    assert!(PointproofsParams::deserialize_parts(&mut parts).is_err());
+    let mut parts: Vec<&[u8]> = sections.clone();
+    parts.push(&[0u8]);
+    assert!(PointproofsParams::deserialize_parts(&mut parts).is_err());
+
+    // well-formed sections that do not fit together
+    let mut gt = param.gt_alpha_nplus1;
+    gt.square();
+    let mut gt_bytes: Vec<u8> = vec![];
+    gt.serialize(&mut gt_bytes, true).unwrap();
+    let mut parts: Vec<&[u8]> = sections[..4].to_vec();
+    parts.push(&gt_bytes);
+    assert!(PointproofsParams::deserialize_parts(&mut parts).is_err());
+}
+
+#[test]
+fn test_contribution_chain() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param0 = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+
+    // a chain of 3 contributors
+    let mut chain = vec![param0];
+    let mut proofs = vec![];
+    for i in 0..3u8 {
+        let mut rng = ChaChaRng::from_seed([i + 1; 32]);
+        let (next, proof) = PointproofsParams::contribute(&chain[i as usize], &mut rng).unwrap();
+        assert!(PointproofsParams::verify_contribution(
+            &chain[i as usize],
+            &next,
+            &proof
+        ));
+        assert_ne!(next, chain[i as usize]);
+        chain.push(next);
+        proofs.push(proof);
+    }
+    assert!(consistent(&chain[3]));
+
+    // the final params can be used
+    let mut out: Vec<u8> = vec![];
+    chain[3].serialize(&mut out, true).unwrap();
+    let (pp, vp) = read_param(&mut out[..].as_ref()).unwrap();
+    let values: Vec<String> = (0..8)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&pp, &values).unwrap();
+    let proof = Proof::new(&pp, &values, 5).unwrap();
+    assert!(proof.verify(&vp, &com, &values[5], 5));
+
+    // proofs do not move to other links of the chain
+    assert!(!PointproofsParams::verify_contribution(
+        &chain[0], &chain[2], &proofs[1]
+    ));
+    assert!(!PointproofsParams::verify_contribution(
+        &chain[1], &chain[2], &proofs[0]
+    ));
+    // a tampered response
+    let mut bad_proof = proofs[1].clone();
+    bad_proof.pok_response.add_assign(&Fr::one());
+    assert!(!PointproofsParams::verify_contribution(
+        &chain[1], &chain[2], &bad_proof
+    ));
+    // new params that are not consistent
+    let mut bad_param = PointproofsParams::deserialize(&mut out[..].as_ref(), true).unwrap();
+    bad_param.g1_alpha_nplus2_to_2n.swap(0, 1);
+    assert!(!PointproofsParams::verify_contribution(
+        &chain[2], &bad_param, &proofs[2]
+    ));
+}
+
+#[test]
+fn test_is_truncation_of() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let master = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    let n = master.n;
+    assert_eq!(n, 8);
+    assert!(master.is_truncation_of(&master));
+
+    // truncate to n = 3: the exponents 1..=3 and 5..=6 are all below master's hole
+    let truncate = |params: &PointproofsParams, m: usize| PointproofsParams {
+        ciphersuite: params.ciphersuite,
+        n: m,
+        g1_alpha_1_to_n: params.g1_alpha_1_to_n[..m].to_vec(),
+        g1_alpha_nplus2_to_2n: params.g1_alpha_1_to_n[m + 1..2 * m].to_vec(),
+        g2_alpha_1_to_n: params.g2_alpha_1_to_n[..m].to_vec(),
+        g2_alpha_nplus2_to_2n: params.g2_alpha_1_to_n[m + 1..2 * m].to_vec(),
+        gt_alpha_nplus1: params.g1_alpha_1_to_n[m].pairing_with(&G2Affine::one()),
+    };
+    let small = truncate(&master, 3);
+    assert!(consistent(&small));
+    assert!(small.is_truncation_of(&master));
+    assert!(!master.is_truncation_of(&small));
+
+    // same n, different alpha
+    let mut rng = ChaChaRng::from_seed([7; 32]);
+    let (other, _) = PointproofsParams::contribute(&master, &mut rng).unwrap();
+    assert_eq!(other.n, n);
+    assert!(consistent(&other));
+    assert!(!other.is_truncation_of(&master));
+    assert!(!master.is_truncation_of(&other));
+    assert!(!truncate(&other, 3).is_truncation_of(&master));
+
+    // a single wrong point or a wrong hole
+    let mut bad = truncate(&master, 3);
+    bad.g2_alpha_nplus2_to_2n.swap(0, 1);
+    assert!(!bad.is_truncation_of(&master));
+    let mut bad = truncate(&master, 3);
+    bad.gt_alpha_nplus1 = master.gt_alpha_nplus1;
+    assert!(!bad.is_truncation_of(&master));
+
+    // the same points for another ciphersuite
+    let mut other_suite = truncate(&master, 3);
+    other_suite.ciphersuite = 1;
+    assert!(!other_suite.is_truncation_of(&master));
+}
+
+#[test]
+fn test_param_accessors() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    for csid in 0..3u8 {
+        let n = 4 + csid as usize;
+        let (pp, vp) = paramgen_from_seed(seed, csid, n).unwrap();
+        assert_eq!(pp.n(), n);
+        assert_eq!(vp.n(), n);
+        assert_eq!(pp.ciphersuite(), csid);
+        assert_eq!(vp.ciphersuite(), csid);
+
+        let values: Vec<String> = (0..pp.n())
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&pp, &values).unwrap();
+        let proof = Proof::new(&pp, &values, n - 1).unwrap();
+        assert_eq!(com.ciphersuite(), vp.ciphersuite());
+        assert_eq!(proof.ciphersuite(), vp.ciphersuite());
+
+        // the accessors survive serialization
+        let (pp2, vp2) = {
+            let mut buf: Vec<u8> = vec![];
+            pp.serialize(&mut buf, true).unwrap();
+            vp.serialize(&mut buf, true).unwrap();
+            let mut reader = &buf[..];
+            (
+                ProverParams::deserialize(&mut reader, true).unwrap(),
+                VerifierParams::deserialize(&mut reader, true).unwrap(),
+            )
+        };
+        assert_eq!(pp2.n(), n);
+        assert_eq!(vp2.ciphersuite(), csid);
+    }
+}
+
+#[test]
+fn test_param_peek_header() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+
+    let mut reader = &buf[..];
+    let header = PointproofsParams::peek_header(&mut reader).unwrap();
+    assert_eq!(
+        header,
+        ParamsHeader {
+            n: 8,
+            ciphersuite: None,
+            compressed: true,
+        }
+    );
+    assert_eq!(header.n, param.n);
+    // only the header is consumed
+    assert_eq!(reader.len(), buf.len() - 4);
+
+    // the full parse continues from there
+    assert_eq!(
+        PointproofsParams::deserialize_after_header(&header, &mut reader).unwrap(),
+        param
+    );
+    assert!(reader.is_empty());
+
+    // invalid headers
+    assert!(PointproofsParams::peek_header(&mut buf[..3].as_ref()).is_err());
+    assert!(PointproofsParams::peek_header(&mut [0u8; 4][..].as_ref()).is_err());
+    let too_large = ((MAX_N + 1) as u32).to_le_bytes();
+    assert!(PointproofsParams::peek_header(&mut too_large[..].as_ref()).is_err());
+    // a header claiming more points than the input holds
+    let mut reader = &buf[..];
+    PointproofsParams::peek_header(&mut reader).unwrap();
+    let large = ParamsHeader {
+        n: 16,
+        ciphersuite: None,
+        compressed: true,
+    };
+    assert!(PointproofsParams::deserialize_after_header(&large, &mut reader).is_err());
+}
+
+#[test]
+fn test_param_uncompressed() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+
+    let mut compressed: Vec<u8> = vec![];
+    param.serialize(&mut compressed, true).unwrap();
+    assert_eq!(compressed, buf);
+    let mut uncompressed: Vec<u8> = vec![];
+    param.serialize(&mut uncompressed, false).unwrap();
+    assert_eq!(uncompressed.len(), 4 + (2 * 8 - 1) * (96 + 192) + 576);
+
+    let header = PointproofsParams::peek_header(&mut uncompressed[..].as_ref()).unwrap();
+    assert_eq!(
+        header,
+        ParamsHeader {
+            n: 8,
+            ciphersuite: None,
+            compressed: false,
+        }
+    );
+    assert_eq!(header.body_len(), uncompressed.len() - 4);
+
+    // the header picks the encoding, whatever the flag of the reader
+    for flag in [true, false].iter() {
+        assert_eq!(
+            PointproofsParams::deserialize(&mut uncompressed[..].as_ref(), *flag).unwrap(),
+            param
+        );
+        assert_eq!(
+            PointproofsParams::deserialize(&mut compressed[..].as_ref(), *flag).unwrap(),
+            param
+        );
+    }
+    assert_eq!(
+        PointproofsParams::deserialize_from_slice(&uncompressed).unwrap(),
+        param
+    );
+    assert_eq!(
+        PointproofsParams::deserialize_single_read(&mut uncompressed[..].as_ref()).unwrap(),
+        param
+    );
+
+    // truncated, or with the flag flipped
+    let len = uncompressed.len();
+    assert!(PointproofsParams::deserialize_from_slice(&uncompressed[..len - 1]).is_err());
+    assert!(
+        PointproofsParams::deserialize_single_read(&mut uncompressed[..len - 1].as_ref()).is_err()
+    );
+    let mut flipped = uncompressed.clone();
+    flipped[3] &= 0x7f;
+    assert!(PointproofsParams::deserialize_from_slice(&flipped).is_err());
+    let mut flipped = compressed.clone();
+    flipped[3] |= 0x80;
+    assert!(PointproofsParams::deserialize_from_slice(&flipped).is_err());
+    let zero = UNCOMPRESSED_FLAG.to_le_bytes();
+    assert!(PointproofsParams::peek_header(&mut zero[..].as_ref()).is_err());
+}
+
+#[test]
+fn test_param_debug_redacted() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    let short = format!("{:?}", param);
+    assert!(short.starts_with("PointproofsParams { n: 8, digest: "));
+    assert!(short.len() < 100);
+    assert!(param.debug_full().len() > 50 * short.len());
+    assert!(param.debug_full().contains("g2_alpha_nplus2_to_2n"));
+
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 8).unwrap();
+    let (prover_params1, verifier_params1) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 1, 8).unwrap();
+    let pp = format!("{:?}", prover_params);
+    let vp = format!("{:?}", verifier_params);
+    assert!(pp.starts_with("ProverParams { ciphersuite: 0, n: 8, precomp: false, digest: "));
+    assert!(vp.starts_with(
+        "VerifierParams { ciphersuite: 0, n: 8, precomp: false, prepared: false, digest: "
+    ));
+    assert!(pp.len() < 100 && vp.len() < 120);
+    assert!(prover_params.debug_full().len() > 10 * pp.len());
+    assert!(verifier_params.debug_full().len() > 10 * vp.len());
+
+    // the digest tells parameters apart, and ignores pre-computation
+    let digest = |s: &str| s.rsplit(' ').nth(1).unwrap().to_string();
+    assert_ne!(digest(&pp), digest(&format!("{:?}", prover_params1)));
+    assert_ne!(digest(&vp), digest(&format!("{:?}", verifier_params1)));
+    let mut pp3 = prover_params.clone();
+    pp3.precomp_3();
+    let pp3 = format!("{:?}", pp3);
+    assert!(pp3.contains("precomp: true"));
+    assert_eq!(digest(&pp), digest(&pp3));
+    let mut vp3 = verifier_params.clone();
+    vp3.precomp_3();
+    assert_eq!(digest(&vp), digest(&format!("{:?}", vp3)));
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_consistent_parallel() {
+    use pairings::param::{consistent_detailed_parallel_with_rng, consistent_parallel};
+
+    let read = || {
+        let mut f = std::fs::File::open("crs.param").unwrap();
+        PointproofsParams::deserialize(&mut f, true).unwrap()
+    };
+    let mut corrupted: Vec<PointproofsParams> = vec![read()];
+    let mut p = read();
+    p.g1_alpha_1_to_n[3] = G1Affine::one();
+    corrupted.push(p);
+    let mut p = read();
+    p.g2_alpha_nplus2_to_2n[1] = G2Affine::zero();
+    corrupted.push(p);
+    let mut p = read();
+    p.g1_alpha_1_to_n.swap(1, 2);
+    corrupted.push(p);
+    let mut p = read();
+    p.g1_alpha_1_to_n[2] = p.g1_alpha_1_to_n[1];
+    p.g2_alpha_1_to_n[2] = p.g2_alpha_1_to_n[1];
+    corrupted.push(p);
+    let mut p = read();
+    let gt = p.gt_alpha_nplus1;
+    p.gt_alpha_nplus1.mul_assign(&gt);
+    corrupted.push(p);
+    let mut p = read();
+    p.g2_alpha_nplus2_to_2n.swap(0, 1);
+    corrupted.push(p);
+
+    // the first one is valid
+    for (i, p) in corrupted.iter().enumerate() {
+        let mut rng = ChaChaRng::from_seed([0u8; 32]);
+        let serial = consistent_detailed_with_rng(p, &mut rng);
+        let mut rng = ChaChaRng::from_seed([0u8; 32]);
+        let parallel = consistent_detailed_parallel_with_rng(p, &mut rng);
+        assert_eq!(serial, parallel);
+        assert_eq!(serial.is_ok(), i == 0);
+        assert_eq!(consistent_parallel(p), i == 0);
+    }
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn test_consistent_parallel_in_pool() {
+    use pairings::param::{
+        consistent_detailed_parallel_with_rng_in_pool, consistent_parallel_in_pool,
+    };
+
+    let mut f = std::fs::File::open("crs.param").unwrap();
+    let params = PointproofsParams::deserialize(&mut f, true).unwrap();
+    let mut bad =
+        PointproofsParams::deserialize(&mut std::fs::File::open("crs.param").unwrap(), true)
+            .unwrap();
+    bad.g1_alpha_1_to_n.swap(1, 2);
+
+    for num_threads in [1usize, 2].iter() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(*num_threads)
+            .build()
+            .unwrap();
+        assert!(consistent_parallel_in_pool(&params, &pool));
+        assert!(!consistent_parallel_in_pool(&bad, &pool));
+        let mut rng = ChaChaRng::from_seed([0u8; 32]);
+        assert_eq!(
+            consistent_detailed_parallel_with_rng_in_pool(&bad, &mut rng, &pool),
+            Err(ConsistencyFailure::G1G2Powers)
+        );
+    }
+}
+
+// the points are swapped between the groups with group_switched
+#[cfg(not(feature = "group_switched"))]
+#[test]
+fn test_paramgen_from_seed_known_answer() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    // (ciphersuite, n, SHA-256 of the prover params, SHA-256 of the verifier params)
+    let expected = [
+        (
+            0u8,
+            1usize,
+            "51e8e931c742c4bf201c57b3cbceacf983bc2d97de00590cd3b6abfc9278c021",
+            "d1c014ec56cac3e54eeedef4e95caea442a37fdf26bd587e1c66e29e6bab3bc9",
+        ),
+        (
+            1,
+            1,
+            "6db63456be6bea88b973e31ed495c10426a28ba3f1fb147f61b62c59e259b8fa",
+            "b67d3a5ae77a0559d1cce092e70413f1ad35484de5b2bdd9ac850caa1ad2e01e",
+        ),
+        (
+            0,
+            4,
+            "9bb5409cb2d0b9faa6d18b025467978998e5ccad291e088bb85c27772e021fe5",
+            "9fc5ed70173dcd43eff389202fabf2e3f8aea363c24f9b9172dc3ca3e97f9004",
+        ),
+        (
+            1,
+            4,
+            "953c60c69492ec3cb2474d89258d8c5546814dfeee5cde6431199de17b7d15cf",
+            "acc8ffa6890659d53d165cb776792b257aa1d29b9b442a68400dadfc63dffc34",
+        ),
+        (
+            0,
+            8,
+            "b8926e7ee164bd608edc800b95607f41b71dd8ddca57161df6a79bc796af27ee",
+            "3224933372f5eb795ea0b6975210edd9d2e179a3e9314703a91f35b0b2311f86",
+        ),
+        (
+            1,
+            8,
+            "f9da8f00a691397866a29e185f0648de56909f350e5425b4cf5aa3b51fb48c4a",
+            "9d1f02f2ac44af64bb421e4cb06904148b4d80dbae80ed7d0c7d95de06d5ac5e",
+        ),
+    ];
+    let to_hex =
+        |digest: [u8; 32]| -> String { digest.iter().map(|b| format!("{:02x}", b)).collect() };
+    for (ciphersuite, n, pp_digest, vp_digest) in expected.iter() {
+        let (pp, vp) = paramgen_from_seed(seed, *ciphersuite, *n).unwrap();
+        assert_eq!(to_hex(pp.fingerprint()), *pp_digest);
+        assert_eq!(to_hex(vp.fingerprint()), *vp_digest);
+
+        // the fingerprint is the SHA-256 of the serialization
+        // prover: |csid | n | 2n G1 points | pp_len = 0 |
+        // verifier: |csid | n | n G2 points | pp_len = 0 | gt |
+        let mut pp_bytes: Vec<u8> = vec![];
+        pp.serialize(&mut pp_bytes, true).unwrap();
+        assert_eq!(pp_bytes.len(), 5 + 2 * n * 48 + 4);
+        assert_eq!(vp.to_bytes().len(), 5 + n * 96 + 4 + 576);
+    }
+}
+
+#[test]
+fn test_param_serialized_len() {
+    let buf = std::fs::read("crs.param").unwrap();
+    assert_eq!(
+        PointproofsParams::serialized_len(8, 0, true),
+        Some(buf.len())
+    );
+
+    // any points will do, the serialization does not check consistency
+    for n in [1usize, 2, 8, 33].iter() {
+        for ciphersuite in [0u8, 1, 2].iter() {
+            let param = PointproofsParams {
+                ciphersuite: *ciphersuite,
+                n: *n,
+                g1_alpha_1_to_n: vec![G1Affine::one(); *n],
+                g1_alpha_nplus2_to_2n: vec![G1Affine::one(); *n - 1],
+                g2_alpha_1_to_n: vec![G2Affine::one(); *n],
+                g2_alpha_nplus2_to_2n: vec![G2Affine::one(); *n - 1],
+                gt_alpha_nplus1: Fq12::one(),
+            };
+            for compressed in [true, false].iter() {
+                let mut buf: Vec<u8> = vec![];
+                param.serialize(&mut buf, *compressed).unwrap();
+                assert_eq!(
+                    PointproofsParams::serialized_len(*n, *ciphersuite, *compressed),
+                    Some(buf.len())
+                );
+                let header = PointproofsParams::peek_header(&mut buf[..].as_ref()).unwrap();
+                assert_eq!(header.header_len() + header.body_len(), buf.len());
+            }
+        }
+    }
+
+    // serialize rejects params of size 0 or of an invalid ciphersuite,
+    // and the length does not underflow
+    for compressed in [true, false].iter() {
+        assert_eq!(PointproofsParams::serialized_len(0, 0, *compressed), None);
+        assert_eq!(
+            PointproofsParams::serialized_len(MAX_N + 1, 0, *compressed),
+            None
+        );
+        assert_eq!(PointproofsParams::serialized_len(8, 3, *compressed), None);
+        let header = ParamsHeader {
+            n: 0,
+            ciphersuite: None,
+            compressed: *compressed,
+        };
+        assert_eq!(header.body_len(), 0);
+    }
+}
+
+#[test]
+fn test_write_verifier_param() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    let (pp, vp) = read_param_with_rng(&mut buf[..].as_ref(), &mut rng).unwrap();
+
+    let mut trimmed: Vec<u8> = vec![];
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    let written =
+        write_verifier_param_with_rng(&mut buf[..].as_ref(), &mut trimmed, &mut rng).unwrap();
+    assert_eq!(written, vp);
+    assert!(2 * trimmed.len() < buf.len());
+
+    // proofs verify against the trimmed parameters
+    let vp_trimmed = VerifierParams::from_bytes(&trimmed).unwrap();
+    assert_eq!(vp_trimmed, vp);
+    let n = pp.n();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&pp, &values).unwrap();
+    for i in 0..n {
+        let proof = Proof::new(&pp, &values, i).unwrap();
+        assert!(proof.verify(&vp_trimmed, &com, &values[i], i));
+        assert!(!proof.verify(&vp_trimmed, &com, &values[(i + 1) % n], i));
+    }
+
+    // the full parameters are checked before anything is written
+    let mut bad = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    bad.g1_alpha_1_to_n.swap(1, 2);
+    let mut bad_buf: Vec<u8> = vec![];
+    bad.serialize(&mut bad_buf, true).unwrap();
+    let mut out: Vec<u8> = vec![];
+    assert!(write_verifier_param(&mut bad_buf[..].as_ref(), &mut out).is_err());
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_consistent_n_equals_one() {
+    use pairing_plus::CurveProjective;
+    use pairings::hash_to_field_pointproofs;
+
+    // n = 1 has no power of alpha above alpha^N,
+    // so only e(g1^alpha, g2) = e(g1, g2^alpha) and gt are checked
+    let alpha = hash_to_field_pointproofs("This is Leo's Favourite very very very long Seed");
+    let g1_alpha = G1Affine::one().mul(alpha).into_affine();
+    let g2_alpha = G2Affine::one().mul(alpha).into_affine();
+    let mut params = PointproofsParams {
+        ciphersuite: 0,
+        n: 1,
+        g1_alpha_1_to_n: vec![g1_alpha],
+        g1_alpha_nplus2_to_2n: vec![],
+        g2_alpha_1_to_n: vec![g2_alpha],
+        g2_alpha_nplus2_to_2n: vec![],
+        gt_alpha_nplus1: g1_alpha.pairing_with(&g2_alpha),
+    };
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    assert_eq!(consistent_detailed_with_rng(&params, &mut rng), Ok(()));
+
+    // the params round trip, and read_param accepts them
+    let mut buf: Vec<u8> = vec![];
+    params.serialize(&mut buf, true).unwrap();
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    let (pp, vp) = read_param_with_rng(&mut buf[..].as_ref(), &mut rng).unwrap();
+    assert_eq!(pp.n(), 1);
+    let com = Commitment::new(&pp, &["this is message number 0"]).unwrap();
+    let proof = Proof::new(&pp, &["this is message number 0"], 0).unwrap();
+    assert!(proof.verify(&vp, &com, "this is message number 0", 0));
+
+    let gt = params.gt_alpha_nplus1;
+    params.gt_alpha_nplus1 = Fq12::one();
+    assert_eq!(
+        consistent_detailed(&params),
+        Err(ConsistencyFailure::GtAlphaNplus1)
+    );
+    params.gt_alpha_nplus1 = gt;
+
+    // lists that do not match n are reported, and not indexed out of range
+    params.g1_alpha_nplus2_to_2n.push(g1_alpha);
+    assert_eq!(
+        consistent_detailed(&params),
+        Err(ConsistencyFailure::InvalidLength)
+    );
+    params.g1_alpha_nplus2_to_2n.clear();
+    params.g2_alpha_1_to_n.clear();
+    assert_eq!(
+        consistent_detailed(&params),
+        Err(ConsistencyFailure::InvalidLength)
+    );
+    params.n = 0;
+    params.g1_alpha_1_to_n.clear();
+    assert_eq!(
+        consistent_detailed(&params),
+        Err(ConsistencyFailure::InvalidLength)
+    );
+}
+
+#[test]
+fn test_param_ciphersuite_round_trip() {
+    let buf = std::fs::read("crs.param").unwrap();
+    let mut param = PointproofsParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    // the file predates the ciphersuite id, and is read as ciphersuite 0
+    assert_eq!(param.ciphersuite, 0);
+
+    for csid in 0..3u8 {
+        param.ciphersuite = csid;
+        for compressed in [true, false].iter() {
+            let mut out: Vec<u8> = vec![];
+            param.serialize(&mut out, *compressed).unwrap();
+            if csid == 0 && *compressed {
+                assert_eq!(out, buf);
+            }
+            let header = PointproofsParams::peek_header(&mut out[..].as_ref()).unwrap();
+            assert_eq!(
+                header.ciphersuite,
+                if csid == 0 { None } else { Some(csid) }
+            );
+            assert_eq!(header.header_len() + header.body_len(), out.len());
+
+            let recovered = PointproofsParams::deserialize(&mut out[..].as_ref(), true).unwrap();
+            assert_eq!(recovered.ciphersuite, csid);
+            assert_eq!(recovered, param);
+            assert_eq!(
+                PointproofsParams::deserialize_from_slice(&out).unwrap(),
+                param
+            );
+            assert_eq!(
+                PointproofsParams::deserialize_single_read(&mut out[..].as_ref()).unwrap(),
+                param
+            );
+        }
+
+        // the parameters read from the file use its ciphersuite
+        let mut out: Vec<u8> = vec![];
+        param.serialize(&mut out, true).unwrap();
+        let mut rng = ChaChaRng::from_seed([0u8; 32]);
+        let (pp, vp) = read_param_with_rng(&mut out[..].as_ref(), &mut rng).unwrap();
+        assert_eq!(pp.ciphersuite(), csid);
+        assert_eq!(vp.ciphersuite(), csid);
+        let values: Vec<String> = (0..pp.n())
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&pp, &values).unwrap();
+        let proof = Proof::new(&pp, &values, 0).unwrap();
+        assert_eq!(com.ciphersuite(), csid);
+        assert!(proof.verify(&vp, &com, &values[0], 0));
+
+        // a commitment for another ciphersuite is rejected
+        let (other_pp, _) = paramgen_from_seed(
+            "This is Leo's Favourite very very very long Seed",
+            (csid + 1) % 3,
+            pp.n(),
+        )
+        .unwrap();
+        let other_com = Commitment::new(&other_pp, &values).unwrap();
+        let other_proof = Proof::new(&other_pp, &values, 0).unwrap();
+        assert!(!other_proof.verify(&vp, &other_com, &values[0], 0));
+        assert!(!proof.verify(&vp, &other_com, &values[0], 0));
+    }
+
+    // invalid ciphersuites are rejected in both directions
+    param.ciphersuite = 3;
+    let mut out: Vec<u8> = vec![];
+    assert!(param.serialize(&mut out, true).is_err());
+    for csid in [0u8, 3].iter() {
+        let mut bad = buf.clone();
+        bad[3] |= (CIPHERSUITE_FLAG >> 24) as u8;
+        bad.insert(4, *csid);
+        assert!(PointproofsParams::deserialize(&mut bad[..].as_ref(), true).is_err());
+        assert!(PointproofsParams::deserialize_from_slice(&bad).is_err());
+    }
+}
@@ -1,6 +1,15 @@
+use ff::PrimeField;
+use pairing_plus::bls12_381::{Fr, FrRepr};
+use pairing_plus::serdes::SerDes;
 use pairing_plus::CurveProjective;
+use pairings::hash_to_field_pointproofs::{
+    hash_to_field_pointproofs, hash_to_field_repr_pointproofs,
+};
 use pairings::param::paramgen_from_seed;
+use pairings::pointproofs_groups::PointproofsG1;
 use pairings::*;
+use rand::prng::ChaChaRng;
+use rand::{Rng, SeedableRng};
 
 #[test]
 fn negative_test_batch_new_proof() {
@@ -9,7 +18,7 @@ fn negative_test_batch_new_proof() {
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
 
     let mut pp2 = prover_params.clone();
-    pp2.ciphersuite = 1;
+    pp2.ciphersuite = 3;
     let mut pp3 = prover_params.clone();
     pp3.n = 2;
 
@@ -45,9 +54,9 @@ fn negative_test_proof() {
     let (prover_params, verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut prover_params2 = prover_params.clone();
-    prover_params2.ciphersuite = 1;
+    prover_params2.ciphersuite = 3;
     let mut verifier_params2 = verifier_params.clone();
-    verifier_params2.ciphersuite = 1;
+    verifier_params2.ciphersuite = 3;
 
     let mut prover_params256 = prover_params.clone();
     prover_params256.precomp_256();
@@ -81,14 +90,14 @@ fn negative_test_proof() {
         .is_err());
 
     let mut proof2 = proof.clone();
-    proof2.ciphersuite = 1;
+    proof2.ciphersuite = 3;
     assert!(proof2
         .update(&prover_params, 0, 1, &values2[1], &values2[2])
         .is_err());
 
     let com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 3;
     assert!(!proof.verify(&verifier_params2, &com, &values[0], 0));
     assert!(!proof.verify(&verifier_params, &com, &values[0], 9));
     assert!(!proof.verify(&verifier_params, &com2, &values[0], 0));
@@ -444,3 +453,1412 @@ fn test_batch_new_proof() {
         "pre_compute256 failed"
     );
 }
+
+#[test]
+fn test_new_all() {
+    // n = 1 and sizes on both sides of a power of 2
+    for n in [1usize, 2, 5, 8, 16].iter() {
+        let n = *n;
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+        let mut values: Vec<String> = vec![];
+        for i in 0..n {
+            let s = format!("this is message number {}", i);
+            values.push(s);
+        }
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proofs = Proof::new_all(&prover_params, &values).unwrap();
+        assert_eq!(proofs.len(), n);
+        for (i, proof) in proofs.iter().enumerate() {
+            assert_eq!(*proof, Proof::new(&prover_params, &values, i).unwrap());
+            assert!(
+                proof.verify(&verifier_params, &com, &values[i], i),
+                "n = {}, index = {}",
+                n,
+                i
+            );
+        }
+
+        let mut prover_params2 = prover_params.clone();
+        prover_params2.ciphersuite = 3;
+        assert!(Proof::new_all(&prover_params2, &values).is_err());
+        assert!(Proof::new_all(&prover_params, &values[1..]).is_err());
+    }
+}
+
+#[test]
+fn test_new_all_tuned() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut prover_params256 = prover_params.clone();
+    prover_params256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proofs: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values, i).unwrap())
+        .collect();
+
+    // from a budget of a single scalar up to one that fits all of them
+    for max_memory in [0, 40, 3 * 40, 5 * 40, n * 40, usize::max_value()].iter() {
+        let tuned = Proof::new_all_tuned(&prover_params, &values, *max_memory).unwrap();
+        assert_eq!(tuned, proofs, "max_memory = {}", max_memory);
+        let tuned = Proof::new_all_tuned(&prover_params256, &values, *max_memory).unwrap();
+        assert_eq!(tuned, proofs, "pre_compute256 failed");
+    }
+    // on either side of the crossover to the FFT
+    let fft_memory = Proof::new_all_memory(n);
+    assert!(fft_memory > n * 40);
+    for max_memory in [fft_memory - 1, fft_memory].iter() {
+        let tuned = Proof::new_all_tuned(&prover_params, &values, *max_memory).unwrap();
+        assert_eq!(tuned, proofs, "max_memory = {}", max_memory);
+    }
+    for (i, proof) in proofs.iter().enumerate() {
+        assert!(proof.verify(&verifier_params, &com, &values[i], i));
+    }
+
+    let mut prover_params2 = prover_params.clone();
+    prover_params2.ciphersuite = 3;
+    assert!(Proof::new_all_tuned(&prover_params2, &values, n * 40).is_err());
+    assert!(Proof::new_all_tuned(&prover_params, &values[1..], n * 40).is_err());
+}
+
+#[test]
+fn test_new_from_repr_slice() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut prover_params256 = prover_params.clone();
+    prover_params256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let reprs: Vec<FrRepr> = values
+        .iter()
+        .map(|s| hash_to_field_repr_pointproofs(s))
+        .collect();
+
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    assert_eq!(
+        Commitment::new_from_repr_slice(&prover_params, &reprs).unwrap(),
+        com
+    );
+    assert_eq!(
+        Commitment::new_from_repr_slice(&prover_params256, &reprs).unwrap(),
+        com
+    );
+    for i in 0..n {
+        let proof = Proof::new_from_repr_slice(&prover_params, &reprs, i).unwrap();
+        assert_eq!(proof, Proof::new(&prover_params, &values, i).unwrap());
+        assert_eq!(
+            Proof::new_from_repr_slice(&prover_params256, &reprs, i).unwrap(),
+            proof
+        );
+        assert!(proof.verify(&verifier_params, &com, &values[i], i));
+    }
+
+    // the modulus itself is not canonical
+    let mut bad_reprs = reprs.clone();
+    bad_reprs[3] = Fr::char();
+    match Commitment::new_from_repr_slice(&prover_params, &bad_reprs) {
+        Err(PointproofsError::NonCanonicalScalar) => (),
+        _ => panic!("non-canonical scalar accepted"),
+    }
+    bad_reprs[3] = FrRepr([u64::max_value(); 4]);
+    match Proof::new_from_repr_slice(&prover_params, &bad_reprs, 0) {
+        Err(PointproofsError::NonCanonicalScalar) => (),
+        _ => panic!("non-canonical scalar accepted"),
+    }
+
+    assert!(Commitment::new_from_repr_slice(&prover_params, &reprs[1..]).is_err());
+    assert!(Proof::new_from_repr_slice(&prover_params, &reprs[1..], 0).is_err());
+    assert!(Proof::new_from_repr_slice(&prover_params, &reprs, n).is_err());
+    let mut prover_params2 = prover_params.clone();
+    prover_params2.ciphersuite = 3;
+    assert!(Commitment::new_from_repr_slice(&prover_params2, &reprs).is_err());
+    assert!(Proof::new_from_repr_slice(&prover_params2, &reprs, 0).is_err());
+}
+
+#[test]
+fn test_subvector_proof() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    for (lo, hi) in [(0, 1), (3, 7), (0, n), (n - 1, n)].iter() {
+        let proof = Proof::new_subvector(&prover_params, &values, *lo, *hi).unwrap();
+        let indices: Vec<usize> = (*lo..*hi).collect();
+        assert_eq!(
+            proof,
+            Proof::batch_new_aggregated(&prover_params, &com, &values, &indices).unwrap()
+        );
+        assert_eq!(
+            proof,
+            Proof::new_subvector_with_commit(&prover_params, &com, &values, *lo, *hi).unwrap()
+        );
+        assert!(proof.verify_subvector(&verifier_params, &com, &values[*lo..*hi], *lo, *hi));
+
+        // wrong range, or wrong values
+        let mut wrong_values = values[*lo..*hi].to_vec();
+        wrong_values[0] = "this is a wrong message".to_owned();
+        assert!(!proof.verify_subvector(&verifier_params, &com, &wrong_values, *lo, *hi));
+        if *hi < n {
+            assert!(!proof.verify_subvector(
+                &verifier_params,
+                &com,
+                &values[*lo + 1..*hi + 1],
+                *lo + 1,
+                *hi + 1
+            ));
+        }
+    }
+
+    // degenerated ranges
+    match Proof::new_subvector(&prover_params, &values, 3, 3) {
+        Err(PointproofsError::EmptyRange) => (),
+        _ => panic!("expected PointproofsError::EmptyRange"),
+    }
+    match Proof::new_subvector(&prover_params, &values, 4, 3) {
+        Err(PointproofsError::EmptyRange) => (),
+        _ => panic!("expected PointproofsError::EmptyRange"),
+    }
+    match Proof::new_subvector(&prover_params, &values, 3, n + 1) {
+        Err(PointproofsError::InvalidRange) => (),
+        _ => panic!("expected PointproofsError::InvalidRange"),
+    }
+    match Proof::new_subvector_with_commit(&prover_params, &com, &values, 3, 3) {
+        Err(PointproofsError::EmptyRange) => (),
+        _ => panic!("expected PointproofsError::EmptyRange"),
+    }
+    match Proof::new_subvector_with_commit(&prover_params, &com, &values, 3, n + 1) {
+        Err(PointproofsError::InvalidRange) => (),
+        _ => panic!("expected PointproofsError::InvalidRange"),
+    }
+    let proof = Proof::new_subvector(&prover_params, &values, 0, n).unwrap();
+    let empty: Vec<String> = vec![];
+    assert!(!proof.verify_subvector(&verifier_params, &com, &empty, 3, 3));
+    assert!(!proof.verify_subvector(&verifier_params, &com, &values, 1, n + 1));
+    assert!(!proof.verify_subvector(&verifier_params, &com, &values[1..], 0, n));
+}
+
+#[test]
+#[cfg(feature = "debug-verify")]
+fn test_verify_update_consistency() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut prover_params256 = prover_params.clone();
+    prover_params256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let new_value = "this is a new message".to_owned();
+
+    for (proof_index, changed_index) in
+        [(0, 0), (0, 1), (1, 0), (3, 5), (5, 3), (7, 0), (0, 7)].iter()
+    {
+        for pp in [&prover_params, &prover_params256].iter() {
+            assert!(Proof::verify_update_consistency(
+                pp,
+                &values,
+                *proof_index,
+                *changed_index,
+                values[*changed_index].clone(),
+                new_value.clone()
+            ));
+        }
+    }
+
+    // the value before the change does not match
+    assert!(!Proof::verify_update_consistency(
+        &prover_params,
+        &values,
+        3,
+        5,
+        values[4].clone(),
+        new_value.clone()
+    ));
+    // invalid indices
+    assert!(!Proof::verify_update_consistency(
+        &prover_params,
+        &values,
+        n,
+        5,
+        values[5].clone(),
+        new_value.clone()
+    ));
+    assert!(!Proof::verify_update_consistency(
+        &prover_params,
+        &values,
+        3,
+        n,
+        new_value.clone(),
+        new_value
+    ));
+}
+
+#[test]
+fn test_verify_and_open() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    for i in 0..n {
+        let proof = Proof::new(&prover_params, &values, i).unwrap();
+        let opened = proof
+            .verify_and_open(&verifier_params, &com, &values[i], i)
+            .unwrap();
+        assert_eq!(opened, hash_to_field_pointproofs(&values[i]));
+    }
+
+    let proof = Proof::new(&prover_params, &values, 2).unwrap();
+    assert_eq!(
+        proof.verify_and_open(&verifier_params, &com, &values[3], 2),
+        Err(VerifyFailure::PairingCheck)
+    );
+    assert_eq!(
+        proof.verify_and_open(&verifier_params, &com, &values[2], 3),
+        Err(VerifyFailure::PairingCheck)
+    );
+    assert_eq!(
+        proof.verify_and_open(&verifier_params, &com, &values[2], n),
+        Err(VerifyFailure::IndexOutOfRange)
+    );
+    let mut com2 = com.clone();
+    com2.ciphersuite = 3;
+    assert_eq!(
+        proof.verify_and_open(&verifier_params, &com2, &values[2], 2),
+        Err(VerifyFailure::CiphersuiteMismatch)
+    );
+    let mut verifier_params2 = verifier_params.clone();
+    verifier_params2.ciphersuite = 3;
+    let mut proof2 = proof.clone();
+    proof2.ciphersuite = 3;
+    assert_eq!(
+        proof2.verify_and_open(&verifier_params2, &com2, &values[2], 2),
+        Err(VerifyFailure::InvalidCiphersuite)
+    );
+}
+
+#[test]
+fn test_verify_identity_commitment() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    // a vector of empty values does not commit to the identity
+    let values: Vec<Vec<u8>> = vec![vec![]; n];
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    assert!(!com.commit.is_zero());
+    let proofs: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values, i).unwrap())
+        .collect();
+    for (i, proof) in proofs.iter().enumerate() {
+        assert!(proof.verify(&verifier_params, &com, &values[i], i));
+    }
+
+    // the identity commitment rejects every opening
+    let identity = Commitment {
+        ciphersuite: 0,
+        n: 0,
+        commit: PointproofsG1::zero(),
+    };
+    let zero_proof = Proof {
+        ciphersuite: 0,
+        n: 0,
+        proof: PointproofsG1::zero(),
+    };
+    for (i, proof) in proofs.iter().chain([zero_proof.clone()].iter()).enumerate() {
+        let index = i % n;
+        assert!(!proof.verify(&verifier_params, &identity, &values[index], index));
+        assert_eq!(
+            proof.verify_and_open(&verifier_params, &identity, &values[index], index),
+            Err(VerifyFailure::IdentityCommitment)
+        );
+    }
+    let set = vec![0usize, 3];
+    let value_sub_vector = vec![values[0].clone(), values[3].clone()];
+    let agg_proof =
+        Proof::same_commit_aggregate(&com, &proofs[..1], &set[..1], &value_sub_vector[..1], n)
+            .unwrap();
+    assert!(!agg_proof.same_commit_batch_verify(
+        &verifier_params,
+        &identity,
+        &set,
+        &value_sub_vector
+    ));
+    assert!(!zero_proof.same_commit_batch_verify(
+        &verifier_params,
+        &identity,
+        &set,
+        &value_sub_vector
+    ));
+    assert!(!zero_proof.cross_commit_batch_verify(
+        &verifier_params,
+        &[identity.clone(), com.clone()],
+        &[set.clone(), set.clone()],
+        &[value_sub_vector.clone(), value_sub_vector.clone()]
+    ));
+}
+
+#[test]
+fn test_proof_batch_update() {
+    let n = 64usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp256 = prover_params.clone();
+    pp256.precomp_256();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+
+    // 50 random changes, possibly hitting the same index more than once
+    // and possibly hitting the index of the proof
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    let mut new_values = values.clone();
+    let mut changes: Vec<(usize, Vec<u8>, Vec<u8>)> = vec![];
+    for j in 0..50 {
+        let i = rng.gen_range(0, n);
+        let new_value = format!("this is new message number {} for index {}", j, i);
+        changes.push((
+            i,
+            new_values[i].as_bytes().to_vec(),
+            new_value.as_bytes().to_vec(),
+        ));
+        new_values[i] = new_value;
+    }
+    let proof_index = changes[0].0;
+    let new_com = Commitment::new(&prover_params, &new_values).unwrap();
+
+    for index in [proof_index, (proof_index + 1) % n].iter() {
+        let proof = Proof::new(&prover_params, &values, *index).unwrap();
+
+        // sequential updates
+        let mut expected = proof.clone();
+        for (i, old, new) in changes.iter() {
+            expected
+                .update(&prover_params, *index, *i, old, new)
+                .unwrap();
+        }
+
+        for pp in [&prover_params, &pp256].iter() {
+            let mut batch = proof.clone();
+            batch.batch_update(pp, *index, &changes).unwrap();
+            assert_eq!(batch, expected);
+        }
+        // the proof does not depend on its own value
+        assert!(expected.verify(&verifier_params, &new_com, &new_values[*index], *index));
+    }
+
+    // an invalid change leaves the proof untouched
+    let proof = Proof::new(&prover_params, &values, 1).unwrap();
+    let mut bad_changes = changes.clone();
+    bad_changes.push((n, vec![], vec![]));
+    let mut bad = proof.clone();
+    match bad.batch_update(&prover_params, 1, &bad_changes) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+    assert_eq!(bad, proof);
+}
+
+#[test]
+fn test_verify_prepared() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut prepared = verifier_params.clone();
+    prepared.precomp();
+    assert_eq!(prepared, verifier_params);
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    for i in 0..n {
+        let proof = Proof::new(&prover_params, &values, i).unwrap();
+        assert!(proof.verify(&prepared, &com, &values[i], i));
+        assert!(!proof.verify(&prepared, &com, &values[(i + 1) % n], i));
+        assert_eq!(
+            proof.verify_and_open(&prepared, &com, &values[i], i),
+            proof.verify_and_open(&verifier_params, &com, &values[i], i)
+        );
+    }
+
+    // the cache is dropped by serialization, verification still works
+    let mut buf: Vec<u8> = vec![];
+    prepared.serialize(&mut buf, true).unwrap();
+    let mut buf_plain: Vec<u8> = vec![];
+    verifier_params.serialize(&mut buf_plain, true).unwrap();
+    assert_eq!(buf, buf_plain);
+    let recovered = VerifierParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+    assert!(proof.verify(&recovered, &com, &values[3], 3));
+}
+
+#[test]
+fn test_proof_apply_plan() {
+    let n = 32usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let proofs: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values, i).unwrap())
+        .collect();
+
+    // K = 10 changes
+    let mut new_values = values.clone();
+    let mut changes: Vec<(usize, String, String)> = vec![];
+    for i in (0..n).step_by(3).take(10) {
+        let new_value = format!("this is new message number {}", i);
+        changes.push((i, values[i].clone(), new_value.clone()));
+        new_values[i] = new_value;
+    }
+    let new_com = Commitment::new(&prover_params, &new_values).unwrap();
+
+    let plan = Proof::plan_updates(&prover_params, &changes).unwrap();
+    for (i, proof) in proofs.iter().enumerate() {
+        let mut planned = proof.clone();
+        planned.apply_plan(&plan, i).unwrap();
+
+        let mut expected = proof.clone();
+        expected.batch_update(&prover_params, i, &changes).unwrap();
+        assert_eq!(planned, expected);
+        assert!(planned.verify(&verifier_params, &new_com, &new_values[i], i));
+    }
+
+    // invalid inputs
+    let mut proof = proofs[0].clone();
+    match proof.apply_plan(&plan, n) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+    assert_eq!(proof, proofs[0]);
+    let mut bad_changes = changes.clone();
+    bad_changes.push((n, String::new(), String::new()));
+    match Proof::plan_updates(&prover_params, &bad_changes) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+}
+
+#[test]
+fn test_verify_bytes_checked() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let fingerprint = com.fingerprint();
+    let mut com_bytes: Vec<u8> = vec![];
+    com.serialize(&mut com_bytes, true).unwrap();
+
+    let proof = Proof::new(&prover_params, &values, 2).unwrap();
+
+    // success
+    assert_eq!(
+        proof.verify_bytes_checked(&verifier_params, &com_bytes, &fingerprint, &values[2], 2),
+        proof.verify_and_open(&verifier_params, &com, &values[2], 2)
+    );
+    assert!(proof
+        .verify_bytes_checked(&verifier_params, &com_bytes, &fingerprint, &values[2], 2)
+        .is_ok());
+
+    // deserialization failures
+    assert_eq!(
+        proof.verify_bytes_checked(
+            &verifier_params,
+            &com_bytes[..com_bytes.len() - 1],
+            &fingerprint,
+            &values[2],
+            2
+        ),
+        Err(VerifyFailure::InvalidEncoding)
+    );
+    let mut trailing = com_bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        proof.verify_bytes_checked(&verifier_params, &trailing, &fingerprint, &values[2], 2),
+        Err(VerifyFailure::InvalidEncoding)
+    );
+
+    // a valid commitment to other values
+    let mut other_values = values.clone();
+    other_values[5] = "this is a different message".to_string();
+    let other_com = Commitment::new(&prover_params, &other_values).unwrap();
+    assert_ne!(other_com.fingerprint(), fingerprint);
+    let mut other_bytes: Vec<u8> = vec![];
+    other_com.serialize(&mut other_bytes, true).unwrap();
+    assert_eq!(
+        proof.verify_bytes_checked(&verifier_params, &other_bytes, &fingerprint, &values[2], 2),
+        Err(VerifyFailure::FingerprintMismatch)
+    );
+
+    // the fingerprint matches but the proof does not
+    assert_eq!(
+        proof.verify_bytes_checked(&verifier_params, &com_bytes, &fingerprint, &values[3], 2),
+        Err(VerifyFailure::PairingCheck)
+    );
+}
+
+#[test]
+fn test_batch_verify_same_commit() {
+    let n = 16usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proofs: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values, i).unwrap())
+        .collect();
+
+    let openings: Vec<(usize, &[u8], &Proof)> = (0..n)
+        .map(|i| (i, values[i].as_bytes(), &proofs[i]))
+        .collect();
+    assert!(Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &openings
+    ));
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    assert!(Proof::batch_verify_same_commit_with_rng(
+        &verifier_params,
+        &com,
+        &openings[3..7],
+        &mut rng
+    ));
+    assert!(!Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &[]
+    ));
+
+    // any single tampered opening fails the batch
+    let wrong_value = "this is not a committed message";
+    for i in 0..n {
+        let mut bad = openings.clone();
+        bad[i].1 = wrong_value.as_bytes();
+        assert!(!Proof::batch_verify_same_commit(
+            &verifier_params,
+            &com,
+            &bad
+        ));
+
+        let mut bad = openings.clone();
+        bad[i].2 = &proofs[(i + 1) % n];
+        assert!(!Proof::batch_verify_same_commit(
+            &verifier_params,
+            &com,
+            &bad
+        ));
+
+        let mut bad = openings.clone();
+        bad[i].0 = (i + 1) % n;
+        assert!(!Proof::batch_verify_same_commit(
+            &verifier_params,
+            &com,
+            &bad
+        ));
+    }
+
+    // out of range index
+    let mut bad = openings.clone();
+    bad[0].0 = n;
+    assert!(!Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &bad
+    ));
+
+    // a different commitment
+    let mut other_values = values.clone();
+    other_values[0] = wrong_value.to_string();
+    let other_com = Commitment::new(&prover_params, &other_values).unwrap();
+    assert!(!Proof::batch_verify_same_commit(
+        &verifier_params,
+        &other_com,
+        &openings
+    ));
+}
+
+#[test]
+fn test_ciphersuites() {
+    let n = 8usize;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    let mut params = vec![];
+    let mut coms = vec![];
+    let mut proofs = vec![];
+    for csid in 0..3u8 {
+        let (pp, vp) = paramgen_from_seed(seed, csid, n).unwrap();
+        let com = Commitment::new(&pp, &values).unwrap();
+        let proof = Proof::new(&pp, &values, 2).unwrap();
+        assert!(proof.verify(&vp, &com, &values[2], 2));
+        assert!(!proof.verify(&vp, &com, &values[3], 2));
+
+        // serialization keeps the ciphersuite
+        let mut buf: Vec<u8> = vec![];
+        com.serialize(&mut buf, true).unwrap();
+        assert_eq!(buf[0], csid);
+        assert_eq!(
+            Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap(),
+            com
+        );
+
+        params.push((pp, vp));
+        coms.push(com);
+        proofs.push(proof);
+    }
+
+    for i in 0..3 {
+        for j in 0..3 {
+            if i == j {
+                continue;
+            }
+            // the values are hashed differently
+            assert_ne!(coms[i].commit, coms[j].commit);
+            assert_ne!(proofs[i].proof, proofs[j].proof);
+
+            // ciphersuite-i commitments and proofs do not verify against ciphersuite-j params
+            assert_eq!(
+                proofs[i].verify_and_open(&params[j].1, &coms[i], &values[2], 2),
+                Err(VerifyFailure::CiphersuiteMismatch)
+            );
+            assert_eq!(
+                proofs[j].verify_and_open(&params[j].1, &coms[i], &values[2], 2),
+                Err(VerifyFailure::CiphersuiteMismatch)
+            );
+
+            // nor with a relabelled ciphersuite
+            let mut com = coms[i].clone();
+            let mut proof = proofs[i].clone();
+            com.ciphersuite = j as u8;
+            proof.ciphersuite = j as u8;
+            assert_eq!(
+                proof.verify_and_open(&params[j].1, &com, &values[2], 2),
+                Err(VerifyFailure::PairingCheck)
+            );
+        }
+    }
+
+    assert!(paramgen_from_seed(seed, 3, n).is_err());
+}
+
+#[test]
+fn test_ciphersuite_mismatch_pairings() {
+    let n = 8usize;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let (pp0, vp0) = paramgen_from_seed(seed, 0, n).unwrap();
+    let (pp1, vp1) = paramgen_from_seed(seed, 1, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com0 = Commitment::new(&pp0, &values).unwrap();
+    let com1 = Commitment::new(&pp1, &values).unwrap();
+    let proof0 = Proof::new(&pp0, &values, 2).unwrap();
+    let proof1 = Proof::new(&pp1, &values, 2).unwrap();
+
+    // verify: every pair of proof, commitment and params must agree
+    let mismatches = [
+        (&proof1, &com0, &vp0),
+        (&proof0, &com1, &vp0),
+        (&proof0, &com0, &vp1),
+        (&proof1, &com1, &vp0),
+        (&proof1, &com0, &vp1),
+        (&proof0, &com1, &vp1),
+    ];
+    for (proof, com, vp) in mismatches.iter() {
+        assert_eq!(
+            proof.verify_and_open(vp, com, &values[2], 2),
+            Err(VerifyFailure::CiphersuiteMismatch)
+        );
+        assert!(!proof.verify(vp, com, &values[2], 2));
+    }
+
+    // an unknown ciphersuite is rejected even if all three agree
+    let mut proof3 = proof0.clone();
+    let mut com3 = com0.clone();
+    let mut vp3 = vp0.clone();
+    proof3.ciphersuite = 3;
+    com3.ciphersuite = 3;
+    vp3.ciphersuite = 3;
+    assert_eq!(
+        proof3.verify_and_open(&vp3, &com3, &values[2], 2),
+        Err(VerifyFailure::InvalidCiphersuite)
+    );
+
+    // Commitment::update and Proof::update: the object and the params must agree
+    let new_value = "this is new message number 5";
+    for (com, pp) in [(&com0, &pp1), (&com1, &pp0), (&com3, &pp0)].iter() {
+        let mut com = (*com).clone();
+        match com.update(pp, 5, &values[5][..], new_value) {
+            Err(PointproofsError::InvalidCiphersuite) => (),
+            _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+        }
+    }
+    for (proof, pp) in [(&proof0, &pp1), (&proof1, &pp0), (&proof3, &pp0)].iter() {
+        let mut proof = (*proof).clone();
+        match proof.update(pp, 2, 5, &values[5][..], new_value) {
+            Err(PointproofsError::InvalidCiphersuite) => (),
+            _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+        }
+    }
+    let mut com = com1.clone();
+    let mut proof = proof1.clone();
+    com.update(&pp1, 5, &values[5][..], new_value).unwrap();
+    proof.update(&pp1, 2, 5, &values[5][..], new_value).unwrap();
+    assert!(proof.verify(&vp1, &com, &values[2], 2));
+
+    // deserialization only accepts known ciphersuites
+    let mut com_bytes: Vec<u8> = vec![];
+    com0.serialize(&mut com_bytes, true).unwrap();
+    let mut proof_bytes: Vec<u8> = vec![];
+    proof0.serialize(&mut proof_bytes, true).unwrap();
+    let mut pp_bytes: Vec<u8> = vec![];
+    pp0.serialize(&mut pp_bytes, true).unwrap();
+    let mut vp_bytes: Vec<u8> = vec![];
+    vp0.serialize(&mut vp_bytes, true).unwrap();
+    for csid in 0..=255u8 {
+        com_bytes[0] = csid;
+        proof_bytes[0] = csid;
+        pp_bytes[0] = csid;
+        vp_bytes[0] = csid;
+        let known = csid < 3;
+        assert_eq!(
+            Commitment::deserialize(&mut com_bytes[..].as_ref(), true).is_ok(),
+            known
+        );
+        assert_eq!(
+            Proof::deserialize(&mut proof_bytes[..].as_ref(), true).is_ok(),
+            known
+        );
+        assert_eq!(
+            ProverParams::deserialize(&mut pp_bytes[..].as_ref(), true).is_ok(),
+            known
+        );
+        assert_eq!(
+            VerifierParams::deserialize(&mut vp_bytes[..].as_ref(), true).is_ok(),
+            known
+        );
+    }
+}
+
+#[test]
+fn test_owned_value_types() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let strings: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let vecs: Vec<Vec<u8>> = strings.iter().map(|s| s.clone().into_bytes()).collect();
+    let slices: Vec<&[u8]> = strings.iter().map(|s| s.as_bytes()).collect();
+
+    let com = Commitment::new(&prover_params, &slices).unwrap();
+    assert_eq!(com, Commitment::new(&prover_params, &strings).unwrap());
+    assert_eq!(com, Commitment::new(&prover_params, &vecs).unwrap());
+
+    for i in 0..n {
+        let proof = Proof::new(&prover_params, &slices, i).unwrap();
+        assert_eq!(proof, Proof::new(&prover_params, &strings, i).unwrap());
+        assert_eq!(proof, Proof::new(&prover_params, &vecs, i).unwrap());
+
+        assert!(proof.verify(&verifier_params, &com, slices[i], i));
+        assert!(proof.verify(&verifier_params, &com, &strings[i], i));
+        assert!(proof.verify(&verifier_params, &com, strings[i].clone(), i));
+        assert!(proof.verify(&verifier_params, &com, &vecs[i], i));
+        assert!(proof.verify(&verifier_params, &com, vecs[i].clone(), i));
+    }
+}
+
+#[test]
+fn test_bad_index_and_length_errors() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut pp3 = prover_params.clone();
+    pp3.precomp_3();
+
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let empty: Vec<String> = vec![];
+    let longer: Vec<String> = (0..n + 1)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+
+    for pp in [&prover_params, &pp3].iter() {
+        // values.len() != n
+        match Commitment::new(pp, &empty) {
+            Err(PointproofsError::MismatchedN) => (),
+            _ => panic!("expected PointproofsError::MismatchedN"),
+        }
+        match Commitment::new(pp, &values[1..]) {
+            Err(PointproofsError::MismatchedN) => (),
+            _ => panic!("expected PointproofsError::MismatchedN"),
+        }
+        match Commitment::new(pp, &longer) {
+            Err(PointproofsError::TooManyValues { got, max }) => {
+                assert_eq!((got, max), (n + 1, n))
+            }
+            _ => panic!("expected PointproofsError::TooManyValues"),
+        }
+        for bad in [&empty[..], &values[1..], &longer[..]].iter() {
+            match Proof::new(pp, bad, 0) {
+                Err(PointproofsError::MismatchedN) => (),
+                _ => panic!("expected PointproofsError::MismatchedN"),
+            }
+        }
+
+        // index >= n
+        let mut com = Commitment::new(pp, &values).unwrap();
+        let mut proof = Proof::new(pp, &values, 0).unwrap();
+        for index in [n, n + 1, 2 * n, usize::max_value()].iter() {
+            match Proof::new(pp, &values, *index) {
+                Err(PointproofsError::IndexOutOfRange) => (),
+                _ => panic!("expected PointproofsError::IndexOutOfRange"),
+            }
+            match com.update(pp, *index, &values[0], &values[1]) {
+                Err(PointproofsError::IndexOutOfRange) => (),
+                _ => panic!("expected PointproofsError::IndexOutOfRange"),
+            }
+            match proof.update(pp, 0, *index, &values[0], &values[1]) {
+                Err(PointproofsError::IndexOutOfRange) => (),
+                _ => panic!("expected PointproofsError::IndexOutOfRange"),
+            }
+            match proof.update(pp, *index, 0, &values[0], &values[1]) {
+                Err(PointproofsError::IndexOutOfRange) => (),
+                _ => panic!("expected PointproofsError::IndexOutOfRange"),
+            }
+        }
+        // the failed updates left the commitment and proof untouched
+        assert_eq!(com, Commitment::new(pp, &values).unwrap());
+        assert_eq!(proof, Proof::new(pp, &values, 0).unwrap());
+    }
+}
+
+#[test]
+fn test_proof_cannot_be_rerandomized() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[3], 3));
+
+    // any blinding of the proof breaks the verification equation
+    for j in 0..4 {
+        let mut blinded = proof.clone();
+        let mut blinding = PointproofsG1::one();
+        blinding.mul_assign(hash_to_field_pointproofs(format!("blinding {}", j)));
+        blinded.proof.add_assign(&blinding);
+        assert!(!blinded.verify(&verifier_params, &com, &values[3], 3));
+    }
+
+    // openings under two salted commitments to the same values are unlinkable
+    let mut rng = ChaChaRng::from_seed([0u8; 32]);
+    let com_a = Commitment::new_salted_with_rng(&prover_params, &values, &mut rng).unwrap();
+    let com_b = Commitment::new_salted_with_rng(&prover_params, &values, &mut rng).unwrap();
+    let proof_a = Proof::new_salted(&prover_params, &com_a, &values, 3).unwrap();
+    let proof_b = Proof::new_salted(&prover_params, &com_b, &values, 3).unwrap();
+    assert_ne!(proof_a, proof_b);
+    assert!(proof_a.verify_salted(&verifier_params, &com_a, &values[3], 3));
+    assert!(proof_b.verify_salted(&verifier_params, &com_b, &values[3], 3));
+}
+
+#[test]
+fn test_multi_pairing_check() {
+    use ff::Field;
+    use pairing_plus::bls12_381::Fq12;
+    use pairings::pointproofs_groups::PointproofsG2;
+
+    let a = hash_to_field_pointproofs("a");
+    let b = hash_to_field_pointproofs("b");
+    let mut ab = a;
+    ab.mul_assign(&b);
+
+    let mut g1_a = PointproofsG1::one();
+    g1_a.mul_assign(a);
+    let mut g2_b = PointproofsG2::one();
+    g2_b.mul_assign(b);
+    let mut g1_minus_ab = PointproofsG1::one();
+    g1_minus_ab.mul_assign(ab);
+    g1_minus_ab.negate();
+
+    // e(g1^a, g2^b) * e(g1^{-ab}, g2) == 1
+    let pairs = vec![
+        (g1_a.into_affine(), g2_b.into_affine()),
+        (
+            g1_minus_ab.into_affine(),
+            PointproofsG2::one().into_affine(),
+        ),
+    ];
+    assert!(multi_pairing_check(&pairs, &Fq12::one()));
+    assert!(!multi_pairing_check(&pairs[..1], &Fq12::one()));
+    assert!(multi_pairing_check(&[], &Fq12::one()));
+    assert!(!multi_pairing_check(&pairs[1..], &Fq12::one()));
+}
+
+#[test]
+fn test_verify_pairs() {
+    use std::collections::HashMap;
+
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    // a map of pending openings
+    let mut pending: HashMap<usize, (String, Proof)> = HashMap::new();
+    for i in [1usize, 4, 6].iter() {
+        let proof = Proof::new(&prover_params, &values, *i).unwrap();
+        pending.insert(*i, (values[*i].clone(), proof));
+    }
+    assert!(Proof::verify_pairs(
+        &verifier_params,
+        &com,
+        pending.iter().map(|(i, (v, p))| (*i, v.as_bytes(), p))
+    ));
+
+    // an empty iterator is valid
+    assert!(Proof::verify_pairs(&verifier_params, &com, vec![]));
+
+    // a wrong value fails
+    pending.get_mut(&4).unwrap().0 = "this is not a committed message".to_string();
+    assert!(!Proof::verify_pairs(
+        &verifier_params,
+        &com,
+        pending.iter().map(|(i, (v, p))| (*i, v.as_bytes(), p))
+    ));
+
+    // the iteration stops at the first out of range index
+    let proof = Proof::new(&prover_params, &values, 0).unwrap();
+    let entries = vec![
+        (0usize, values[0].as_bytes(), &proof),
+        (n, values[0].as_bytes(), &proof),
+        (0usize, values[0].as_bytes(), &proof),
+    ];
+    let mut consumed = 0;
+    assert!(!Proof::verify_pairs(
+        &verifier_params,
+        &com,
+        entries.iter().cloned().inspect(|_| consumed += 1)
+    ));
+    assert_eq!(consumed, 2);
+}
+
+#[test]
+fn test_n_equals_one() {
+    let n = 1usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values = vec!["this is message number 0".to_string()];
+    let new_values = vec!["this is a new message number 0".to_string()];
+
+    // commit, prove and verify
+    let mut com = Commitment::new(&prover_params, &values).unwrap();
+    let mut proof = Proof::new(&prover_params, &values, 0).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[0], 0));
+    assert!(!proof.verify(&verifier_params, &com, &new_values[0], 0));
+    assert!(Proof::new(&prover_params, &values, 1).is_err());
+
+    // there are no other values, so the proof is the identity,
+    // and it survives a round trip
+    assert_eq!(proof.proof, PointproofsG1::zero());
+    let mut buf: Vec<u8> = vec![];
+    proof.serialize(&mut buf, true).unwrap();
+    assert_eq!(
+        Proof::deserialize(&mut buf[..].as_ref(), true).unwrap(),
+        proof
+    );
+    assert_eq!(Proof::deserialize_v0(&mut buf[..].as_ref()).unwrap(), proof);
+
+    // update
+    com.update(&prover_params, 0, &values[0], &new_values[0])
+        .unwrap();
+    proof
+        .update(&prover_params, 0, 0, &values[0], &new_values[0])
+        .unwrap();
+    assert_eq!(com, Commitment::new(&prover_params, &new_values).unwrap());
+    assert_eq!(proof, Proof::new(&prover_params, &new_values, 0).unwrap());
+    assert!(proof.verify(&verifier_params, &com, &new_values[0], 0));
+    assert!(!proof.verify(&verifier_params, &com, &values[0], 0));
+
+    // aggregation within and across commitments
+    let agg = Proof::same_commit_aggregate(&com, &[proof.clone()], &[0], &new_values, n).unwrap();
+    assert!(agg.same_commit_batch_verify(&verifier_params, &com, &[0], &new_values));
+    assert!(!agg.same_commit_batch_verify(&verifier_params, &com, &[0], &values));
+
+    let com2 = Commitment::new(&prover_params, &values).unwrap();
+    let proof2 = Proof::new(&prover_params, &values, 0).unwrap();
+    let commits = vec![com.clone(), com2];
+    let set = vec![vec![0], vec![0]];
+    let value_sub_vector = vec![new_values.clone(), values.clone()];
+    let cross = Proof::cross_commit_aggregate_full(
+        &commits,
+        &[vec![proof], vec![proof2]],
+        &set,
+        &value_sub_vector,
+        n,
+    )
+    .unwrap();
+    assert!(cross.cross_commit_batch_verify(&verifier_params, &commits, &set, &value_sub_vector));
+    let swapped = vec![values.clone(), new_values.clone()];
+    assert!(!cross.cross_commit_batch_verify(&verifier_params, &commits, &set, &swapped));
+}
+
+#[test]
+fn test_multiexp() {
+    use pairing_plus::CurveAffine;
+    use pairings::pointproofs_groups::{PointproofsG1Affine, PointproofsG2, PointproofsG2Affine};
+
+    // above the threshold of the parallel path
+    let n = 1500;
+    let mut g1_points: Vec<PointproofsG1Affine> = Vec::with_capacity(n);
+    let mut g2_points: Vec<PointproofsG2Affine> = Vec::with_capacity(n);
+    let mut g1 = PointproofsG1::one();
+    let mut g2 = PointproofsG2::one();
+    for _ in 0..n {
+        g1_points.push(g1.into_affine());
+        g2_points.push(g2.into_affine());
+        g1.add_assign(&PointproofsG1::one());
+        g2.add_assign(&PointproofsG2::one());
+    }
+    let scalars: Vec<FrRepr> = (0..n)
+        .map(|i| hash_to_field_repr_pointproofs(format!("this is message number {}", i)))
+        .collect();
+    let scalars_u64: Vec<&[u64; 4]> = scalars.iter().map(|s| &s.0).collect();
+
+    for len in [0usize, 1, 2, 100, n].iter() {
+        assert_eq!(
+            multiexp_g1(&g1_points[..*len], &scalars_u64[..*len]),
+            if *len == 0 {
+                PointproofsG1::zero()
+            } else {
+                PointproofsG1Affine::sum_of_products(&g1_points[..*len], &scalars_u64[..*len])
+            }
+        );
+        assert_eq!(
+            multiexp_g2(&g2_points[..*len], &scalars_u64[..*len]),
+            if *len == 0 {
+                PointproofsG2::zero()
+            } else {
+                PointproofsG2Affine::sum_of_products(&g2_points[..*len], &scalars_u64[..*len])
+            }
+        );
+    }
+
+    // against one exponentiation per point
+    let mut expected = PointproofsG1::zero();
+    for i in 0..100 {
+        expected.add_assign(&g1_points[i].mul(Fr::from_repr(scalars[i]).unwrap()));
+    }
+    assert_eq!(
+        multiexp_g1(&g1_points[..100], &scalars_u64[..100]),
+        expected
+    );
+}
+
+#[test]
+fn test_verify_fr() {
+    use ff::Field;
+
+    let n = 8usize;
+    for ciphersuite in 0..3u8 {
+        let (prover_params, verifier_params) = paramgen_from_seed(
+            "This is Leo's Favourite very very very long Seed",
+            ciphersuite,
+            n,
+        )
+        .unwrap();
+        let values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let scalars = prover_params.hash_values(&values).unwrap();
+        if ciphersuite == 0 {
+            for (scalar, value) in scalars.iter().zip(values.iter()) {
+                assert_eq!(*scalar, hash_to_field_pointproofs(value));
+            }
+        }
+        let com = Commitment::new(&prover_params, &values).unwrap();
+
+        for i in 0..n {
+            let proof = Proof::new(&prover_params, &values, i).unwrap();
+            for j in 0..n {
+                // verify and verify_fr agree on every value
+                assert_eq!(
+                    proof.verify_fr(&verifier_params, &com, &scalars[j], i),
+                    proof.verify(&verifier_params, &com, &values[j], i)
+                );
+            }
+            assert!(proof.verify_fr(&verifier_params, &com, &scalars[i], i));
+            assert!(!proof.verify_fr(&verifier_params, &com, &scalars[i], (i + 1) % n));
+            assert!(!proof.verify_fr(&verifier_params, &com, &scalars[i], n));
+            assert!(!proof.verify_fr(&verifier_params, &com, &Fr::zero(), i));
+        }
+    }
+}
+
+#[test]
+fn test_max_value_len_prove_paths() {
+    let n = 8usize;
+    let (mut prover_params, mut verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let values2: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", n - 1 - i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let com2 = Commitment::new(&prover_params, &values2).unwrap();
+    let set = vec![1usize, 3];
+    let proofs = Proof::batch_new(&prover_params, &values, &set).unwrap();
+    let proofs2 = Proof::batch_new(&prover_params, &values2, &set).unwrap();
+    let opened: Vec<&str> = set.iter().map(|i| values[*i].as_str()).collect();
+    let opened2: Vec<&str> = set.iter().map(|i| values2[*i].as_str()).collect();
+    let agg = Proof::same_commit_aggregate(&com, &proofs, &set, &opened, n).unwrap();
+    let coms = [com.clone(), com2.clone()];
+    let sets = [set.clone(), set.clone()];
+    let opened_sets = [opened.clone(), opened2.clone()];
+    let agg_cross = Proof::cross_commit_aggregate_full(
+        &coms,
+        &[proofs.clone(), proofs2.clone()],
+        &sets,
+        &opened_sets,
+        n,
+    )
+    .unwrap();
+    let openings = [
+        (1, opened[0].as_bytes(), &proofs[0]),
+        (3, opened[1].as_bytes(), &proofs[1]),
+    ];
+
+    // every value is 24 bytes long
+    verifier_params.set_max_value_len(24);
+    assert!(agg.same_commit_batch_verify(&verifier_params, &com, &set, &opened));
+    assert!(Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &openings
+    ));
+    assert!(agg_cross.cross_commit_batch_verify(&verifier_params, &coms, &sets, &opened_sets));
+    assert!(agg_cross.cross_aggregate_verify(
+        &[&verifier_params, &verifier_params],
+        &coms,
+        &sets,
+        &opened_sets
+    ));
+
+    // the batch verifiers reject the values before hashing them
+    verifier_params.set_max_value_len(23);
+    assert!(!agg.same_commit_batch_verify(&verifier_params, &com, &set, &opened));
+    assert!(!Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &openings
+    ));
+    assert!(!agg_cross.cross_commit_batch_verify(&verifier_params, &coms, &sets, &opened_sets));
+    assert!(!agg_cross.cross_aggregate_verify(
+        &[&verifier_params, &verifier_params],
+        &coms,
+        &sets,
+        &opened_sets
+    ));
+
+    // the provers reject a vector with a value longer than 24 bytes
+    prover_params.set_max_value_len(24);
+    let long_value = "this is message number 55".to_string();
+    let mut long_values = values.clone();
+    long_values[5] = long_value.clone();
+    match Proof::batch_new(&prover_params, &long_values, &set) {
+        Err(PointproofsError::ValueTooLong {
+            index: 5,
+            len: 25,
+            max: 24,
+        }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match Proof::new_all(&prover_params, &long_values) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    // below and above the FFT crossover
+    for max_memory in [0, usize::max_value()].iter() {
+        match Proof::new_all_tuned(&prover_params, &long_values, *max_memory) {
+            Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+            _ => panic!("expected PointproofsError::ValueTooLong"),
+        }
+    }
+    match Proof::batch_new_aggregated(&prover_params, &com, &long_values, &set) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+
+    // the updates bound the old and the new values, and leave self untouched
+    let mut proof = proofs[0].clone();
+    match proof.update(&prover_params, 1, 5, &values[5], &long_value) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    match proof.batch_update(
+        &prover_params,
+        1,
+        &[
+            (2, values[2].as_str(), values[3].as_str()),
+            (5, long_value.as_str(), values[5].as_str()),
+        ],
+    ) {
+        Err(PointproofsError::ValueTooLong { index: 5, .. }) => (),
+        _ => panic!("expected PointproofsError::ValueTooLong"),
+    }
+    assert_eq!(proof, proofs[0]);
+}
+
+#[test]
+fn test_mismatched_n_same_commit_batch_verify() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let set = vec![1usize, 4, 7];
+    let proofs: Vec<Proof> = set
+        .iter()
+        .map(|i| Proof::new(&prover_params, &values, *i).unwrap())
+        .collect();
+    let sub_values: Vec<&String> = set.iter().map(|i| &values[*i]).collect();
+    let agg = Proof::same_commit_aggregate(&com, &proofs, &set, &sub_values, n).unwrap();
+    assert!(agg.same_commit_batch_verify(&verifier_params, &com, &set, &sub_values));
+
+    // the recorded n of the commitment or the proof is not the n of the parameters
+    let mut wrong_com = com.clone();
+    wrong_com.n = 2 * n;
+    assert!(!agg.same_commit_batch_verify(&verifier_params, &wrong_com, &set, &sub_values));
+    let mut wrong_agg = agg.clone();
+    wrong_agg.n = 2 * n;
+    assert!(!wrong_agg.same_commit_batch_verify(&verifier_params, &com, &set, &sub_values));
+
+    // an unknown n is only checked against the parameters
+    let com_recover = Commitment::from_bytes(&com.to_bytes()).unwrap();
+    let agg_recover = Proof::from_bytes(&agg.to_bytes()).unwrap();
+    assert!(agg_recover.same_commit_batch_verify(
+        &verifier_params,
+        &com_recover,
+        &set,
+        &sub_values
+    ));
+}
+
+#[test]
+fn test_mismatched_n_batch_verify_same_commit() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proofs: Vec<Proof> = (0..n)
+        .map(|i| Proof::new(&prover_params, &values, i).unwrap())
+        .collect();
+    let openings: Vec<(usize, &[u8], &Proof)> = [1usize, 4, 7]
+        .iter()
+        .map(|i| (*i, values[*i].as_bytes(), &proofs[*i]))
+        .collect();
+    assert!(Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &openings
+    ));
+    assert!(Proof::verify_pairs(
+        &verifier_params,
+        &com,
+        openings.iter().cloned()
+    ));
+
+    // the recorded n of the commitment is not the n of the parameters
+    let mut wrong_com = com.clone();
+    wrong_com.n = 2 * n;
+    assert!(!Proof::batch_verify_same_commit(
+        &verifier_params,
+        &wrong_com,
+        &openings
+    ));
+    assert!(!Proof::verify_pairs(
+        &verifier_params,
+        &wrong_com,
+        openings.iter().cloned()
+    ));
+
+    // nor is the recorded n of one of the proofs
+    let mut wrong_proof = proofs[4].clone();
+    wrong_proof.n = 2 * n;
+    let mut wrong_openings = openings.clone();
+    wrong_openings[1].2 = &wrong_proof;
+    assert!(!Proof::batch_verify_same_commit(
+        &verifier_params,
+        &com,
+        &wrong_openings
+    ));
+    assert!(!Proof::verify_pairs(
+        &verifier_params,
+        &com,
+        wrong_openings.iter().cloned()
+    ));
+}
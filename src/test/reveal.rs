@@ -0,0 +1,87 @@
+use pairing_plus::serdes::SerDes;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_commit_then_reveal() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+
+    // commit phase: only the hashes are published
+    let hashes: Vec<[u8; VALUE_HASH_LEN]> = values.iter().map(HashCommitment::hash_value).collect();
+    let com = HashCommitment::new_from_hashes(&prover_params, &hashes).unwrap();
+    assert_eq!(com, HashCommitment::new(&prover_params, &values).unwrap());
+    // the hash commitment is not a commitment to the values
+    assert_ne!(
+        com.commitment(),
+        &Commitment::new(&prover_params, &values).unwrap()
+    );
+
+    let mut buf: Vec<u8> = vec![];
+    com.serialize(&mut buf, true).unwrap();
+    let com_recover = HashCommitment::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert_eq!(com, com_recover);
+
+    // reveal phase
+    for i in 0..n {
+        let proof = Proof::reveal(&prover_params, &hashes, i, &values[i]).unwrap();
+        assert!(proof.verify_reveal(&verifier_params, &com_recover, i, &values[i]));
+        assert!(!proof.verify_reveal(&verifier_params, &com, (i + 1) % n, &values[i]));
+
+        // a wrong value does not verify
+        let wrong = format!("this is not message number {}", i);
+        assert!(!proof.verify_reveal(&verifier_params, &com, i, &wrong));
+        // the hash itself is not a valid reveal
+        assert!(!proof.verify_reveal(&verifier_params, &com, i, &hashes[i]));
+        match Proof::reveal(&prover_params, &hashes, i, &wrong) {
+            Err(PointproofsError::HashMismatch) => (),
+            _ => panic!("expected PointproofsError::HashMismatch"),
+        }
+    }
+    match Proof::reveal(&prover_params, &hashes, n, &values[0]) {
+        Err(PointproofsError::IndexOutOfRange) => (),
+        _ => panic!("expected PointproofsError::IndexOutOfRange"),
+    }
+}
+
+#[test]
+fn test_reveal_compare() {
+    let n = 4usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values = vec![
+        b"value 0".to_vec(),
+        b"value 1".to_vec(),
+        b"value 2".to_vec(),
+        b"value 3".to_vec(),
+    ];
+    let hashes: Vec<[u8; VALUE_HASH_LEN]> = values.iter().map(HashCommitment::hash_value).collect();
+    let com = HashCommitment::new_from_hashes(&prover_params, &hashes).unwrap();
+
+    // an equal value, in another buffer
+    let equal = values[2].clone();
+    let proof = Proof::reveal(&prover_params, &hashes, 2, &equal).unwrap();
+    assert!(proof.verify_reveal(&verifier_params, &com, 2, &equal));
+
+    // values that differ in the first byte, the last byte, or the length
+    let mut first = values[2].clone();
+    first[0] ^= 1;
+    let mut last = values[2].clone();
+    *last.last_mut().unwrap() ^= 1;
+    let mut longer = values[2].clone();
+    longer.push(0);
+    for wrong in [first, last, longer, vec![]].iter() {
+        match Proof::reveal(&prover_params, &hashes, 2, wrong) {
+            Err(PointproofsError::HashMismatch) => (),
+            _ => panic!("expected PointproofsError::HashMismatch"),
+        }
+        assert!(!proof.verify_reveal(&verifier_params, &com, 2, wrong));
+    }
+}
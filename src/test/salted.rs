@@ -0,0 +1,70 @@
+use pairing_plus::serdes::SerDes;
+use pairings::param::paramgen_from_seed;
+use pairings::pointproofs_groups::COMMIT_LEN;
+use pairings::*;
+use rand::prng::ChaChaRng;
+use rand::SeedableRng;
+
+#[test]
+fn test_salted_commitment() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    // values from a small space
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("{}", i % 2);
+        values.push(s);
+    }
+
+    let com1 = Commitment::new_salted(&prover_params, &values).unwrap();
+    let com2 = Commitment::new_salted(&prover_params, &values).unwrap();
+    let plain = Commitment::new(&prover_params, &values).unwrap();
+    assert_ne!(com1.salt(), com2.salt());
+    assert_ne!(com1.commitment(), com2.commitment());
+    assert_ne!(com1.commitment(), &plain);
+
+    for com in [&com1, &com2].iter() {
+        for i in 0..n {
+            let proof = Proof::new_salted(&prover_params, com, &values, i).unwrap();
+            assert!(proof.verify_salted(&verifier_params, com, &values[i], i));
+            assert!(!proof.verify_salted(&verifier_params, com, &values[(i + 1) % n], i));
+            // the salt is not optional
+            assert!(!proof.verify(&verifier_params, com.commitment(), &values[i], i));
+        }
+    }
+    // a proof for one salt does not verify against another
+    let proof = Proof::new_salted(&prover_params, &com1, &values, 0).unwrap();
+    assert!(!proof.verify_salted(&verifier_params, &com2, &values[0], 0));
+
+    // the salt is serialized with the commitment
+    let mut buf: Vec<u8> = vec![];
+    com1.serialize(&mut buf, true).unwrap();
+    assert_eq!(buf.len(), COMMIT_LEN + SALT_LEN);
+    let com_rec = SaltedCommitment::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    assert_eq!(com_rec, com1);
+    assert!(proof.verify_salted(&verifier_params, &com_rec, &values[0], 0));
+    assert!(SaltedCommitment::deserialize(&mut buf[..buf.len() - 1].as_ref(), true).is_err());
+
+    // the salt comes from the rng
+    let com3 = Commitment::new_salted_with_rng(
+        &prover_params,
+        &values,
+        &mut ChaChaRng::from_seed([0u8; 32]),
+    )
+    .unwrap();
+    let com4 = Commitment::new_salted_with_rng(
+        &prover_params,
+        &values,
+        &mut ChaChaRng::from_seed([0u8; 32]),
+    )
+    .unwrap();
+    assert_eq!(com3, com4);
+    assert_eq!(
+        SaltedCommitment::new_with_salt(&prover_params, &values, *com3.salt()).unwrap(),
+        com3
+    );
+
+    assert!(Commitment::new_salted(&prover_params, &values[1..]).is_err());
+}
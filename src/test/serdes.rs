@@ -1,8 +1,10 @@
 use pairing_plus::serdes::SerDes;
 use pairing_plus::CurveProjective;
-use pairings::param::paramgen_from_seed;
+use pairings::param::{paramgen_from_seed, MAX_N};
 use pairings::pointproofs_groups::*;
 use pairings::*;
+use std::collections::HashSet;
+use std::convert::TryFrom;
 
 #[test]
 fn test_serdes_prover_param() {
@@ -159,9 +161,9 @@ fn test_serdes_commit() {
     let mut buf: Vec<u8> = vec![];
     let valid_commit = Commitment {
         ciphersuite: 0,
+        n: 0,
         commit: PointproofsG1::one(),
     };
-    assert!(valid_commit.serialize(&mut buf, false).is_err());
     assert!(valid_commit.serialize(&mut buf, true).is_ok());
     let len = buf.len();
     let valid_commit_recover = match Commitment::deserialize(&mut buf[..].as_ref(), true) {
@@ -169,10 +171,15 @@ fn test_serdes_commit() {
         Err(e) => panic!("Error deserilization: {}", e),
     };
     assert_eq!(valid_commit, valid_commit_recover);
-    assert!(Commitment::deserialize(&mut buf[..].as_ref(), false).is_err());
+    // the encoding is read from the blob, not from the argument
+    assert_eq!(
+        Commitment::deserialize(&mut buf[..].as_ref(), false).unwrap(),
+        valid_commit
+    );
 
     let invalid_commit = Commitment {
-        ciphersuite: 1,
+        ciphersuite: 3,
+        n: 0,
         commit: PointproofsG1::one(),
     };
     assert!(invalid_commit.serialize(&mut buf, true).is_err());
@@ -190,6 +197,7 @@ fn test_serdes_proof() {
     let mut buf: Vec<u8> = vec![];
     let valid_proof = Proof {
         ciphersuite: 0,
+        n: 0,
         proof: PointproofsG1::one(),
     };
 
@@ -200,10 +208,14 @@ fn test_serdes_proof() {
         Err(e) => panic!("Error deserilization: {}", e),
     };
     assert_eq!(valid_proof, valid_proof_recover);
-    assert!(Proof::deserialize(&mut buf[..].as_ref(), false).is_err());
+    assert_eq!(
+        Proof::deserialize(&mut buf[..].as_ref(), false).unwrap(),
+        valid_proof
+    );
 
     let invalid_proof = Proof {
-        ciphersuite: 1,
+        ciphersuite: 3,
+        n: 0,
         proof: PointproofsG1::one(),
     };
     assert!(invalid_proof.serialize(&mut buf, true).is_err());
@@ -215,3 +227,917 @@ fn test_serdes_proof() {
     assert!(Proof::deserialize(&mut invalid_buf2[..].as_ref(), true).is_err());
     assert!(Proof::deserialize(&mut invalid_buf2[..].as_ref(), false).is_err());
 }
+
+#[test]
+fn test_serdes_precomp_table() {
+    let n = 8;
+    let (prover_params, mut verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    // the table is reloaded as is
+    for window in [3, 16, 256].iter() {
+        let mut pp = prover_params.clone();
+        pp.precomp_window(*window).unwrap();
+        let mut buf: Vec<u8> = vec![];
+        assert!(pp.serialize(&mut buf, true).is_ok());
+        assert_eq!(buf.len(), 9 + 2 * n * (1 + window) * POINTPROOFSG1_LEN);
+        let pp_recover = ProverParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(pp, pp_recover);
+
+        let mut com = Commitment::new(&prover_params, &values).unwrap();
+        let mut com_recover = com.clone();
+        com.update(&pp, 2, &values[2], &values[3]).unwrap();
+        com_recover
+            .update(&pp_recover, 2, &values[2], &values[3])
+            .unwrap();
+        assert_eq!(com, com_recover);
+    }
+
+    // pp_len does not match n: not a multiple of 2n, or a window of 1
+    let mut buf: Vec<u8> = vec![];
+    assert!(prover_params.serialize(&mut buf, true).is_ok());
+    let pp_len_offset = 5 + 2 * n * POINTPROOFSG1_LEN;
+    for pp_len in [5u32, 2 * n as u32, 2 * n as u32 + 1].iter() {
+        let mut invalid_buf = buf[..pp_len_offset].to_vec();
+        invalid_buf.extend_from_slice(&pp_len.to_le_bytes());
+        // any valid points will do for the table
+        for i in 0..*pp_len as usize {
+            let start = 5 + (i % (2 * n)) * POINTPROOFSG1_LEN;
+            invalid_buf.extend_from_slice(&buf[start..start + POINTPROOFSG1_LEN]);
+        }
+        assert!(ProverParams::deserialize(&mut invalid_buf[..].as_ref(), true).is_err());
+    }
+
+    // same for the verifier
+    verifier_params.precomp_3();
+    let mut buf: Vec<u8> = vec![];
+    assert!(verifier_params.serialize(&mut buf, true).is_ok());
+    assert_eq!(buf.len(), 585 + 4 * n * POINTPROOFSG2_LEN);
+    let verifier_params_recover = VerifierParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+    let mut buf_recover: Vec<u8> = vec![];
+    assert!(verifier_params_recover
+        .serialize(&mut buf_recover, true)
+        .is_ok());
+    assert_eq!(buf, buf_recover);
+
+    let pp_len_offset = 5 + n * POINTPROOFSG2_LEN;
+    let mut invalid_buf = buf.clone();
+    invalid_buf[pp_len_offset] = 2;
+    assert!(VerifierParams::deserialize(&mut invalid_buf[..].as_ref(), true).is_err());
+}
+
+#[test]
+fn test_deserialize_v0() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 2).unwrap();
+
+    // a v0 blob is `|ciphersuite id| compressed point |`
+    let mut com_v0: Vec<u8> = vec![0u8];
+    com.commit
+        .into_affine()
+        .serialize(&mut com_v0, true)
+        .unwrap();
+    let mut proof_v0: Vec<u8> = vec![0u8];
+    proof
+        .proof
+        .into_affine()
+        .serialize(&mut proof_v0, true)
+        .unwrap();
+    assert_eq!(com_v0.len(), COMMIT_LEN);
+    assert_eq!(proof_v0.len(), PROOF_LEN);
+
+    let com_recover = Commitment::deserialize_v0(&mut com_v0[..].as_ref()).unwrap();
+    let proof_recover = Proof::deserialize_v0(&mut proof_v0[..].as_ref()).unwrap();
+    assert!(proof_recover.verify(&verifier_params, &com_recover, &values[2], 2));
+
+    // the main deserialization reads v0 blobs
+    assert_eq!(
+        Commitment::deserialize(&mut com_v0[..].as_ref(), true).unwrap(),
+        com_recover
+    );
+    assert_eq!(
+        Proof::deserialize(&mut proof_v0[..].as_ref(), true).unwrap(),
+        proof_recover
+    );
+
+    // the ciphersuite id is still checked
+    let mut bad = com_v0.clone();
+    bad[0] = 0xff;
+    assert!(Commitment::deserialize_v0(&mut bad[..].as_ref()).is_err());
+}
+
+#[test]
+fn test_serdes_params_from_slice() {
+    let n = 8usize;
+    let (mut prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    prover_params.precomp_3();
+
+    let mut pp_buf: Vec<u8> = vec![];
+    prover_params.serialize(&mut pp_buf, true).unwrap();
+    let mut vp_buf: Vec<u8> = vec![];
+    verifier_params.serialize(&mut vp_buf, true).unwrap();
+    assert_eq!(
+        ProverParams::deserialize_from_slice(&pp_buf).unwrap(),
+        prover_params
+    );
+    assert_eq!(
+        VerifierParams::deserialize_from_slice(&vp_buf).unwrap(),
+        verifier_params
+    );
+
+    // trailing bytes
+    pp_buf.push(0);
+    vp_buf.push(0);
+    assert!(ProverParams::deserialize_from_slice(&pp_buf).is_err());
+    assert!(VerifierParams::deserialize_from_slice(&vp_buf).is_err());
+
+    // a 12-byte blob claiming n = 65535 fails before reading any point
+    let mut header = vec![0u8; 12];
+    header[1..5].copy_from_slice(&65535u32.to_le_bytes());
+    match ProverParams::deserialize_from_slice(&header) {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+        _ => panic!("expected an error"),
+    }
+    match VerifierParams::deserialize_from_slice(&header) {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+        _ => panic!("expected an error"),
+    }
+    assert!(ProverParams::deserialize(&mut header[..].as_ref(), true).is_err());
+    assert!(VerifierParams::deserialize(&mut header[..].as_ref(), true).is_err());
+
+    // n above the bound, or 0
+    for bad_n in [MAX_N as u32 + 1, 0u32].iter() {
+        header[1..5].copy_from_slice(&bad_n.to_le_bytes());
+        match ProverParams::deserialize_from_slice(&header) {
+            Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+            _ => panic!("expected an error"),
+        }
+        assert!(VerifierParams::deserialize_from_slice(&header).is_err());
+    }
+}
+
+#[test]
+fn test_serdes_bytes_helpers() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 4).unwrap();
+
+    // to_bytes is serialize with compressed = true
+    let mut com_buf: Vec<u8> = vec![];
+    com.serialize(&mut com_buf, true).unwrap();
+    assert_eq!(com.to_bytes(), com_buf);
+    let mut proof_buf: Vec<u8> = vec![];
+    proof.serialize(&mut proof_buf, true).unwrap();
+    assert_eq!(proof.to_bytes(), proof_buf);
+    let mut vp_buf: Vec<u8> = vec![];
+    verifier_params.serialize(&mut vp_buf, true).unwrap();
+    assert_eq!(verifier_params.to_bytes(), vp_buf);
+
+    // round trips, with from_bytes and with TryFrom
+    let com_bytes = com.to_bytes();
+    let proof_bytes = proof.to_bytes();
+    let vp_bytes = verifier_params.to_bytes();
+    assert_eq!(Commitment::from_bytes(&com_bytes).unwrap(), com);
+    assert_eq!(Proof::from_bytes(&proof_bytes).unwrap(), proof);
+    assert_eq!(
+        VerifierParams::from_bytes(&vp_bytes).unwrap(),
+        verifier_params
+    );
+    assert_eq!(Commitment::try_from(&com_bytes[..]).unwrap(), com);
+    assert_eq!(Proof::try_from(&proof_bytes[..]).unwrap(), proof);
+    assert_eq!(
+        VerifierParams::try_from(&vp_bytes[..]).unwrap(),
+        verifier_params
+    );
+    let recovered_vp = VerifierParams::from_bytes(&vp_bytes).unwrap();
+    let recovered_com = Commitment::from_bytes(&com_bytes).unwrap();
+    let recovered_proof = Proof::from_bytes(&proof_bytes).unwrap();
+    assert!(recovered_proof.verify(&recovered_vp, &recovered_com, &values[4], 4));
+
+    // truncated and trailing bytes
+    assert!(Commitment::from_bytes(&com_bytes[..com_bytes.len() - 1]).is_err());
+    assert!(Proof::from_bytes(&proof_bytes[..proof_bytes.len() - 1]).is_err());
+    assert!(VerifierParams::from_bytes(&vp_bytes[..vp_bytes.len() - 1]).is_err());
+    assert!(Commitment::from_bytes(&[com_bytes.clone(), vec![0]].concat()).is_err());
+    assert!(Proof::from_bytes(&[proof_bytes.clone(), vec![0]].concat()).is_err());
+    assert!(VerifierParams::from_bytes(&[vp_bytes.clone(), vec![0]].concat()).is_err());
+    assert!(Commitment::try_from(&[][..]).is_err());
+
+    // invalid ciphersuite or points
+    let mut bad = com_bytes.clone();
+    bad[0] = 3;
+    assert!(Commitment::from_bytes(&bad).is_err());
+    assert!(Proof::try_from(&vec![0u8; proof_bytes.len()][..]).is_err());
+}
+
+#[test]
+fn test_serdes_versioned() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 4).unwrap();
+
+    // round trips; the payload is the raw serialization
+    let mut buf: Vec<u8> = vec![];
+    com.serialize_versioned(&mut buf).unwrap();
+    assert_eq!(buf[0..4], ENVELOPE_MAGIC);
+    assert_eq!(buf[4], ENVELOPE_VERSION);
+    assert_eq!(&buf[10..], &com.to_bytes()[..]);
+    assert_eq!(
+        Commitment::deserialize_versioned(&mut buf[..].as_ref()).unwrap(),
+        com
+    );
+
+    // several envelopes in a row; a reader can skip one by its header
+    let mut stream: Vec<u8> = vec![];
+    proof.serialize_versioned(&mut stream).unwrap();
+    verifier_params.serialize_versioned(&mut stream).unwrap();
+    prover_params.serialize_versioned(&mut stream).unwrap();
+    let mut reader = &stream[..];
+    assert_eq!(Proof::deserialize_versioned(&mut reader).unwrap(), proof);
+    let mut len = [0u8; 4];
+    len.copy_from_slice(&reader[6..10]);
+    let skip = 10 + u32::from_le_bytes(len) as usize;
+    let recovered_vp = VerifierParams::deserialize_versioned(&mut reader).unwrap();
+    assert_eq!(recovered_vp, verifier_params);
+    assert_eq!(
+        ProverParams::deserialize_versioned(&mut reader).unwrap(),
+        prover_params
+    );
+    assert!(reader.is_empty());
+    let mut reader = &stream[..];
+    Proof::deserialize_versioned(&mut reader).unwrap();
+    assert_eq!(
+        ProverParams::deserialize_versioned(&mut reader[skip..].as_ref()).unwrap(),
+        prover_params
+    );
+
+    // the kind must match
+    assert!(Proof::deserialize_versioned(&mut buf[..].as_ref()).is_err());
+
+    // unknown version
+    let mut bad = buf.clone();
+    bad[4] = ENVELOPE_VERSION + 1;
+    match Commitment::deserialize_versioned(&mut bad[..].as_ref()) {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidData),
+        Ok(_) => panic!("expected an unknown version error"),
+    }
+
+    // bad magic
+    let mut bad = buf.clone();
+    bad[0] ^= 1;
+    assert!(Commitment::deserialize_versioned(&mut bad[..].as_ref()).is_err());
+
+    // the length must match the payload
+    let mut bad = buf.clone();
+    bad[6] += 1;
+    match Commitment::deserialize_versioned(&mut bad[..].as_ref()) {
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+        Ok(_) => panic!("expected a truncated payload error"),
+    }
+    let mut bad = buf.clone();
+    bad.push(0);
+    bad[6] += 1;
+    assert!(Commitment::deserialize_versioned(&mut bad[..].as_ref()).is_err());
+    let mut bad = buf.clone();
+    bad[6] -= 1;
+    assert!(Commitment::deserialize_versioned(&mut bad[..].as_ref()).is_err());
+    assert!(Commitment::deserialize_versioned(&mut buf[..buf.len() - 1].as_ref()).is_err());
+    assert!(Commitment::deserialize_versioned(&mut buf[..5].as_ref()).is_err());
+}
+
+#[test]
+fn test_commitment_proof_eq_hash() {
+    let n = 8usize;
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let (prover_params, _) = paramgen_from_seed(seed, 0, n).unwrap();
+    let (prover_params1, _) = paramgen_from_seed(seed, 1, n).unwrap();
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let mut new_values = values.clone();
+    new_values[3] = "this is new message number 3".to_string();
+
+    // the same commitment, computed in two ways, so that the projective
+    // coordinates of the points differ
+    let com = Commitment::new(&prover_params, &new_values).unwrap();
+    let mut com_updated = Commitment::new(&prover_params, &values).unwrap();
+    com_updated
+        .update(&prover_params, 3, &values[3], &new_values[3])
+        .unwrap();
+    assert_eq!(com, com_updated);
+    let proof = Proof::new(&prover_params, &new_values, 5).unwrap();
+    let mut proof_updated = Proof::new(&prover_params, &values, 5).unwrap();
+    proof_updated
+        .update(&prover_params, 5, 3, &values[3], &new_values[3])
+        .unwrap();
+    assert_eq!(proof, proof_updated);
+
+    let com_other = Commitment::new(&prover_params, &values).unwrap();
+    let com_cs1 = Commitment::new(&prover_params1, &new_values).unwrap();
+    assert_ne!(com, com_other);
+    assert_ne!(com, com_cs1);
+
+    // dedup in a HashSet
+    let mut coms = HashSet::new();
+    assert!(coms.insert(com.clone()));
+    assert!(!coms.insert(com_updated.clone()));
+    assert!(coms.insert(com_other));
+    assert!(coms.insert(com_cs1));
+    assert_eq!(coms.len(), 3);
+    assert!(coms.contains(&com_updated));
+
+    let mut proofs = HashSet::new();
+    assert!(proofs.insert(proof));
+    assert!(!proofs.insert(proof_updated));
+    assert!(proofs.insert(Proof::new(&prover_params, &new_values, 6).unwrap()));
+    assert_eq!(proofs.len(), 2);
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_prover_params_from_mmap() {
+    let n = 32usize;
+    let (mut prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    prover_params.precomp_3();
+    let mut buf: Vec<u8> = vec![];
+    prover_params.serialize(&mut buf, true).unwrap();
+
+    let path = std::env::temp_dir().join(format!("pointproofs_mmap_{}.param", std::process::id()));
+    std::fs::write(&path, &buf).unwrap();
+    let recovered = ProverParams::from_mmap(&path).unwrap();
+    assert_eq!(recovered, prover_params);
+
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&recovered, &values).unwrap();
+    let proof = Proof::new(&recovered, &values, 7).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[7], 7));
+
+    // truncated, trailing or corrupted files are rejected
+    std::fs::write(&path, &buf[..buf.len() - 1]).unwrap();
+    assert!(ProverParams::from_mmap(&path).is_err());
+    std::fs::write(&path, &[buf.clone(), vec![0]].concat()).unwrap();
+    assert!(ProverParams::from_mmap(&path).is_err());
+    let mut bad = buf.clone();
+    bad[5] ^= 0xff;
+    std::fs::write(&path, &bad).unwrap();
+    assert!(ProverParams::from_mmap(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+    assert!(ProverParams::from_mmap(&path).is_err());
+}
+
+#[test]
+fn test_fingerprints() {
+    use sha2::{Digest, Sha256};
+
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let (prover_params1, verifier_params1) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 1, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    // the fingerprint is the SHA-256 of the compressed serialization
+    let sha256 = |bytes: &[u8]| {
+        let mut hasher = Sha256::new();
+        hasher.input(bytes);
+        hasher.result().to_vec()
+    };
+    assert_eq!(com.fingerprint().to_vec(), sha256(&com.to_bytes()));
+    let mut buf: Vec<u8> = vec![];
+    prover_params.serialize(&mut buf, true).unwrap();
+    assert_eq!(prover_params.fingerprint().to_vec(), sha256(&buf));
+    assert_eq!(
+        verifier_params.fingerprint().to_vec(),
+        sha256(&verifier_params.to_bytes())
+    );
+
+    // re-parsing yields the same fingerprint
+    let com2 = Commitment::from_bytes(&com.to_bytes()).unwrap();
+    assert_eq!(com.fingerprint(), com2.fingerprint());
+    let pp2 = ProverParams::deserialize_from_slice(&buf).unwrap();
+    assert_eq!(prover_params.fingerprint(), pp2.fingerprint());
+    let vp2 = VerifierParams::from_bytes(&verifier_params.to_bytes()).unwrap();
+    assert_eq!(verifier_params.fingerprint(), vp2.fingerprint());
+
+    // and different objects have different fingerprints
+    let mut new_values = values.clone();
+    new_values[0] = "this is new message number 0".to_string();
+    let new_com = Commitment::new(&prover_params, &new_values).unwrap();
+    assert_ne!(com.fingerprint(), new_com.fingerprint());
+    let com1 = Commitment::new(&prover_params1, &values).unwrap();
+    assert_ne!(com.fingerprint(), com1.fingerprint());
+    assert_ne!(prover_params.fingerprint(), prover_params1.fingerprint());
+    assert_ne!(
+        verifier_params.fingerprint(),
+        verifier_params1.fingerprint()
+    );
+    let mut pp3 = prover_params.clone();
+    pp3.precomp_3();
+    assert_ne!(prover_params.fingerprint(), pp3.fingerprint());
+}
+
+#[test]
+fn test_serdes_uncompressed() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 2).unwrap();
+
+    // compressed blobs are unchanged, uncompressed ones are flagged
+    let mut compressed: Vec<u8> = vec![];
+    com.serialize(&mut compressed, true).unwrap();
+    let mut uncompressed: Vec<u8> = vec![];
+    com.serialize(&mut uncompressed, false).unwrap();
+    assert_eq!(compressed, com.to_bytes());
+    assert_eq!(compressed[0], 0);
+    assert_eq!(uncompressed[0], UNCOMPRESSED_POINT_FLAG);
+    assert_eq!(uncompressed.len(), 2 * compressed.len() - 1);
+
+    // both encodings round-trip, whatever compressed is passed in
+    for &flag in [true, false].iter() {
+        assert_eq!(
+            Commitment::deserialize(&mut compressed[..].as_ref(), flag).unwrap(),
+            com
+        );
+        assert_eq!(
+            Commitment::deserialize(&mut uncompressed[..].as_ref(), flag).unwrap(),
+            com
+        );
+    }
+
+    let mut proof_buf: Vec<u8> = vec![];
+    proof.serialize(&mut proof_buf, false).unwrap();
+    assert_eq!(proof_buf[0], UNCOMPRESSED_POINT_FLAG);
+    let proof_recover = Proof::deserialize(&mut proof_buf[..].as_ref(), false).unwrap();
+    assert_eq!(proof, proof_recover);
+    assert!(proof_recover.verify(&verifier_params, &com, &values[2], 2));
+
+    // the flag must match the encoding of the point
+    let mut bad = uncompressed.clone();
+    bad[0] = 0;
+    assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+    let mut bad = compressed.clone();
+    bad[0] = UNCOMPRESSED_POINT_FLAG;
+    assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+    // and the ciphersuite is still checked under the flag
+    let mut bad = uncompressed.clone();
+    bad[0] = 3 | UNCOMPRESSED_POINT_FLAG;
+    assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+    // an uncompressed point off the curve is rejected
+    let mut bad = uncompressed.clone();
+    bad[uncompressed.len() - 1] ^= 1;
+    assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+
+    // salted commitments carry the encoding of their commitment
+    let salted = SaltedCommitment::new_with_salt(&prover_params, &values, [7u8; SALT_LEN]).unwrap();
+    let mut salted_buf: Vec<u8> = vec![];
+    salted.serialize(&mut salted_buf, false).unwrap();
+    assert_eq!(salted_buf[0], UNCOMPRESSED_POINT_FLAG);
+    assert_eq!(
+        SaltedCommitment::deserialize(&mut salted_buf[..].as_ref(), true).unwrap(),
+        salted
+    );
+}
+
+#[test]
+fn test_serdes_non_canonical() {
+    // the modulus of the base field of BLS12-381
+    const MODULUS: [u8; 48] = [
+        0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac,
+        0xd7, 0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0,
+        0xf6, 0x24, 0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff,
+        0xff, 0xaa, 0xab,
+    ];
+    // x + modulus, if it still fits below the flag bits
+    let add_modulus = |x: &[u8]| -> Option<Vec<u8>> {
+        let mut res = vec![0u8; 48];
+        let mut carry = 0u16;
+        for i in (0..48).rev() {
+            let sum = u16::from(x[i]) + u16::from(MODULUS[i]) + carry;
+            res[i] = sum as u8;
+            carry = sum >> 8;
+        }
+        if carry != 0 || res[0] & 0xe0 != 0 {
+            None
+        } else {
+            Some(res)
+        }
+    };
+
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 0).unwrap();
+
+    // canonical blobs are accepted, in both encodings
+    for &compressed in [true, false].iter() {
+        let mut buf: Vec<u8> = vec![];
+        com.serialize(&mut buf, compressed).unwrap();
+        assert_eq!(
+            Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap(),
+            com
+        );
+        let mut buf: Vec<u8> = vec![];
+        proof.serialize(&mut buf, compressed).unwrap();
+        let proof_recover = Proof::deserialize(&mut buf[..].as_ref(), true).unwrap();
+        assert!(proof_recover.verify(&verifier_params, &com, &values[0], 0));
+    }
+
+    // the (first) coordinate plus the modulus encodes the same point
+    let mut tested = 0;
+    for i in 0..64 {
+        let values: Vec<String> = (0..n)
+            .map(|j| format!("this is message number {}", i * n + j))
+            .collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        for &compressed in [true, false].iter() {
+            let mut buf: Vec<u8> = vec![];
+            com.serialize(&mut buf, compressed).unwrap();
+            let flags = buf[1] & 0xe0;
+            let mut x = buf[1..49].to_vec();
+            x[0] &= 0x1f;
+            if let Some(mut x) = add_modulus(&x) {
+                x[0] |= flags;
+                let mut bad = buf.clone();
+                bad[1..49].copy_from_slice(&x);
+                assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+                assert!(Proof::deserialize(&mut bad[..].as_ref(), true).is_err());
+                assert!(Commitment::deserialize_v0(&mut bad[..].as_ref()).is_err());
+                tested += 1;
+            }
+        }
+    }
+    assert!(tested > 0);
+
+    // the point at infinity with stray bits
+    let mut identity = vec![0u8; COMMIT_LEN];
+    identity[1] = 0xc0;
+    let mut bad = identity.clone();
+    bad[COMMIT_LEN - 1] = 1;
+    assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+    assert!(Proof::deserialize(&mut bad[..].as_ref(), true).is_err());
+    let mut bad = identity.clone();
+    bad[1] |= 0x20;
+    assert!(Commitment::deserialize(&mut bad[..].as_ref(), true).is_err());
+    assert!(Proof::deserialize(&mut bad[..].as_ref(), true).is_err());
+}
+
+#[test]
+fn test_serdes_identity() {
+    let identity = Commitment {
+        ciphersuite: 0,
+        n: 0,
+        commit: PointproofsG1::zero(),
+    };
+    let generator = Commitment {
+        ciphersuite: 0,
+        n: 0,
+        commit: PointproofsG1::one(),
+    };
+    for &compressed in [true, false].iter() {
+        // the identity is rejected as a commitment
+        let mut buf: Vec<u8> = vec![];
+        identity.serialize(&mut buf, compressed).unwrap();
+        assert!(Commitment::deserialize(&mut buf[..].as_ref(), true).is_err());
+        let mut salted = buf.clone();
+        salted.extend_from_slice(&[0u8; SALT_LEN]);
+        assert!(SaltedCommitment::deserialize(&mut salted[..].as_ref(), true).is_err());
+        if compressed {
+            assert!(Commitment::deserialize_v0(&mut buf[..].as_ref()).is_err());
+        }
+
+        // but not as a proof, since it is the proof when n = 1
+        let identity_proof = Proof {
+            ciphersuite: 0,
+            n: 0,
+            proof: PointproofsG1::zero(),
+        };
+        assert_eq!(
+            Proof::deserialize(&mut buf[..].as_ref(), true).unwrap(),
+            identity_proof
+        );
+        if compressed {
+            assert_eq!(
+                Proof::deserialize_v0(&mut buf[..].as_ref()).unwrap(),
+                identity_proof
+            );
+        }
+
+        // the generator is a legal value
+        let mut buf: Vec<u8> = vec![];
+        generator.serialize(&mut buf, compressed).unwrap();
+        assert_eq!(
+            Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap(),
+            generator
+        );
+    }
+}
+
+#[test]
+fn test_serdes_serialized_len() {
+    assert_eq!(Commitment::serialized_len(true), COMMIT_LEN);
+    assert_eq!(Proof::serialized_len(true), PROOF_LEN);
+
+    for n in [1usize, 8, 33].iter() {
+        let (prover_params, _verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *n).unwrap();
+        let values: Vec<String> = (0..*n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proof = Proof::new(&prover_params, &values, 0).unwrap();
+        for compressed in [true, false].iter() {
+            let mut buf: Vec<u8> = vec![];
+            com.serialize(&mut buf, *compressed).unwrap();
+            assert_eq!(Commitment::serialized_len(*compressed), buf.len());
+            let mut buf: Vec<u8> = vec![];
+            proof.serialize(&mut buf, *compressed).unwrap();
+            assert_eq!(Proof::serialized_len(*compressed), buf.len());
+        }
+    }
+}
+
+#[test]
+fn test_deserialize_unchecked() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+
+    for i in 0..n {
+        let proof = Proof::new(&prover_params, &values, i).unwrap();
+        let bytes = proof.to_bytes();
+        let proof_recover = Proof::deserialize_unchecked(&mut bytes[..].as_ref()).unwrap();
+        assert_eq!(proof_recover, proof);
+        assert!(Proof::verify_unchecked(
+            &verifier_params,
+            &com,
+            &bytes,
+            &values[i],
+            i
+        ));
+        assert!(!Proof::verify_unchecked(
+            &verifier_params,
+            &com,
+            &bytes,
+            &values[(i + 1) % n],
+            i
+        ));
+        // trailing bytes are rejected
+        let mut long = bytes.clone();
+        long.push(0);
+        assert!(!Proof::verify_unchecked(
+            &verifier_params,
+            &com,
+            &long,
+            &values[i],
+            i
+        ));
+        // the uncompressed encoding is still checked in full
+        let mut buf: Vec<u8> = vec![];
+        proof.serialize(&mut buf, false).unwrap();
+        let proof_recover = Proof::deserialize_unchecked(&mut buf[..].as_ref()).unwrap();
+        assert_eq!(proof_recover, proof);
+    }
+
+    // almost every point on the curve is outside the subgroup:
+    // find one with a small x, which only the checked path rejects
+    let mut found = false;
+    for x in 0..=255u8 {
+        let mut bytes = vec![0u8; PROOF_LEN];
+        bytes[1] = 0x80;
+        bytes[PROOF_LEN - 1] = x;
+        let unchecked = Proof::deserialize_unchecked(&mut bytes[..].as_ref());
+        if unchecked.is_ok() && Proof::deserialize(&mut bytes[..].as_ref(), true).is_err() {
+            found = true;
+            break;
+        }
+    }
+    assert!(found, "no point outside the subgroup found");
+
+    // x is not on the curve, or above the modulus
+    let mut bytes = vec![0xffu8; PROOF_LEN];
+    bytes[0] = 0;
+    bytes[1] = 0x9f;
+    assert!(Proof::deserialize_unchecked(&mut bytes[..].as_ref()).is_err());
+}
+
+#[test]
+#[cfg(not(feature = "group_switched"))]
+fn test_serdes_subgroup_check_failed() {
+    use pairings::param::read_param;
+
+    // (0, 2) is on the curve y^2 = x^3 + 4, and has order 3
+    let mut compressed = vec![0u8; COMMIT_LEN];
+    compressed[1] = 0x80;
+    let mut uncompressed = vec![0u8; 1 + 2 * POINTPROOFSG1_LEN];
+    uncompressed[0] = UNCOMPRESSED_POINT_FLAG;
+    uncompressed[2 * POINTPROOFSG1_LEN] = 2;
+    for buf in [compressed.clone(), uncompressed.clone()].iter() {
+        match Commitment::deserialize(&mut buf[..].as_ref(), true) {
+            Err(e) => match PointproofsError::from(e) {
+                PointproofsError::SubgroupCheckFailed => (),
+                _ => panic!("expected PointproofsError::SubgroupCheckFailed"),
+            },
+            _ => panic!("expected PointproofsError::SubgroupCheckFailed"),
+        }
+        match Proof::deserialize(&mut buf[..].as_ref(), true) {
+            Err(e) => match PointproofsError::from(e) {
+                PointproofsError::SubgroupCheckFailed => (),
+                _ => panic!("expected PointproofsError::SubgroupCheckFailed"),
+            },
+            _ => panic!("expected PointproofsError::SubgroupCheckFailed"),
+        }
+    }
+
+    // the io error keeps the message, but the mapping follows its payload
+    match Commitment::deserialize(&mut compressed[..].as_ref(), true) {
+        Err(e) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(
+                e.to_string(),
+                PointproofsError::SubgroupCheckFailed.to_string()
+            );
+        }
+        _ => panic!("expected an error"),
+    }
+    let e = std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        PointproofsError::SubgroupCheckFailed.to_string(),
+    );
+    match PointproofsError::from(e) {
+        PointproofsError::Serde(_) => (),
+        _ => panic!("expected PointproofsError::Serde"),
+    }
+
+    // (0, 3) is not on the curve, which is another error
+    uncompressed[2 * POINTPROOFSG1_LEN] = 3;
+    match Commitment::deserialize(&mut uncompressed[..].as_ref(), true) {
+        Err(e) => match PointproofsError::from(e) {
+            PointproofsError::Serde(_) => (),
+            _ => panic!("expected PointproofsError::Serde"),
+        },
+        _ => panic!("expected PointproofsError::Serde"),
+    }
+
+    // the same point as the first generator of the params
+    let mut params = std::fs::read("crs.param").unwrap();
+    params[4..4 + POINTPROOFSG1_LEN].copy_from_slice(&compressed[1..]);
+    match read_param(&mut params[..].as_ref()) {
+        Err(PointproofsError::SubgroupCheckFailed) => (),
+        _ => panic!("expected PointproofsError::SubgroupCheckFailed"),
+    }
+}
+
+#[test]
+fn test_serdes_strict_encoding() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 0).unwrap();
+
+    for &compressed in [true, false].iter() {
+        let mut com_buf: Vec<u8> = vec![];
+        com.serialize(&mut com_buf, compressed).unwrap();
+        let mut proof_buf: Vec<u8> = vec![];
+        proof.serialize(&mut proof_buf, compressed).unwrap();
+
+        // the encoding that was written is read back
+        assert_eq!(
+            Commitment::deserialize_strict(&mut com_buf[..].as_ref(), compressed).unwrap(),
+            com
+        );
+        assert_eq!(
+            Proof::deserialize_strict(&mut proof_buf[..].as_ref(), compressed).unwrap(),
+            proof
+        );
+
+        // the other one is rejected, while deserialize reads both
+        assert!(Commitment::deserialize_strict(&mut com_buf[..].as_ref(), !compressed).is_err());
+        assert!(Proof::deserialize_strict(&mut proof_buf[..].as_ref(), !compressed).is_err());
+        assert_eq!(
+            Commitment::deserialize(&mut com_buf[..].as_ref(), !compressed).unwrap(),
+            com
+        );
+    }
+}
+
+#[test]
+fn test_from_slice() {
+    let n = 16;
+    let (prover_params, mut verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+
+    // the slice parsers read what serialize writes
+    let vp = VerifierParams::from_slice(&verifier_params.to_bytes()).unwrap();
+    assert_eq!(vp, verifier_params);
+    assert!(proof.verify(&vp, &com, &values[3], 3));
+    verifier_params.precomp_3();
+    let bytes = verifier_params.to_bytes();
+    assert_eq!(
+        VerifierParams::from_slice(&bytes).unwrap().to_bytes(),
+        bytes
+    );
+    for compressed in [true, false].iter() {
+        let mut com_buf: Vec<u8> = vec![];
+        com.serialize(&mut com_buf, *compressed).unwrap();
+        assert_eq!(Commitment::from_slice(&com_buf).unwrap(), com);
+        let mut proof_buf: Vec<u8> = vec![];
+        proof.serialize(&mut proof_buf, *compressed).unwrap();
+        assert_eq!(Proof::from_slice(&proof_buf).unwrap(), proof);
+    }
+    let recovered = Commitment::from_slice(&com.to_bytes_with_n().unwrap()).unwrap();
+    assert_eq!(recovered.n, n);
+    let recovered = Proof::from_slice(&proof.to_bytes_with_n().unwrap()).unwrap();
+    assert_eq!(recovered.n, n);
+
+    // trailing and missing bytes
+    let mut com_buf = com.to_bytes();
+    com_buf.push(0);
+    match Commitment::from_slice(&com_buf) {
+        Err(PointproofsError::InvalidEncoding(_)) => (),
+        _ => panic!("expected PointproofsError::InvalidEncoding"),
+    }
+    match Commitment::from_slice(&com_buf[..COMMIT_LEN - 1]) {
+        Err(PointproofsError::InvalidEncoding(_)) => (),
+        _ => panic!("expected PointproofsError::InvalidEncoding"),
+    }
+    let bytes = vp.to_bytes();
+    match VerifierParams::from_slice(&bytes[..bytes.len() - 1]) {
+        Err(PointproofsError::InvalidEncoding(_)) => (),
+        _ => panic!("expected PointproofsError::InvalidEncoding"),
+    }
+
+    // the ciphersuite and n are checked
+    com_buf[0] = 5;
+    match Commitment::from_slice(&com_buf[..COMMIT_LEN]) {
+        Err(PointproofsError::InvalidCiphersuite) => (),
+        _ => panic!("expected PointproofsError::InvalidCiphersuite"),
+    }
+    let mut bytes = vp.to_bytes();
+    bytes[1..5].copy_from_slice(&0u32.to_le_bytes());
+    match VerifierParams::from_slice(&bytes) {
+        Err(PointproofsError::InvalidN) => (),
+        _ => panic!("expected PointproofsError::InvalidN"),
+    }
+
+    // the identity is a proof, but not a commitment
+    let mut identity = vec![0u8; COMMIT_LEN];
+    identity[1] = 0xc0;
+    match Commitment::from_slice(&identity) {
+        Err(PointproofsError::InvalidEncoding(_)) => (),
+        _ => panic!("expected PointproofsError::InvalidEncoding"),
+    }
+    assert!(Proof::from_slice(&identity).is_ok());
+}
@@ -0,0 +1,72 @@
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_sliding_commitment() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut sliding = SlidingCommitment::new(&prover_params).unwrap();
+    let mut stream: Vec<String> = vec![];
+    for i in 0..2 * n {
+        let s = format!("this is message number {}", i);
+        let com = sliding.push(&prover_params, &s).unwrap().clone();
+        stream.push(s);
+
+        // the commitment matches a fresh one over the window
+        assert_eq!(
+            com,
+            Commitment::new(&prover_params, sliding.values()).unwrap()
+        );
+    }
+    assert_eq!(sliding.pushed(), 2 * n);
+
+    // the most recent n entries verify
+    let com = sliding.commitment();
+    for (position, value) in stream.iter().enumerate().skip(n) {
+        let index = sliding.index_of(position).unwrap();
+        assert_eq!(index, position % n);
+        let proof = sliding.prove(&prover_params, position).unwrap();
+        assert!(proof.verify(&verifier_params, com, value, index));
+    }
+
+    // the evicted ones do not
+    for position in 0..n {
+        assert!(sliding.index_of(position).is_none());
+        assert!(sliding.prove(&prover_params, position).is_err());
+        let proof = sliding.prove(&prover_params, position + n).unwrap();
+        assert!(!proof.verify(&verifier_params, com, &stream[position], position % n));
+    }
+    assert!(sliding.index_of(2 * n).is_none());
+}
+
+#[test]
+fn test_sliding_commitment_partial_window() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut sliding = SlidingCommitment::new(&prover_params).unwrap();
+    assert!(sliding.index_of(0).is_none());
+    for i in 0..3 {
+        sliding
+            .push(&prover_params, format!("this is message number {}", i))
+            .unwrap();
+    }
+    for position in 0..3 {
+        let proof = sliding.prove(&prover_params, position).unwrap();
+        assert!(proof.verify(
+            &verifier_params,
+            sliding.commitment(),
+            format!("this is message number {}", position),
+            position
+        ));
+    }
+    assert!(sliding.index_of(3).is_none());
+
+    // parameters of another size
+    let (prover_params2, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 4).unwrap();
+    assert!(sliding.push(&prover_params2, "some value").is_err());
+}
@@ -0,0 +1,74 @@
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_params_store() {
+    let (master, _vp16) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 16).unwrap();
+    // same alpha, hence a truncation of the master
+    let (truncated, vp8) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, 8).unwrap();
+    let (other, _vp) =
+        paramgen_from_seed("This is another very very very very long Seed", 0, 8).unwrap();
+
+    let mut store = ParamsStore::new();
+    assert!(store.is_empty());
+    let fp_master = store.insert(&master);
+    assert_eq!(store.num_points(), 32);
+
+    // every point of the truncation is already in the store
+    let fp_truncated = store.insert(&truncated);
+    assert_ne!(fp_master, fp_truncated);
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.num_points(), 32);
+
+    // inserting again changes nothing
+    assert_eq!(store.insert(&truncated), fp_truncated);
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.num_points(), 32);
+
+    // unrelated parameters only share the placeholder at index n
+    let fp_other = store.insert(&other);
+    assert_eq!(store.len(), 3);
+    assert_eq!(store.num_points(), 32 + 15);
+
+    // the parameters come back as they were
+    let mut values: Vec<String> = vec![];
+    for i in 0..16 {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let pp16 = store.get(&fp_master).unwrap();
+    assert_eq!(
+        Commitment::new(&pp16, &values).unwrap(),
+        Commitment::new(&master, &values).unwrap()
+    );
+    let pp8 = store.get(&fp_truncated).unwrap();
+    let com = Commitment::new(&pp8, &values[..8]).unwrap();
+    assert_eq!(com, Commitment::new(&truncated, &values[..8]).unwrap());
+    let proof = Proof::new(&pp8, &values[..8], 3).unwrap();
+    assert!(proof.verify(&vp8, &com, &values[3], 3));
+    let pp_other = store.get(&fp_other).unwrap();
+    assert_eq!(
+        Commitment::new(&pp_other, &values[..8]).unwrap(),
+        Commitment::new(&other, &values[..8]).unwrap()
+    );
+
+    assert!(store.get(&[0u8; 32]).is_none());
+
+    // the store is keyed by the fingerprint of the parameters
+    assert_eq!(fp_master, master.fingerprint());
+    assert_eq!(fp_truncated, truncated.fingerprint());
+    assert_eq!(store.get(&other.fingerprint()).unwrap(), other);
+    assert_eq!(pp8.fingerprint(), fp_truncated);
+
+    // with a pre-computed table, the fingerprint covers the table,
+    // and the parameters come back without it
+    let mut precomp = truncated.clone();
+    precomp.precomp_3();
+    let fp_precomp = store.insert(&precomp);
+    assert_eq!(fp_precomp, precomp.fingerprint());
+    assert_ne!(fp_precomp, fp_truncated);
+    assert_eq!(store.num_points(), 32 + 15);
+    assert_eq!(store.get(&fp_precomp).unwrap(), truncated);
+}
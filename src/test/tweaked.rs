@@ -0,0 +1,60 @@
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_tweaked_commitment() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let tweaks: [&[u8]; 3] = [b"", b"tweak 1", b"tweak 2"];
+    let plain = Commitment::new(&prover_params, &values).unwrap();
+    let coms: Vec<Commitment> = tweaks
+        .iter()
+        .map(|t| Commitment::new_tweaked(&prover_params, &values, t).unwrap())
+        .collect();
+
+    // deterministic, and unrelated across tweaks
+    assert_eq!(
+        coms[1],
+        Commitment::new_tweaked(&prover_params, &values, tweaks[1]).unwrap()
+    );
+    for (j, com) in coms.iter().enumerate() {
+        assert_ne!(com, &plain);
+        for other in coms[j + 1..].iter() {
+            assert_ne!(com, other);
+        }
+    }
+
+    for i in 0..n {
+        for (j, com) in coms.iter().enumerate() {
+            let proof = Proof::new_tweaked(&prover_params, &values, i, tweaks[j]).unwrap();
+            assert!(proof.verify_tweaked(&verifier_params, com, &values[i], i, tweaks[j]));
+            assert!(!proof.verify(&verifier_params, com, &values[i], i));
+
+            // cross-tweak verification fails
+            for (k, other) in coms.iter().enumerate() {
+                if k != j {
+                    assert!(!proof.verify_tweaked(&verifier_params, com, &values[i], i, tweaks[k]));
+                    assert!(!proof.verify_tweaked(
+                        &verifier_params,
+                        other,
+                        &values[i],
+                        i,
+                        tweaks[k]
+                    ));
+                }
+            }
+        }
+    }
+
+    // the tweak length is part of the encoding:
+    // ("ab", "c") and ("a", "bc") are different
+    let com_ab = Commitment::new_tweaked(&prover_params, &["c"; 8], b"ab").unwrap();
+    let com_a = Commitment::new_tweaked(&prover_params, &["bc"; 8], b"a").unwrap();
+    assert_ne!(com_ab, com_a);
+}
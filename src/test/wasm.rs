@@ -0,0 +1,60 @@
+use pairing_plus::serdes::SerDes;
+use pairings::param::paramgen_from_seed;
+use pairings::wasm::*;
+use pairings::*;
+
+#[test]
+fn test_wasm_verify() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        values.push(s);
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 3).unwrap();
+
+    let mut vp_bytes: Vec<u8> = vec![];
+    verifier_params.serialize(&mut vp_bytes, true).unwrap();
+    let mut com_bytes: Vec<u8> = vec![];
+    com.serialize(&mut com_bytes, true).unwrap();
+    let mut proof_bytes: Vec<u8> = vec![];
+    proof.serialize(&mut proof_bytes, true).unwrap();
+
+    let vp = WasmVerifierParams::from_bytes(&vp_bytes).unwrap();
+    assert_eq!(vp.n(), n);
+    let wasm_com = WasmCommitment::from_bytes(&com_bytes).unwrap();
+    let wasm_proof = WasmProof::from_bytes(&proof_bytes).unwrap();
+    assert!(wasm_proof.verify(&vp, &wasm_com, values[3].as_ref(), 3));
+    assert!(!wasm_proof.verify(&vp, &wasm_com, values[3].as_ref(), 2));
+    assert!(!wasm_proof.verify(&vp, &wasm_com, values[2].as_ref(), 3));
+
+    assert!(verify_bytes(
+        &vp,
+        &com_bytes,
+        &proof_bytes,
+        values[3].as_ref(),
+        3
+    ));
+    assert!(!verify_bytes(
+        &vp,
+        &com_bytes[1..],
+        &proof_bytes,
+        values[3].as_ref(),
+        3
+    ));
+    assert!(!verify_bytes(
+        &vp,
+        &com_bytes,
+        &proof_bytes[..proof_bytes.len() - 1],
+        values[3].as_ref(),
+        3
+    ));
+
+    assert!(WasmVerifierParams::from_bytes(&vp_bytes[..vp_bytes.len() / 2]).is_none());
+    assert!(WasmCommitment::from_bytes(&[]).is_none());
+    assert!(WasmProof::from_bytes(&[]).is_none());
+}
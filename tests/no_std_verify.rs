@@ -0,0 +1,115 @@
+// this file is part of the pointproofs.
+// it verifies a proof with the parts of the crate that are built without
+// the `std` feature; run it with
+//      cargo test --no-default-features --test no_std_verify
+// the parameters, the commitment and the proof are computed here from a
+// fixed alpha, since paramgen and Proof::new need std
+
+extern crate ff_zeroize as ff;
+extern crate pairing_plus as pairing;
+extern crate pointproofs;
+
+use ff::{Field, PrimeField};
+use pairing::bls12_381::{Bls12, Fr, G1Affine, G2Affine};
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pointproofs::pairings::pointproofs_groups::*;
+use pointproofs::pairings::*;
+
+const N: usize = 8;
+
+/// The bytes of the verifier parameters, the commitment to the values,
+/// and the proof for values[index], in the format of `serialize`,
+/// all computed from the same alpha.
+fn precomputed(values: &[String], index: usize) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+    let alpha = Fr::from_str("1234567890123456789012345678901234567890").unwrap();
+    let alpha_power = |i: usize| alpha.pow([i as u64]);
+
+    // |ciphersuite id | n | g2^{alpha^i} for i in [1, n] | pp_len = 0 | e(g1, g2)^{alpha^{n+1}} |
+    let mut vp = vec![0u8];
+    vp.extend_from_slice(&(N as u32).to_le_bytes());
+    for i in 1..=N {
+        let generator = PointproofsG2Affine::one().mul(alpha_power(i)).into_affine();
+        vp.extend_from_slice(generator.into_compressed().as_ref());
+    }
+    vp.extend_from_slice(&0u32.to_le_bytes());
+    let gt = Bls12::pairing(G1Affine::one(), G2Affine::one()).pow(alpha_power(N + 1).into_repr());
+    gt.serialize(&mut vp, true).unwrap();
+
+    let hashes: Vec<Fr> = values
+        .iter()
+        .map(|v| hash_to_field_with_ciphersuite(0, v).unwrap())
+        .collect();
+
+    // com = g1^{\sum_i alpha^{i+1} m_i}
+    let mut com = Fr::zero();
+    for (i, m) in hashes.iter().enumerate() {
+        let mut t = alpha_power(i + 1);
+        t.mul_assign(m);
+        com.add_assign(&t);
+    }
+    // proof = com^{alpha^{n-index}} / g1^{alpha^{n+1} m_index}
+    let mut proof = com;
+    proof.mul_assign(&alpha_power(N - index));
+    let mut t = alpha_power(N + 1);
+    t.mul_assign(&hashes[index]);
+    proof.sub_assign(&t);
+
+    let point = |e: Fr| {
+        let mut buf = vec![0u8];
+        buf.extend_from_slice(
+            PointproofsG1Affine::one()
+                .mul(e)
+                .into_affine()
+                .into_compressed()
+                .as_ref(),
+        );
+        buf
+    };
+    (vp, point(com), point(proof))
+}
+
+#[test]
+fn test_verify_precomputed_proof() {
+    let values: Vec<String> = (0..N)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let index = 3;
+    let (vp_bytes, com_bytes, proof_bytes) = precomputed(&values, index);
+
+    let verifier_params = VerifierParams::from_slice(&vp_bytes).unwrap();
+    let com = Commitment::from_slice(&com_bytes).unwrap();
+    let proof = Proof::from_slice(&proof_bytes).unwrap();
+    assert!(proof.verify(&verifier_params, &com, &values[index], index));
+    assert!(!proof.verify(&verifier_params, &com, &values[index + 1], index));
+    assert!(!proof.verify(&verifier_params, &com, &values[index], index + 1));
+
+    // each parser takes exactly one blob
+    assert!(VerifierParams::from_slice(&vp_bytes[..vp_bytes.len() - 1]).is_err());
+    let mut long = com_bytes.clone();
+    long.push(0);
+    assert!(Commitment::from_slice(&long).is_err());
+    assert!(Proof::from_slice(&proof_bytes[..proof_bytes.len() - 1]).is_err());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_from_slice_matches_deserialize() {
+    let values: Vec<String> = (0..N)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let (vp_bytes, com_bytes, proof_bytes) = precomputed(&values, 3);
+
+    assert_eq!(
+        VerifierParams::from_slice(&vp_bytes).unwrap(),
+        VerifierParams::deserialize(&mut vp_bytes[..].as_ref(), true).unwrap()
+    );
+    assert_eq!(
+        Commitment::from_slice(&com_bytes).unwrap(),
+        Commitment::deserialize(&mut com_bytes[..].as_ref(), true).unwrap()
+    );
+    assert_eq!(
+        Proof::from_slice(&proof_bytes).unwrap(),
+        Proof::deserialize(&mut proof_bytes[..].as_ref(), true).unwrap()
+    );
+}